@@ -36,6 +36,7 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, CapturedPlace, ParamEnv, RegionVid, TyCtxt};
 use rustc_session::lint::builtin::UNUSED_MUT;
+use rustc_session::ItemTimingKind;
 use rustc_span::{Span, Symbol};
 
 use either::Either;
@@ -116,11 +117,15 @@ pub fn provide(providers: &mut Providers) {
             if let Some(def) = ty::WithOptConstParam::try_lookup(did, tcx) {
                 tcx.mir_borrowck_const_arg(def)
             } else {
-                mir_borrowck(tcx, ty::WithOptConstParam::unknown(did))
+                tcx.sess.time_item(ItemTimingKind::MirBorrowck, did, || {
+                    mir_borrowck(tcx, ty::WithOptConstParam::unknown(did))
+                })
             }
         },
         mir_borrowck_const_arg: |tcx, (did, param_did)| {
-            mir_borrowck(tcx, ty::WithOptConstParam { did, const_param_did: Some(param_did) })
+            tcx.sess.time_item(ItemTimingKind::MirBorrowck, did, || {
+                mir_borrowck(tcx, ty::WithOptConstParam { did, const_param_did: Some(param_did) })
+            })
         },
         ..*providers
     };
@@ -27,6 +27,8 @@ pub(crate) fn build_sysroot(
 
     let is_native = bootstrap_host_compiler.triple == target_triple;
 
+    let panic_strategy = get_panic_strategy(&bootstrap_host_compiler.triple, &target_triple);
+
     // Copy the backend
     let cg_clif_dylib_path = if cfg!(windows) {
         // Windows doesn't have rpath support, so the cg_clif dylib needs to be next to the
@@ -48,6 +50,7 @@ pub(crate) fn build_sysroot(
         let mut build_cargo_wrapper_cmd = Command::new(&bootstrap_host_compiler.rustc);
         build_cargo_wrapper_cmd
             .env("TOOLCHAIN_NAME", toolchain_name.clone())
+            .env("PANIC_STRATEGY", panic_strategy)
             .arg(RelPath::SCRIPTS.to_path(dirs).join(&format!("{wrapper}.rs")))
             .arg("-o")
             .arg(DIST_DIR.to_path(dirs).join(wrapper_name))
@@ -61,6 +64,7 @@ pub(crate) fn build_sysroot(
         bootstrap_host_compiler.clone(),
         &cg_clif_dylib_path,
         sysroot_kind,
+        panic_strategy,
     );
     host.install_into_sysroot(&DIST_DIR.to_path(dirs));
 
@@ -76,6 +80,7 @@ pub(crate) fn build_sysroot(
             },
             &cg_clif_dylib_path,
             sysroot_kind,
+            panic_strategy,
         )
         .install_into_sysroot(&DIST_DIR.to_path(dirs));
     }
@@ -112,6 +117,23 @@ pub(crate) fn build_sysroot(
     target_compiler
 }
 
+/// Returns the panic strategy to build the sysroot with, which the rustc and cargo wrappers pass
+/// to rustc too.
+fn get_panic_strategy(host_triple: &str, target_triple: &str) -> &'static str {
+    if !super::config::get_bool("panic_unwind") {
+        return "abort";
+    }
+
+    // Landing pads are only implemented for x86_64 Linux.
+    for triple in [host_triple, target_triple] {
+        if triple != "x86_64-unknown-linux-gnu" {
+            eprintln!("`panic_unwind` is not supported for {}", triple);
+            process::exit(1);
+        }
+    }
+    "unwind"
+}
+
 struct SysrootTarget {
     triple: String,
     libs: Vec<PathBuf>,
@@ -147,14 +169,19 @@ fn build_sysroot_for_triple(
     compiler: Compiler,
     cg_clif_dylib_path: &Path,
     sysroot_kind: SysrootKind,
+    panic_strategy: &str,
 ) -> SysrootTarget {
     match sysroot_kind {
         SysrootKind::None => build_rtstartup(dirs, &compiler)
             .unwrap_or(SysrootTarget { triple: compiler.triple, libs: vec![] }),
         SysrootKind::Llvm => build_llvm_sysroot_for_triple(compiler),
-        SysrootKind::Clif => {
-            build_clif_sysroot_for_triple(dirs, channel, compiler, &cg_clif_dylib_path)
-        }
+        SysrootKind::Clif => build_clif_sysroot_for_triple(
+            dirs,
+            channel,
+            compiler,
+            &cg_clif_dylib_path,
+            panic_strategy,
+        ),
     }
 }
 
@@ -198,6 +225,7 @@ fn build_clif_sysroot_for_triple(
     channel: &str,
     mut compiler: Compiler,
     cg_clif_dylib_path: &Path,
+    panic_strategy: &str,
 ) -> SysrootTarget {
     match fs::read_to_string(SYSROOT_RUSTC_VERSION.to_path(dirs)) {
         Err(e) => {
@@ -234,7 +262,7 @@ fn build_clif_sysroot_for_triple(
     }
 
     // Build sysroot
-    let mut rustflags = " -Zforce-unstable-if-unmarked -Cpanic=abort".to_string();
+    let mut rustflags = format!(" -Zforce-unstable-if-unmarked -Cpanic={}", panic_strategy);
     rustflags.push_str(&format!(" -Zcodegen-backend={}", cg_clif_dylib_path.to_str().unwrap()));
     // Necessary for MinGW to find rsbegin.o and rsend.o
     rustflags
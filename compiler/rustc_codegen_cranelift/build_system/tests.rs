@@ -92,6 +92,58 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
     TestCase::build_bin_and_run("aot.float-minmax-pass", "example/float-minmax-pass.rs", &[]),
     TestCase::build_bin_and_run("aot.mod_bench", "example/mod_bench.rs", &[]),
     TestCase::build_bin_and_run("aot.issue-72793", "example/issue-72793.rs", &[]),
+    TestCase::custom("aot.should_panic", &|runner| {
+        runner.run_rustc(["example/should_panic.rs", "--test", "-Zpanic-abort-tests"]);
+        runner.run_out_command("should_panic", &[]);
+    }),
+    TestCase::custom("aot.panic_unwind", &|runner| {
+        // `panic_unwind` is only accepted for x86_64 Linux, see `build_sysroot::build_sysroot`.
+        if !config::get_bool("panic_unwind") {
+            eprintln!("[AOT] panic_unwind: the sysroot is built with -Cpanic=abort, skipping");
+            return;
+        }
+
+        runner.run_rustc(["example/panic_unwind.rs", "-Cpanic=unwind"]);
+        runner.run_out_command("panic_unwind", &[]);
+
+        // Without `-Zpanic-abort-tests` the test harness catches the panics of the tests.
+        runner.run_rustc(["example/should_panic.rs", "--test", "-Cpanic=unwind"]);
+        runner.run_out_command("should_panic", &[]);
+    }),
+    TestCase::custom("aot.debuginfo", &|runner| {
+        runner.run_rustc(["example/debuginfo.rs"]);
+        runner.run_out_command("debuginfo", &[]);
+
+        if !runner.is_native
+            || !runner.target_compiler.triple.contains("linux")
+            || Command::new("gdb").arg("--version").output().is_err()
+        {
+            eprintln!("[AOT] debuginfo: gdb not available, skipping the variable checks");
+            return;
+        }
+
+        let mut gdb_cmd = Command::new("gdb");
+        gdb_cmd.args(["-batch", "-nx"]);
+        // The line marked with "break here" in the example.
+        gdb_cmd.args(["-ex", "break debuginfo.rs:19", "-ex", "run"]);
+        gdb_cmd.args(["-ex", "info args", "-ex", "info locals"]);
+        gdb_cmd.arg(BUILD_EXAMPLE_OUT_DIR.to_path(&runner.dirs).join("debuginfo"));
+        let output = gdb_cmd.output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+
+        for expected in [
+            "count = 21",
+            "point = debuginfo::Point {x: 1, y: 2, z: 3}",
+            "doubled = 42",
+            "moved = debuginfo::Point {x: 2, y: 2, z: 3}",
+            "total = 44",
+        ] {
+            if !stdout.lines().any(|line| line == expected) {
+                eprintln!("Expected `{expected}` in the gdb output:\n{stdout}");
+                std::process::exit(1);
+            }
+        }
+    }),
 ];
 
 pub(crate) static RAND_REPO: GitRepo =
@@ -0,0 +1,26 @@
+// The `aot.debuginfo` test stops at the line marked below in gdb and checks the arguments and
+// locals it prints. Keep the line number in `build_system/tests.rs` in sync.
+
+use std::hint::black_box;
+
+// Not a scalar pair, so it is stored in memory.
+#[derive(Clone, Copy)]
+struct Point {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+#[inline(never)]
+fn inspect(count: u32, point: Point) -> i64 {
+    let doubled = count * 2;
+    let moved = Point { x: point.x + 1, ..point };
+    let total = i64::from(doubled) + i64::from(moved.y);
+    black_box(()); // break here
+    black_box((doubled, moved, point));
+    total
+}
+
+fn main() {
+    assert_eq!(inspect(21, black_box(Point { x: 1, y: 2, z: 3 })), 44);
+}
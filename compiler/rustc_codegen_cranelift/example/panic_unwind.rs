@@ -0,0 +1,89 @@
+// Built with `-Cpanic=unwind`. Checks that panics are caught by `catch_unwind` and that the
+// destructors of all frames between the panic and `catch_unwind` run while unwinding.
+
+use std::cell::Cell;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+
+struct DropGuard<'a>(&'a Cell<usize>);
+
+impl Drop for DropGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+struct PanicOnDrop;
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("panic in drop");
+    }
+}
+
+#[inline(never)]
+fn panic_with_guards(drops: &Cell<usize>) {
+    let _outer = DropGuard(drops);
+    let _inner = DropGuard(drops);
+    panic!("panic with guards");
+}
+
+#[inline(never)]
+fn return_values(x: u8) -> (u64, u128, f64) {
+    (u64::from(x), u128::MAX - u128::from(x), f64::from(x) / 2.0)
+}
+
+fn payload_str(payload: &Box<dyn std::any::Any + Send>) -> &str {
+    payload.downcast_ref::<&str>().copied().unwrap()
+}
+
+fn main() {
+    panic::set_hook(Box::new(|_| {}));
+
+    // The guards of the panicking function and of the closure are dropped.
+    let drops = Cell::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = DropGuard(&drops);
+        panic_with_guards(&drops);
+    }));
+    assert_eq!(payload_str(&result.unwrap_err()), "panic with guards");
+    assert_eq!(drops.get(), 3);
+
+    // A panic caught by an inner `catch_unwind` doesn't reach the outer one until it is resumed.
+    let drops = Cell::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = DropGuard(&drops);
+        let inner = panic::catch_unwind(AssertUnwindSafe(|| panic_with_guards(&drops)));
+        assert_eq!(drops.get(), 2);
+        panic::resume_unwind(inner.unwrap_err());
+    }));
+    assert_eq!(payload_str(&result.unwrap_err()), "panic with guards");
+    assert_eq!(drops.get(), 3);
+
+    // Panics of failed bounds checks unwind too.
+    let drops = Cell::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = DropGuard(&drops);
+        [1, 2, 3][black_box(3)]
+    }));
+    assert!(result.is_err());
+    assert_eq!(drops.get(), 1);
+
+    // When a destructor panics, the remaining fields are still dropped.
+    let drops = Cell::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _pair = (PanicOnDrop, DropGuard(&drops));
+    }));
+    assert_eq!(payload_str(&result.unwrap_err()), "panic in drop");
+    assert_eq!(drops.get(), 1);
+
+    // Calls which could unwind into a cleanup block still return their values.
+    let drops = Cell::new(0);
+    {
+        let _guard = DropGuard(&drops);
+        assert_eq!(return_values(black_box(3)), (3, u128::MAX - 3, 1.5));
+        let s = black_box(String::from("a")) + "b";
+        assert_eq!(s, "ab");
+    }
+    assert_eq!(drops.get(), 1);
+}
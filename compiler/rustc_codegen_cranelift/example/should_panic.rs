@@ -0,0 +1,40 @@
+// Built with `--test -Zpanic-abort-tests`, where each test runs in its own process, and with
+// `-Cpanic=unwind` when the sysroot supports it, where the test harness catches the panics.
+
+use std::cell::Cell;
+use std::hint::black_box;
+use std::panic::{self, AssertUnwindSafe};
+
+struct DropGuard<'a>(&'a Cell<usize>);
+
+impl Drop for DropGuard<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+#[test]
+fn catch_unwind_without_panic() {
+    let drops = Cell::new(0);
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let _guard = DropGuard(&drops);
+        42
+    }));
+    assert_eq!(result.ok(), Some(42));
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+#[should_panic(expected = "panic with a live guard")]
+fn should_panic_with_live_guard() {
+    let drops = Cell::new(0);
+    let _guard = DropGuard(&drops);
+    panic!("panic with a live guard");
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn should_panic_in_bounds_check() {
+    let values = vec![1, 2, 3];
+    let _ = values[black_box(3)];
+}
@@ -8,7 +8,11 @@ fn main() {
     let sysroot = PathBuf::from(env::current_exe().unwrap().parent().unwrap());
 
     let mut rustflags = String::new();
-    rustflags.push_str(" -Cpanic=abort -Zpanic-abort-tests -Zcodegen-backend=");
+    rustflags.push_str(concat!(" -Cpanic=", env!("PANIC_STRATEGY")));
+    if env!("PANIC_STRATEGY") == "abort" {
+        rustflags.push_str(" -Zpanic-abort-tests");
+    }
+    rustflags.push_str(" -Zcodegen-backend=");
     rustflags.push_str(
         sysroot
             .join(if cfg!(windows) { "bin" } else { "lib" })
@@ -12,6 +12,7 @@
 use rustc_target::spec::abi::Abi;
 
 use cranelift_codegen::ir::{AbiParam, SigRef};
+use smallvec::SmallVec;
 
 use self::pass_mode::*;
 use crate::prelude::*;
@@ -183,7 +184,8 @@ fn make_local_place<'tcx>(
             CPlace::new_var(fx, local, layout)
         }
     } else {
-        CPlace::new_stack_slot(fx, layout)
+        let place = CPlace::new_stack_slot(fx, layout);
+        crate::debuginfo::label_local_place(fx, local, place)
     };
 
     self::comments::add_local_place_comments(fx, place, local);
@@ -292,6 +294,7 @@ enum ArgKind<'tcx> {
                 } else {
                     CPlace::for_ptr(addr, val.layout())
                 };
+                let place = crate::debuginfo::label_local_place(fx, local, place);
 
                 self::comments::add_local_place_comments(fx, place, local);
 
@@ -355,6 +358,7 @@ pub(crate) fn codegen_terminator_call<'tcx>(
     args: &[Operand<'tcx>],
     destination: Place<'tcx>,
     target: Option<BasicBlock>,
+    cleanup: Option<BasicBlock>,
 ) {
     let func = codegen_operand(fx, func);
     let fn_sig = func.layout().ty.fn_sig(fx.tcx);
@@ -463,11 +467,6 @@ pub(crate) fn codegen_terminator_call<'tcx>(
     let args = args;
     assert_eq!(fn_abi.args.len(), args.len());
 
-    enum CallTarget {
-        Direct(FuncRef),
-        Indirect(SigRef, Value),
-    }
-
     let (func_ref, first_arg_override) = match instance {
         // Trait object call
         Some(Instance { def: InstanceDef::Virtual(_, idx), .. }) => {
@@ -522,13 +521,6 @@ enum CallTarget {
             )
             .collect::<Vec<Value>>();
 
-        let call_inst = match func_ref {
-            CallTarget::Direct(func_ref) => fx.bcx.ins().call(func_ref, &call_args),
-            CallTarget::Indirect(sig, func_ptr) => {
-                fx.bcx.ins().call_indirect(sig, func_ptr, &call_args)
-            }
-        };
-
         // FIXME find a cleaner way to support varargs
         if fn_sig.c_variadic() {
             if !matches!(fn_sig.abi(), Abi::C { .. }) {
@@ -537,10 +529,13 @@ enum CallTarget {
                     &format!("Variadic call for non-C abi {:?}", fn_sig.abi()),
                 );
             }
-            let sig_ref = fx.bcx.func.dfg.call_signature(call_inst).unwrap();
+            let sig_ref = match func_ref {
+                CallTarget::Direct(func_ref) => fx.bcx.func.dfg.ext_funcs[func_ref].signature,
+                CallTarget::Indirect(sig_ref, _) => sig_ref,
+            };
             let abi_params = call_args
-                .into_iter()
-                .map(|arg| {
+                .iter()
+                .map(|&arg| {
                     let ty = fx.bcx.func.dfg.value_type(arg);
                     if !ty.is_int() {
                         // FIXME set %al to upperbound on float args once floats are supported
@@ -555,7 +550,7 @@ enum CallTarget {
             fx.bcx.func.dfg.signatures[sig_ref].params = abi_params;
         }
 
-        call_inst
+        codegen_call_with_unwind_action(fx, func_ref, &call_args, cleanup)
     });
 
     if let Some(dest) = target {
@@ -570,6 +565,7 @@ pub(crate) fn codegen_drop<'tcx>(
     fx: &mut FunctionCx<'_, '_, 'tcx>,
     source_info: mir::SourceInfo,
     drop_place: CPlace<'tcx>,
+    cleanup: Option<BasicBlock>,
 ) {
     let ty = drop_place.layout().ty;
     let drop_instance = Instance::resolve_drop_in_place(fx.tcx, ty).polymorphize(fx.tcx);
@@ -606,7 +602,12 @@ pub(crate) fn codegen_drop<'tcx>(
 
                 let sig = clif_sig_from_fn_abi(fx.tcx, fx.target_config.default_call_conv, &fn_abi);
                 let sig = fx.bcx.import_signature(sig);
-                fx.bcx.ins().call_indirect(sig, drop_fn, &[ptr]);
+                codegen_call_with_unwind_action(
+                    fx,
+                    CallTarget::Indirect(sig, drop_fn),
+                    &[ptr],
+                    cleanup,
+                );
             }
             ty::Dynamic(_, _, ty::DynStar) => {
                 // IN THIS ARM, WE HAVE:
@@ -643,7 +644,12 @@ pub(crate) fn codegen_drop<'tcx>(
 
                 let sig = clif_sig_from_fn_abi(fx.tcx, fx.target_config.default_call_conv, &fn_abi);
                 let sig = fx.bcx.import_signature(sig);
-                fx.bcx.ins().call_indirect(sig, drop_fn, &[data]);
+                codegen_call_with_unwind_action(
+                    fx,
+                    CallTarget::Indirect(sig, drop_fn),
+                    &[data],
+                    cleanup,
+                );
             }
             _ => {
                 assert!(!matches!(drop_instance.def, InstanceDef::Virtual(_, _)));
@@ -671,8 +677,51 @@ pub(crate) fn codegen_drop<'tcx>(
                 }
 
                 let func_ref = fx.get_function_ref(drop_instance);
-                fx.bcx.ins().call(func_ref, &call_args);
+                codegen_call_with_unwind_action(
+                    fx,
+                    CallTarget::Direct(func_ref),
+                    &call_args,
+                    cleanup,
+                );
             }
         }
     }
 }
+
+pub(crate) enum CallTarget {
+    Direct(FuncRef),
+    Indirect(SigRef, Value),
+}
+
+/// Calls `func_ref` and returns its return values. If the callee unwinds and landing pads are
+/// supported, execution continues at the `cleanup` block. Otherwise unwinding continues in the
+/// caller without running any cleanup.
+pub(crate) fn codegen_call_with_unwind_action(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    func_ref: CallTarget,
+    call_args: &[Value],
+    cleanup: Option<BasicBlock>,
+) -> SmallVec<[Value; 2]> {
+    match cleanup {
+        Some(cleanup) if fx.cx.supports_landing_pads => {
+            let (sig_ref, func_ptr) = match func_ref {
+                CallTarget::Direct(func_ref) => (
+                    fx.bcx.func.dfg.ext_funcs[func_ref].signature,
+                    fx.bcx.ins().func_addr(fx.pointer_type, func_ref),
+                ),
+                CallTarget::Indirect(sig_ref, func_ptr) => (sig_ref, func_ptr),
+            };
+            let sig = fx.bcx.func.dfg.signatures[sig_ref].clone();
+            crate::landing_pads::codegen_invoke(fx, func_ptr, sig, call_args, cleanup)
+        }
+        _ => {
+            let call_inst = match func_ref {
+                CallTarget::Direct(func_ref) => fx.bcx.ins().call(func_ref, call_args),
+                CallTarget::Indirect(sig, func_ptr) => {
+                    fx.bcx.ins().call_indirect(sig, func_ptr, call_args)
+                }
+            };
+            fx.bcx.inst_results(call_inst).iter().copied().collect()
+        }
+    }
+}
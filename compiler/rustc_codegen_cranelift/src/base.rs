@@ -9,7 +9,7 @@
 use cranelift_codegen::ir::UserFuncName;
 
 use crate::constant::ConstantCx;
-use crate::debuginfo::FunctionDebugContext;
+use crate::debuginfo::{FunctionDebugContext, TypeDebugContext};
 use crate::prelude::*;
 use crate::pretty_clif::CommentWriter;
 
@@ -24,6 +24,7 @@ pub(crate) struct CodegenedFunction {
 pub(crate) fn codegen_fn<'tcx>(
     tcx: TyCtxt<'tcx>,
     cx: &mut crate::CodegenCx,
+    type_dbg: &mut TypeDebugContext<'tcx>,
     cached_func: Function,
     module: &mut dyn Module,
     instance: Instance<'tcx>,
@@ -53,7 +54,10 @@ pub(crate) fn codegen_fn<'tcx>(
     func.clear();
     func.name = UserFuncName::user(0, func_id.as_u32());
     func.signature = sig;
-    func.collect_debug_info();
+    if cx.debug_context.is_some() {
+        // Track which values belong to which variables to emit their locations in the debuginfo.
+        func.collect_debug_info();
+    }
 
     let mut bcx = FunctionBuilder::new(&mut func, &mut func_ctx);
 
@@ -91,6 +95,7 @@ pub(crate) fn codegen_fn<'tcx>(
         block_map,
         local_map: IndexVec::with_capacity(mir.local_decls.len()),
         caller_location: None, // set by `codegen_fn_prelude`
+        exception_var: None,   // set by `landing_pads::codegen_invoke`
 
         clif_comments,
         last_source_file: None,
@@ -98,6 +103,7 @@ pub(crate) fn codegen_fn<'tcx>(
     };
 
     tcx.prof.generic_activity("codegen clif ir").run(|| codegen_fn_body(&mut fx, start_block));
+    crate::debuginfo::define_variables(&mut fx, type_dbg);
     fx.bcx.seal_all_blocks();
     fx.bcx.finalize();
 
@@ -235,6 +241,7 @@ pub(crate) fn compile_fn(
                 debug_context,
                 codegened_func.func_id,
                 context,
+                isa,
             );
         }
         unwind_context.add_function(codegened_func.func_id, &context, isa);
@@ -292,11 +299,12 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
         fx.bcx.switch_to_block(block);
 
         if bb_data.is_cleanup {
-            // Unwinding after panicking is not supported
-            continue;
+            if !fx.cx.supports_landing_pads {
+                // Cleanup blocks are only reached from landing pads.
+                continue;
+            }
 
-            // FIXME Once unwinding is supported and Cranelift supports marking blocks as cold, do
-            // so for cleanup blocks.
+            fx.bcx.set_cold_block(block);
         }
 
         fx.bcx.ins().nop();
@@ -345,7 +353,7 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
             TerminatorKind::Return => {
                 crate::abi::codegen_return(fx);
             }
-            TerminatorKind::Assert { cond, expected, msg, target, cleanup: _ } => {
+            TerminatorKind::Assert { cond, expected, msg, target, cleanup } => {
                 if !fx.tcx.sess.overflow_checks() {
                     if let mir::AssertKind::OverflowNeg(_) = *msg {
                         let target = fx.get_block(*target);
@@ -380,11 +388,12 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
                             rustc_hir::LangItem::PanicBoundsCheck,
                             &[index, len, location],
                             source_info.span,
+                            *cleanup,
                         );
                     }
                     _ => {
                         let msg_str = msg.description();
-                        codegen_panic(fx, msg_str, source_info);
+                        codegen_panic(fx, msg_str, source_info, *cleanup);
                     }
                 }
             }
@@ -443,7 +452,7 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
                 destination,
                 target,
                 fn_span,
-                cleanup: _,
+                cleanup,
                 from_hir_call: _,
             } => {
                 fx.tcx.prof.generic_activity("codegen call").run(|| {
@@ -454,6 +463,7 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
                         args,
                         *destination,
                         *target,
+                        *cleanup,
                     )
                 });
             }
@@ -485,8 +495,11 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
                 codegen_panic_cannot_unwind(fx, source_info);
             }
             TerminatorKind::Resume => {
-                // FIXME implement unwinding
-                fx.bcx.ins().trap(TrapCode::UnreachableCodeReached);
+                if fx.cx.supports_landing_pads {
+                    crate::landing_pads::codegen_resume(fx);
+                } else {
+                    fx.bcx.ins().trap(TrapCode::UnreachableCodeReached);
+                }
             }
             TerminatorKind::Unreachable => {
                 fx.bcx.ins().trap(TrapCode::UnreachableCodeReached);
@@ -498,9 +511,9 @@ fn codegen_fn_body(fx: &mut FunctionCx<'_, '_, '_>, start_block: Block) {
             | TerminatorKind::GeneratorDrop => {
                 bug!("shouldn't exist at codegen {:?}", bb_data.terminator());
             }
-            TerminatorKind::Drop { place, target, unwind: _ } => {
+            TerminatorKind::Drop { place, target, unwind } => {
                 let drop_place = codegen_place(fx, *place);
-                crate::abi::codegen_drop(fx, source_info, drop_place);
+                crate::abi::codegen_drop(fx, source_info, drop_place, *unwind);
 
                 let target_block = fx.get_block(*target);
                 fx.bcx.ins().jump(target_block, &[]);
@@ -953,6 +966,7 @@ pub(crate) fn codegen_panic<'tcx>(
     fx: &mut FunctionCx<'_, '_, 'tcx>,
     msg_str: &str,
     source_info: mir::SourceInfo,
+    cleanup: Option<BasicBlock>,
 ) {
     let location = fx.get_caller_location(source_info).load_scalar(fx);
 
@@ -960,7 +974,7 @@ pub(crate) fn codegen_panic<'tcx>(
     let msg_len = fx.bcx.ins().iconst(fx.pointer_type, i64::try_from(msg_str.len()).unwrap());
     let args = [msg_ptr, msg_len, location];
 
-    codegen_panic_inner(fx, rustc_hir::LangItem::Panic, &args, source_info.span);
+    codegen_panic_inner(fx, rustc_hir::LangItem::Panic, &args, source_info.span, cleanup);
 }
 
 pub(crate) fn codegen_panic_nounwind<'tcx>(
@@ -972,7 +986,7 @@ pub(crate) fn codegen_panic_nounwind<'tcx>(
     let msg_len = fx.bcx.ins().iconst(fx.pointer_type, i64::try_from(msg_str.len()).unwrap());
     let args = [msg_ptr, msg_len];
 
-    codegen_panic_inner(fx, rustc_hir::LangItem::PanicNounwind, &args, source_info.span);
+    codegen_panic_inner(fx, rustc_hir::LangItem::PanicNounwind, &args, source_info.span, None);
 }
 
 pub(crate) fn codegen_panic_cannot_unwind<'tcx>(
@@ -981,7 +995,7 @@ pub(crate) fn codegen_panic_cannot_unwind<'tcx>(
 ) {
     let args = [];
 
-    codegen_panic_inner(fx, rustc_hir::LangItem::PanicCannotUnwind, &args, source_info.span);
+    codegen_panic_inner(fx, rustc_hir::LangItem::PanicCannotUnwind, &args, source_info.span, None);
 }
 
 fn codegen_panic_inner<'tcx>(
@@ -989,6 +1003,7 @@ fn codegen_panic_inner<'tcx>(
     lang_item: rustc_hir::LangItem,
     args: &[Value],
     span: Span,
+    cleanup: Option<BasicBlock>,
 ) {
     let def_id = fx
         .tcx
@@ -999,12 +1014,17 @@ fn codegen_panic_inner<'tcx>(
     let instance = Instance::mono(fx.tcx, def_id).polymorphize(fx.tcx);
     let symbol_name = fx.tcx.symbol_name(instance).name;
 
-    fx.lib_call(
-        &*symbol_name,
-        args.iter().map(|&arg| AbiParam::new(fx.bcx.func.dfg.value_type(arg))).collect(),
-        vec![],
-        args,
-    );
+    let sig = Signature {
+        params: args.iter().map(|&arg| AbiParam::new(fx.bcx.func.dfg.value_type(arg))).collect(),
+        returns: vec![],
+        call_conv: fx.target_config.default_call_conv,
+    };
+    let func_id = fx.module.declare_function(symbol_name, Linkage::Import, &sig).unwrap();
+    let func_ref = fx.module.declare_func_in_func(func_id, &mut fx.bcx.func);
+    if fx.clif_comments.enabled() {
+        fx.add_comment(func_ref, format!("{:?}", symbol_name));
+    }
+    crate::abi::codegen_call_with_unwind_action(fx, CallTarget::Direct(func_ref), args, cleanup);
 
     fx.bcx.ins().trap(TrapCode::UnreachableCodeReached);
 }
@@ -313,6 +313,10 @@ pub(crate) struct FunctionCx<'m, 'clif, 'tcx: 'm> {
     /// When `#[track_caller]` is used, the implicit caller location is stored in this variable.
     pub(crate) caller_location: Option<CValue<'tcx>>,
 
+    /// The exception which is being unwound while running cleanup blocks. It is set by the
+    /// landing pads of calls with a cleanup block, see `landing_pads::codegen_invoke`.
+    pub(crate) exception_var: Option<Variable>,
+
     pub(crate) clif_comments: crate::pretty_clif::CommentWriter,
 
     /// Last accessed source file and it's debuginfo file id.
@@ -320,7 +324,8 @@ pub(crate) struct FunctionCx<'m, 'clif, 'tcx: 'm> {
     /// For optimization purposes only
     pub(crate) last_source_file: Option<(Lrc<SourceFile>, FileId)>,
 
-    /// This should only be accessed by `CPlace::new_var`.
+    /// This should only be accessed by `CPlace::new_var` and `CPlace::new_var_pair`, and by
+    /// `debuginfo::label_local_place` to number the labels of variables stored in memory.
     pub(crate) next_ssa_var: u32,
 }
 
@@ -3,22 +3,28 @@
 mod emit;
 mod line_info;
 mod object;
+mod types;
 mod unwind;
+mod variables;
 
 use crate::prelude::*;
 
-use cranelift_codegen::ir::Endianness;
+use cranelift_codegen::ir::{Endianness, ValueLabel};
 use cranelift_codegen::isa::TargetIsa;
 
 use gimli::write::{
-    Address, AttributeValue, DwarfUnit, FileId, LineProgram, LineString, Range, RangeList,
-    UnitEntryId,
+    Address, AttributeValue, DwarfUnit, Expression, FileId, LineProgram, LineString, Range,
+    RangeList, UnitEntryId,
 };
 use gimli::{Encoding, Format, LineEncoding, RunTimeEndian};
 use indexmap::IndexSet;
 
 pub(crate) use emit::{DebugReloc, DebugRelocName};
+pub(crate) use types::TypeDebugContext;
 pub(crate) use unwind::UnwindContext;
+pub(crate) use variables::{define_variables, label_local_place};
+
+use variables::VariableLocation;
 
 pub(crate) fn producer() -> String {
     format!(
@@ -39,6 +45,9 @@ pub(crate) struct FunctionDebugContext {
     entry_id: UnitEntryId,
     function_source_loc: (FileId, u64, u64),
     source_loc_set: indexmap::IndexSet<(FileId, u64, u64)>,
+    variables: Vec<(UnitEntryId, VariableLocation)>,
+    /// The labels of the addresses of variables stored in memory, see `label_local_place`.
+    local_addr_labels: FxHashMap<Local, ValueLabel>,
 }
 
 impl DebugContext {
@@ -131,10 +140,17 @@ pub(crate) fn define_function(
         entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
         entry.set(gimli::DW_AT_decl_column, AttributeValue::Udata(column));
 
+        // Variable locations relative to the stack frame are relative to the CFA.
+        let mut frame_base = Expression::new();
+        frame_base.op(gimli::DW_OP_call_frame_cfa);
+        entry.set(gimli::DW_AT_frame_base, AttributeValue::Exprloc(frame_base));
+
         FunctionDebugContext {
             entry_id,
             function_source_loc: (file_id, line, column),
             source_loc_set: IndexSet::new(),
+            variables: vec![],
+            local_addr_labels: FxHashMap::default(),
         }
     }
 }
@@ -145,10 +161,12 @@ pub(crate) fn finalize(
         debug_context: &mut DebugContext,
         func_id: FuncId,
         context: &Context,
+        isa: &dyn TargetIsa,
     ) {
         let symbol = func_id.as_u32() as usize;
 
         let end = self.create_debug_lines(debug_context, symbol, context);
+        self.add_variable_locations(debug_context, symbol, context, isa);
 
         debug_context.unit_range_list.0.push(Range::StartLength {
             begin: Address::Symbol { symbol, addend: 0 },
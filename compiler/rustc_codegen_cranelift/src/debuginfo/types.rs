@@ -0,0 +1,186 @@
+//! Type debuginfo (`DW_TAG_base_type`, `DW_TAG_structure_type`, ...) for the variables of functions
+
+use crate::debuginfo::DebugContext;
+use crate::prelude::*;
+
+use rustc_codegen_ssa::debuginfo::type_names::compute_debuginfo_type_name;
+use rustc_target::abi::FieldsShape;
+
+use gimli::write::{AttributeValue, UnitEntryId};
+
+/// The type entries of a [`DebugContext`]. This is kept separate from the `DebugContext` itself as
+/// the latter doesn't have a `'tcx` lifetime.
+#[derive(Default)]
+pub(crate) struct TypeDebugContext<'tcx> {
+    type_map: FxHashMap<Ty<'tcx>, UnitEntryId>,
+}
+
+impl DebugContext {
+    /// Returns the type entry for `ty`, creating it first if it doesn't exist yet.
+    pub(crate) fn debug_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        ty: Ty<'tcx>,
+    ) -> UnitEntryId {
+        if let Some(&type_id) = type_dbg.type_map.get(&ty) {
+            return type_id;
+        }
+
+        let layout = RevealAllLayoutCx(tcx).layout_of(ty);
+        match *ty.kind() {
+            ty::Bool => self.basic_type(tcx, type_dbg, layout, gimli::DW_ATE_boolean),
+            ty::Char => self.basic_type(tcx, type_dbg, layout, gimli::DW_ATE_UTF),
+            ty::Int(_) => self.basic_type(tcx, type_dbg, layout, gimli::DW_ATE_signed),
+            ty::Uint(_) => self.basic_type(tcx, type_dbg, layout, gimli::DW_ATE_unsigned),
+            ty::Float(_) => self.basic_type(tcx, type_dbg, layout, gimli::DW_ATE_float),
+            ty::Ref(_, pointee_ty, _) | ty::RawPtr(TypeAndMut { ty: pointee_ty, .. })
+                if matches!(layout.abi, Abi::Scalar(_)) =>
+            {
+                let type_id =
+                    self.new_type_entry(tcx, type_dbg, layout, gimli::DW_TAG_pointer_type);
+                let pointee_type_id = self.debug_type(tcx, type_dbg, pointee_ty);
+                self.dwarf
+                    .unit
+                    .get_mut(type_id)
+                    .set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee_type_id));
+                type_id
+            }
+            ty::Array(elem_ty, _) => {
+                let type_id = self.new_type_entry(tcx, type_dbg, layout, gimli::DW_TAG_array_type);
+                let elem_type_id = self.debug_type(tcx, type_dbg, elem_ty);
+                self.dwarf
+                    .unit
+                    .get_mut(type_id)
+                    .set(gimli::DW_AT_type, AttributeValue::UnitRef(elem_type_id));
+
+                let subrange_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_subrange_type);
+                self.dwarf.unit.get_mut(subrange_id).set(
+                    gimli::DW_AT_count,
+                    AttributeValue::Udata(u64::try_from(layout.fields.count()).unwrap()),
+                );
+                type_id
+            }
+            ty::Ref(_, pointee_ty, _) | ty::RawPtr(TypeAndMut { ty: pointee_ty, .. }) => {
+                self.fat_pointer_type(tcx, type_dbg, layout, pointee_ty)
+            }
+            ty::Tuple(_) | ty::Closure(..) => self.struct_type(tcx, type_dbg, layout),
+            ty::Adt(adt_def, _) if adt_def.is_struct() || adt_def.is_union() => {
+                self.struct_type(tcx, type_dbg, layout)
+            }
+            // FIXME describe the variants of enums and the upvars of generators
+            _ => self.new_type_entry(tcx, type_dbg, layout, gimli::DW_TAG_structure_type),
+        }
+    }
+
+    /// Creates a type entry for `layout` with the given tag, name and size, but no other attributes
+    /// or children. The entry is registered before returning, so that recursive types can refer
+    /// to it while adding its pointee or fields.
+    fn new_type_entry<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        layout: TyAndLayout<'tcx>,
+        tag: gimli::DwTag,
+    ) -> UnitEntryId {
+        let name = compute_debuginfo_type_name(tcx, layout.ty, true);
+        let name_id = self.dwarf.strings.add(name);
+
+        let root = self.dwarf.unit.root();
+        let type_id = self.dwarf.unit.add(root, tag);
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+        type_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        type_entry.set(gimli::DW_AT_byte_size, AttributeValue::Udata(layout.size.bytes()));
+
+        type_dbg.type_map.insert(layout.ty, type_id);
+        type_id
+    }
+
+    fn basic_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        layout: TyAndLayout<'tcx>,
+        encoding: gimli::DwAte,
+    ) -> UnitEntryId {
+        let type_id = self.new_type_entry(tcx, type_dbg, layout, gimli::DW_TAG_base_type);
+        self.dwarf
+            .unit
+            .get_mut(type_id)
+            .set(gimli::DW_AT_encoding, AttributeValue::Encoding(encoding));
+        type_id
+    }
+
+    fn struct_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        layout: TyAndLayout<'tcx>,
+    ) -> UnitEntryId {
+        let tag = if let FieldsShape::Union(_) = layout.fields {
+            gimli::DW_TAG_union_type
+        } else {
+            gimli::DW_TAG_structure_type
+        };
+        let type_id = self.new_type_entry(tcx, type_dbg, layout, tag);
+
+        for i in 0..layout.fields.count() {
+            let field_layout = layout.field(&RevealAllLayoutCx(tcx), i);
+            let field_type_id = self.debug_type(tcx, type_dbg, field_layout.ty);
+            let offset = layout.fields.offset(i);
+            self.add_member(type_id, &field_name(layout.ty, i), field_type_id, offset);
+        }
+
+        type_id
+    }
+
+    /// Describes a fat pointer as a struct of the data pointer and the metadata, using the same
+    /// field names as the LLVM backend, which the debugger pretty printers depend on.
+    fn fat_pointer_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        layout: TyAndLayout<'tcx>,
+        pointee_ty: Ty<'tcx>,
+    ) -> UnitEntryId {
+        let type_id = self.new_type_entry(tcx, type_dbg, layout, gimli::DW_TAG_structure_type);
+
+        let tail_ty = tcx.struct_tail_erasing_lifetimes(pointee_ty, ParamEnv::reveal_all());
+        let (data_ptr_name, data_ptr_ty, metadata_name) = match *tail_ty.kind() {
+            ty::Slice(elem_ty) => ("data_ptr", tcx.mk_imm_ptr(elem_ty), "length"),
+            ty::Str => ("data_ptr", tcx.mk_imm_ptr(tcx.types.u8), "length"),
+            _ => ("pointer", tcx.mk_imm_ptr(tcx.mk_unit()), "vtable"),
+        };
+        let metadata_ty = layout.field(&RevealAllLayoutCx(tcx), 1).ty;
+
+        let data_ptr_type_id = self.debug_type(tcx, type_dbg, data_ptr_ty);
+        self.add_member(type_id, data_ptr_name, data_ptr_type_id, layout.fields.offset(0));
+        let metadata_type_id = self.debug_type(tcx, type_dbg, metadata_ty);
+        self.add_member(type_id, metadata_name, metadata_type_id, layout.fields.offset(1));
+
+        type_id
+    }
+
+    fn add_member(
+        &mut self,
+        type_id: UnitEntryId,
+        name: &str,
+        member_type_id: UnitEntryId,
+        offset: Size,
+    ) {
+        let name_id = self.dwarf.strings.add(name);
+
+        let member_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_member);
+        let member_entry = self.dwarf.unit.get_mut(member_id);
+        member_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        member_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(member_type_id));
+        member_entry.set(gimli::DW_AT_data_member_location, AttributeValue::Udata(offset.bytes()));
+    }
+}
+
+fn field_name(ty: Ty<'_>, i: usize) -> String {
+    match *ty.kind() {
+        ty::Adt(adt_def, _) => adt_def.non_enum_variant().fields[i].name.to_string(),
+        _ => format!("__{}", i),
+    }
+}
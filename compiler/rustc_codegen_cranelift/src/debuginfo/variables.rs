@@ -0,0 +1,261 @@
+//! Variable debuginfo (`DW_TAG_variable` and `DW_TAG_formal_parameter`) and their locations
+
+use crate::debuginfo::{FunctionDebugContext, TypeDebugContext};
+use crate::pointer::PointerBase;
+use crate::prelude::*;
+
+use cranelift_codegen::ir::ValueLabel;
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::{LabelValueLoc, ValueLabelsRanges, ValueLocRange};
+
+use gimli::write::{Address, AttributeValue, Expression, Location, LocationList};
+use gimli::Register;
+
+/// Where the value of a variable can be found while the function is running.
+#[derive(Copy, Clone, Debug)]
+pub(super) enum VariableLocation {
+    /// The variable is stored in an SSA variable, which is labeled with the given label.
+    Var(ValueLabel),
+    /// The variable is stored in two SSA variables of the given sizes.
+    VarPair(ValueLabel, u64, ValueLabel, u64),
+    /// The variable is stored in memory, at the address labeled with the given label.
+    Addr(ValueLabel),
+}
+
+/// Labels the address of a user variable stored in memory. This has to be called when the local
+/// is allocated, as a label only covers the value from the point where it is set.
+///
+/// Cranelift doesn't say where a stack slot is relative to the CFA, only relative to the stack
+/// pointer, which moves in the prologue and the epilogue. So instead of a stack slot, the local
+/// gets its address computed once. Cranelift tracks the location of that labeled value in a
+/// register or a spill slot relative to the CFA, like that of SSA variables.
+pub(crate) fn label_local_place<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    local: Local,
+    place: CPlace<'tcx>,
+) -> CPlace<'tcx> {
+    if fx.func_debug_cx.is_none() {
+        return place;
+    }
+    let is_variable = fx.mir.var_debug_info.iter().any(|var_debug_info| {
+        matches!(
+            var_debug_info.value,
+            VarDebugInfoContents::Place(place) if place.local == local && place.projection.is_empty()
+        )
+    });
+    if !is_variable {
+        return place;
+    }
+    let CPlaceInner::Addr(ptr, None) = *place.inner() else { return place };
+    if let (PointerBase::Dangling(_), _) = ptr.debug_base_and_offset() {
+        return place;
+    }
+
+    let addr = ptr.get_addr(fx);
+    // Labels share the numbering of SSA variables, whose labels are their index.
+    let label = ValueLabel::from_u32(fx.next_ssa_var);
+    fx.next_ssa_var += 1;
+    fx.bcx.set_val_label(addr, label);
+    fx.func_debug_cx.as_mut().unwrap().local_addr_labels.insert(local, label);
+    CPlace::for_ptr(Pointer::new(addr), place.layout())
+}
+
+/// Defines the user variables of the function being codegened, which must not have any
+/// projections. This has to be called after the body of the function has been codegened, as
+/// that is what determines where the variables are stored.
+pub(crate) fn define_variables<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    type_dbg: &mut TypeDebugContext<'tcx>,
+) {
+    if fx.func_debug_cx.is_none() {
+        return;
+    }
+
+    let mir = fx.mir;
+    let mut variables = vec![];
+    for var_debug_info in &mir.var_debug_info {
+        // FIXME describe constants and variables stored in a field of another local, like the
+        // captured variables of closures
+        let VarDebugInfoContents::Place(place) = var_debug_info.value else { continue };
+        if !place.projection.is_empty() {
+            continue;
+        }
+
+        let ty = fx.monomorphize(mir.local_decls[place.local].ty);
+        let location = match *fx.local_map[place.local].inner() {
+            CPlaceInner::Var(_local, var) => {
+                Some(VariableLocation::Var(ValueLabel::from_u32(var.as_u32())))
+            }
+            CPlaceInner::VarPair(_local, var1, var2) => {
+                let (ty1, ty2) = fx.clif_pair_type(ty).unwrap();
+                Some(VariableLocation::VarPair(
+                    ValueLabel::from_u32(var1.as_u32()),
+                    u64::from(ty1.bytes()),
+                    ValueLabel::from_u32(var2.as_u32()),
+                    u64::from(ty2.bytes()),
+                ))
+            }
+            // Labeled by `label_local_place`, unless it is a zero-sized variable.
+            CPlaceInner::Addr(_, None) => {
+                let local_addr_labels = &fx.func_debug_cx.as_ref().unwrap().local_addr_labels;
+                local_addr_labels.get(&place.local).copied().map(VariableLocation::Addr)
+            }
+            // FIXME describe locals stored in a lane of a vector and unsized locals
+            CPlaceInner::VarLane(..) | CPlaceInner::Addr(_, Some(_)) => None,
+        };
+
+        let is_argument = (1..=mir.arg_count).contains(&place.local.index());
+        let span = var_debug_info.source_info.span;
+        variables.push((var_debug_info.name, span, is_argument, ty, location));
+    }
+
+    let debug_context = fx.cx.debug_context.as_mut().unwrap();
+    let func_debug_cx = fx.func_debug_cx.as_mut().unwrap();
+    for (name, span, is_argument, ty, location) in variables {
+        let (file, line, column) = DebugContext::get_span_loc(fx.tcx, mir.span, span);
+        let file_id = debug_context.add_source_file(&file);
+        let type_id = debug_context.debug_type(fx.tcx, type_dbg, ty);
+
+        let tag = if is_argument { gimli::DW_TAG_formal_parameter } else { gimli::DW_TAG_variable };
+        // FIXME add to the lexical block of the variable's scope instead of the function itself
+        let var_id = debug_context.dwarf.unit.add(func_debug_cx.entry_id, tag);
+        let name_id = debug_context.dwarf.strings.add(name.as_str());
+        let var_entry = debug_context.dwarf.unit.get_mut(var_id);
+        var_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        var_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(type_id));
+        var_entry.set(gimli::DW_AT_decl_file, AttributeValue::FileIndex(Some(file_id)));
+        var_entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+        var_entry.set(gimli::DW_AT_decl_column, AttributeValue::Udata(column));
+
+        if let Some(location) = location {
+            func_debug_cx.variables.push((var_id, location));
+        }
+    }
+}
+
+impl FunctionDebugContext {
+    pub(super) fn add_variable_locations(
+        &self,
+        debug_context: &mut DebugContext,
+        symbol: usize,
+        context: &Context,
+        isa: &dyn TargetIsa,
+    ) {
+        let compiled_code = context.compiled_code().unwrap();
+        let value_labels_ranges = &compiled_code.value_labels_ranges;
+
+        for &(var_id, location) in &self.variables {
+            let location = match location {
+                VariableLocation::Var(label) => location_list(
+                    debug_context,
+                    isa,
+                    symbol,
+                    value_labels_ranges,
+                    &[(label, None)],
+                    false,
+                ),
+                VariableLocation::VarPair(label1, size1, label2, size2) => location_list(
+                    debug_context,
+                    isa,
+                    symbol,
+                    value_labels_ranges,
+                    &[(label1, Some(size1)), (label2, Some(size2))],
+                    false,
+                ),
+                VariableLocation::Addr(label) => location_list(
+                    debug_context,
+                    isa,
+                    symbol,
+                    value_labels_ranges,
+                    &[(label, None)],
+                    true,
+                ),
+            };
+
+            if let Some(location) = location {
+                debug_context.dwarf.unit.get_mut(var_id).set(gimli::DW_AT_location, location);
+            }
+        }
+    }
+}
+
+/// Creates a location list for a variable made up of the values with the given labels. Each value
+/// is a piece of the given size of the variable, or the whole variable if there is only a single
+/// value. With `is_addr`, the value is instead the address the variable is stored at.
+fn location_list(
+    debug_context: &mut DebugContext,
+    isa: &dyn TargetIsa,
+    symbol: usize,
+    value_labels_ranges: &ValueLabelsRanges,
+    pieces: &[(ValueLabel, Option<u64>)],
+    is_addr: bool,
+) -> Option<AttributeValue> {
+    let piece_ranges: Vec<&[ValueLocRange]> = pieces
+        .iter()
+        .map(|(label, _)| value_labels_ranges.get(label).map_or(&[][..], |ranges| &ranges[..]))
+        .collect();
+
+    // Split the function at every point where the location of any of the pieces changes.
+    let mut bounds: Vec<u32> = piece_ranges
+        .iter()
+        .flat_map(|ranges| ranges.iter().flat_map(|range| [range.start, range.end]))
+        .collect();
+    bounds.sort_unstable();
+    bounds.dedup();
+
+    let mut locations = vec![];
+    for window in bounds.windows(2) {
+        let (start, end) = (window[0], window[1]);
+
+        let mut expr = Expression::new();
+        let mut is_available = false;
+        for (&(_, piece_size), ranges) in pieces.iter().zip(&piece_ranges) {
+            let range = ranges.iter().find(|range| range.start <= start && end <= range.end);
+            if let Some(range) = range {
+                is_available |= push_value_location(isa, range.loc, is_addr, &mut expr);
+            }
+            // A piece without location is reported as optimized out.
+            if let Some(piece_size) = piece_size {
+                expr.op_piece(piece_size);
+            }
+        }
+
+        if is_available {
+            locations.push(Location::StartEnd {
+                begin: Address::Symbol { symbol, addend: i64::from(start) },
+                end: Address::Symbol { symbol, addend: i64::from(end) },
+                data: expr,
+            });
+        }
+    }
+
+    if locations.is_empty() {
+        return None;
+    }
+    let location_list_id = debug_context.dwarf.unit.locations.add(LocationList(locations));
+    Some(AttributeValue::LocationListRef(location_list_id))
+}
+
+/// Pushes the location description of a value to `expr`. Returns false without pushing anything
+/// if the location can't be described.
+fn push_value_location(
+    isa: &dyn TargetIsa,
+    loc: LabelValueLoc,
+    is_addr: bool,
+    expr: &mut Expression,
+) -> bool {
+    match loc {
+        LabelValueLoc::Reg(reg) => {
+            let Ok(reg) = isa.map_regalloc_reg_to_dwarf(reg) else { return false };
+            if is_addr { expr.op_breg(Register(reg), 0) } else { expr.op_reg(Register(reg)) }
+        }
+        // The frame base is the CFA, see `DebugContext::define_function`.
+        LabelValueLoc::CFAOffset(cfa_offset) => {
+            expr.op_fbreg(cfa_offset);
+            if is_addr {
+                expr.op_deref();
+            }
+        }
+    }
+    true
+}
@@ -568,7 +568,12 @@ fn codegen_regular_intrinsic_call<'tcx>(
             intrinsic_args!(fx, args => (from); intrinsic);
 
             if ret.layout().abi.is_uninhabited() {
-                crate::base::codegen_panic(fx, "Transmuting to uninhabited type.", source_info);
+                crate::base::codegen_panic(
+                    fx,
+                    "Transmuting to uninhabited type.",
+                    source_info,
+                    None,
+                );
                 return;
             }
 
@@ -1122,20 +1127,25 @@ fn codegen_regular_intrinsic_call<'tcx>(
             intrinsic_args!(fx, args => (f, data, catch_fn); intrinsic);
             let f = f.load_scalar(fx);
             let data = data.load_scalar(fx);
-            let _catch_fn = catch_fn.load_scalar(fx);
+            let catch_fn = catch_fn.load_scalar(fx);
 
-            // FIXME once unwinding is supported, change this to actually catch panics
-            let f_sig = fx.bcx.func.import_signature(Signature {
-                call_conv: fx.target_config.default_call_conv,
-                params: vec![AbiParam::new(pointer_ty(fx.tcx))],
-                returns: vec![],
-            });
+            if fx.cx.supports_landing_pads {
+                let ret_val = crate::landing_pads::codegen_try(fx, f, data, catch_fn);
+                ret.write_cvalue(fx, CValue::by_val(ret_val, ret.layout()));
+            } else {
+                // Without `-Cpanic=unwind` panics abort before they could be caught.
+                let f_sig = fx.bcx.func.import_signature(Signature {
+                    call_conv: fx.target_config.default_call_conv,
+                    params: vec![AbiParam::new(pointer_ty(fx.tcx))],
+                    returns: vec![],
+                });
 
-            fx.bcx.ins().call_indirect(f_sig, f, &[data]);
+                fx.bcx.ins().call_indirect(f_sig, f, &[data]);
 
-            let layout = ret.layout();
-            let ret_val = CValue::const_val(fx, layout, ty::ScalarInt::null(layout.size));
-            ret.write_cvalue(fx, ret_val);
+                let layout = ret.layout();
+                let ret_val = CValue::const_val(fx, layout, ty::ScalarInt::null(layout.size));
+                ret.write_cvalue(fx, ret_val);
+            }
         }
 
         sym::fadd_fast | sym::fsub_fast | sym::fmul_fast | sym::fdiv_fast | sym::frem_fast => {
@@ -0,0 +1,373 @@
+//! Landing pads for `-Cpanic=unwind`.
+//!
+//! Cranelift doesn't support landing pads, so every call which needs one is made from a shim
+//! written in assembly. These shims have an LSDA in the format `rust_eh_personality` expects:
+//!
+//! * The try shim used for the `try` intrinsic has a catch-all landing pad which calls the catch
+//!   function.
+//! * The invoke shim used for calls with a cleanup block has a cleanup landing pad which returns
+//!   the exception to the caller, which then jumps to the cleanup block. Once the cleanup block
+//!   is done, the `Resume` terminator continues unwinding using `_Unwind_Resume`.
+//!
+//! As the invoke shim can't know the signature of the callee, it calls a thunk, which loads the
+//! callee and its arguments from a block on the stack of the caller and stores the return values
+//! of the call into the same block.
+
+use std::fmt::Write;
+
+use cranelift_codegen::ir::UserFuncName;
+use smallvec::SmallVec;
+
+use crate::prelude::*;
+
+/// Calls `try_fn(data)` and returns 0 if it returns normally. If it unwinds instead, this calls
+/// `catch_fn(data, exception)` and returns 1.
+pub(crate) fn codegen_try(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    try_fn: Value,
+    data: Value,
+    catch_fn: Value,
+) -> Value {
+    let inline_asm_index = fx.cx.inline_asm_index.get();
+    fx.cx.inline_asm_index.set(inline_asm_index + 1);
+    let shim_name = format!("__cg_clif_try_{}_n{}", mangled_cgu_name(fx), inline_asm_index);
+    fx.cx.global_asm.push_str(&generate_try_shim(&shim_name));
+
+    let shim = fx
+        .module
+        .declare_function(
+            &shim_name,
+            Linkage::Import,
+            &Signature {
+                call_conv: CallConv::SystemV,
+                params: vec![AbiParam::new(fx.pointer_type); 3],
+                returns: vec![AbiParam::new(types::I32)],
+            },
+        )
+        .unwrap();
+    let shim = fx.module.declare_func_in_func(shim, &mut fx.bcx.func);
+    if fx.clif_comments.enabled() {
+        fx.add_comment(shim, shim_name);
+    }
+
+    let call = fx.bcx.ins().call(shim, &[try_fn, data, catch_fn]);
+    fx.bcx.inst_results(call)[0]
+}
+
+/// Calls `callee` with signature `sig`. If it unwinds, the exception is stored in the exception
+/// variable of the function and execution continues at the `cleanup` block.
+pub(crate) fn codegen_invoke(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    callee: Value,
+    sig: Signature,
+    args: &[Value],
+    cleanup: BasicBlock,
+) -> SmallVec<[Value; 2]> {
+    // The layout of the block shared with the thunk: the callee, the arguments and then the
+    // return values, each aligned to its size.
+    let mut block_size = 0;
+    let mut alloc = |ty: Type| {
+        let align = ty.bytes().min(16);
+        let offset = (block_size + align - 1) / align * align;
+        block_size = offset + ty.bytes();
+        i32::try_from(offset).unwrap()
+    };
+    let callee_offset = alloc(fx.pointer_type);
+    let arg_offsets = sig.params.iter().map(|param| alloc(param.value_type)).collect::<Vec<_>>();
+    let ret_offsets = sig.returns.iter().map(|ret| alloc(ret.value_type)).collect::<Vec<_>>();
+
+    let mut flags = MemFlags::new();
+    flags.set_notrap();
+
+    let thunk = define_invoke_thunk(fx, &sig, callee_offset, &arg_offsets, &ret_offsets, flags);
+
+    let block_slot = fx.bcx.create_sized_stack_slot(StackSlotData {
+        kind: StackSlotKind::ExplicitSlot,
+        size: (block_size + 15) / 16 * 16,
+    });
+    let block_ptr = fx.bcx.ins().stack_addr(fx.pointer_type, block_slot, 0);
+    fx.bcx.ins().store(flags, callee, block_ptr, callee_offset);
+    for (&arg, &offset) in args.iter().zip(&arg_offsets) {
+        fx.bcx.ins().store(flags, arg, block_ptr, offset);
+    }
+
+    let thunk = fx.module.declare_func_in_func(thunk, &mut fx.bcx.func);
+    let thunk = fx.bcx.ins().func_addr(fx.pointer_type, thunk);
+    let shim = import_invoke_shim(fx);
+    let call = fx.bcx.ins().call(shim, &[thunk, block_ptr]);
+    let exception = fx.bcx.inst_results(call)[0];
+
+    let landing_pad = fx.bcx.create_block();
+    let next = fx.bcx.create_block();
+    fx.bcx.set_cold_block(landing_pad);
+    fx.bcx.ins().brnz(exception, landing_pad, &[]);
+    fx.bcx.ins().jump(next, &[]);
+
+    fx.bcx.switch_to_block(landing_pad);
+    let exception_var = exception_var(fx);
+    fx.bcx.def_var(exception_var, exception);
+    let cleanup = fx.get_block(cleanup);
+    fx.bcx.ins().jump(cleanup, &[]);
+
+    fx.bcx.switch_to_block(next);
+    sig.returns
+        .iter()
+        .zip(&ret_offsets)
+        .map(|(ret, &offset)| fx.bcx.ins().load(ret.value_type, flags, block_ptr, offset))
+        .collect()
+}
+
+/// Continues unwinding with the exception caught by the landing pad of a previous call.
+pub(crate) fn codegen_resume(fx: &mut FunctionCx<'_, '_, '_>) {
+    let exception_var = exception_var(fx);
+    let exception = fx.bcx.use_var(exception_var);
+    fx.lib_call("_Unwind_Resume", vec![AbiParam::new(fx.pointer_type)], vec![], &[exception]);
+    fx.bcx.ins().trap(TrapCode::UnreachableCodeReached);
+}
+
+fn exception_var(fx: &mut FunctionCx<'_, '_, '_>) -> Variable {
+    if let Some(exception_var) = fx.exception_var {
+        return exception_var;
+    }
+
+    let exception_var = Variable::from_u32(fx.next_ssa_var);
+    fx.next_ssa_var += 1;
+    fx.bcx.declare_var(exception_var, fx.pointer_type);
+    fx.exception_var = Some(exception_var);
+    exception_var
+}
+
+fn mangled_cgu_name(fx: &FunctionCx<'_, '_, '_>) -> String {
+    fx.cx.cgu_name.as_str().replace('.', "__").replace('-', "_")
+}
+
+/// Defines a thunk which loads the callee and its arguments from the block pointed to by its only
+/// argument, calls the callee and stores the return values into the block.
+fn define_invoke_thunk(
+    fx: &mut FunctionCx<'_, '_, '_>,
+    sig: &Signature,
+    callee_offset: i32,
+    arg_offsets: &[i32],
+    ret_offsets: &[i32],
+    flags: MemFlags,
+) -> FuncId {
+    let thunk_sig = Signature {
+        call_conv: CallConv::SystemV,
+        params: vec![AbiParam::new(fx.pointer_type)],
+        returns: vec![],
+    };
+    let thunk_id = fx.module.declare_anonymous_function(&thunk_sig).unwrap();
+
+    let mut ctx = Context::for_function(Function::with_name_signature(
+        UserFuncName::user(0, thunk_id.as_u32()),
+        thunk_sig,
+    ));
+    {
+        let mut func_ctx = FunctionBuilderContext::new();
+        let mut bcx = FunctionBuilder::new(&mut ctx.func, &mut func_ctx);
+
+        let block = bcx.create_block();
+        bcx.append_block_params_for_function_params(block);
+        bcx.switch_to_block(block);
+        let block_ptr = bcx.block_params(block)[0];
+
+        let callee = bcx.ins().load(fx.pointer_type, flags, block_ptr, callee_offset);
+        let args = sig
+            .params
+            .iter()
+            .zip(arg_offsets)
+            .map(|(param, &offset)| bcx.ins().load(param.value_type, flags, block_ptr, offset))
+            .collect::<Vec<_>>();
+        let sig_ref = bcx.import_signature(sig.clone());
+        let call_inst = bcx.ins().call_indirect(sig_ref, callee, &args);
+        let results = bcx.inst_results(call_inst).to_vec(); // Clone to prevent borrow error
+        for (result, &offset) in results.into_iter().zip(ret_offsets) {
+            bcx.ins().store(flags, result, block_ptr, offset);
+        }
+
+        bcx.ins().return_(&[]);
+        bcx.seal_all_blocks();
+        bcx.finalize();
+    }
+    fx.module.define_function(thunk_id, &mut ctx).unwrap();
+    fx.cx.unwind_context.add_function(thunk_id, &ctx, fx.module.isa());
+
+    thunk_id
+}
+
+/// Imports the invoke shim of the codegen unit, adding it to the global asm the first time.
+fn import_invoke_shim(fx: &mut FunctionCx<'_, '_, '_>) -> FuncRef {
+    let shim_name = format!("__cg_clif_invoke_{}", mangled_cgu_name(fx));
+    if !fx.cx.has_invoke_shim {
+        fx.cx.global_asm.push_str(&generate_invoke_shim(&shim_name));
+        fx.cx.has_invoke_shim = true;
+    }
+
+    let shim = fx
+        .module
+        .declare_function(
+            &shim_name,
+            Linkage::Import,
+            &Signature {
+                call_conv: CallConv::SystemV,
+                params: vec![AbiParam::new(fx.pointer_type); 2],
+                returns: vec![AbiParam::new(fx.pointer_type)],
+            },
+        )
+        .unwrap();
+    let shim = fx.module.declare_func_in_func(shim, &mut fx.bcx.func);
+    if fx.clif_comments.enabled() {
+        fx.add_comment(shim, shim_name);
+    }
+    shim
+}
+
+/// Generates the try shim for x86_64 ELF targets. This mirrors what LLVM generates for the `try`
+/// intrinsic, including the LSDA in `.gcc_except_table`, which is what `rust_eh_personality`
+/// expects.
+fn generate_try_shim(name: &str) -> String {
+    let mut asm = String::new();
+
+    write_shim_start(&mut asm, name);
+
+    // Keep `data` and `catch_fn` in callee-saved registers for the landing pad. The stack is
+    // realigned to 16 bytes for the calls.
+    asm.push_str("    push rbx\n");
+    asm.push_str("    .cfi_def_cfa_offset 16\n");
+    asm.push_str("    push r12\n");
+    asm.push_str("    .cfi_def_cfa_offset 24\n");
+    asm.push_str("    sub rsp, 8\n");
+    asm.push_str("    .cfi_def_cfa_offset 32\n");
+    asm.push_str("    .cfi_offset rbx, -16\n");
+    asm.push_str("    .cfi_offset r12, -24\n");
+    asm.push_str("    mov rbx, rsi\n");
+    asm.push_str("    mov r12, rdx\n");
+    asm.push_str("    mov rax, rdi\n");
+    asm.push_str("    mov rdi, rsi\n");
+    writeln!(asm, ".L{}_try_begin:", name).unwrap();
+    asm.push_str("    call rax\n");
+    writeln!(asm, ".L{}_try_end:", name).unwrap();
+    asm.push_str("    xor eax, eax\n");
+    writeln!(asm, "    jmp .L{}_return", name).unwrap();
+
+    // The personality function passes the exception in rax.
+    writeln!(asm, ".L{}_landing_pad:", name).unwrap();
+    asm.push_str("    mov rdi, rbx\n");
+    asm.push_str("    mov rsi, rax\n");
+    asm.push_str("    call r12\n");
+    asm.push_str("    mov eax, 1\n");
+
+    writeln!(asm, ".L{}_return:", name).unwrap();
+    asm.push_str("    add rsp, 8\n");
+    asm.push_str("    .cfi_def_cfa_offset 24\n");
+    asm.push_str("    pop r12\n");
+    asm.push_str("    .cfi_def_cfa_offset 16\n");
+    asm.push_str("    pop rbx\n");
+    asm.push_str("    .cfi_def_cfa_offset 8\n");
+    asm.push_str("    ret\n");
+
+    // Only the call of the try function has a call site entry. Unwinding out of the catch
+    // function terminates the process like it does for LLVM.
+    write_shim_end(&mut asm, name, true);
+
+    asm
+}
+
+/// Generates the invoke shim for x86_64 ELF targets. It calls `thunk(block)` and returns null if
+/// it returns normally. If it unwinds instead, the cleanup landing pad returns the exception.
+fn generate_invoke_shim(name: &str) -> String {
+    let mut asm = String::new();
+
+    write_shim_start(&mut asm, name);
+
+    // Realign the stack to 16 bytes for the call.
+    asm.push_str("    sub rsp, 8\n");
+    asm.push_str("    .cfi_def_cfa_offset 16\n");
+    asm.push_str("    mov rax, rdi\n");
+    asm.push_str("    mov rdi, rsi\n");
+    writeln!(asm, ".L{}_try_begin:", name).unwrap();
+    asm.push_str("    call rax\n");
+    writeln!(asm, ".L{}_try_end:", name).unwrap();
+    asm.push_str("    xor eax, eax\n");
+
+    // The personality function passes the exception in rax, so it only needs to be returned.
+    writeln!(asm, ".L{}_landing_pad:", name).unwrap();
+    asm.push_str("    add rsp, 8\n");
+    asm.push_str("    .cfi_def_cfa_offset 8\n");
+    asm.push_str("    ret\n");
+
+    // A cleanup landing pad is skipped while searching for a handler, so a panic is only caught
+    // if there is a try shim further up the stack, like with LLVM.
+    write_shim_end(&mut asm, name, false);
+
+    asm
+}
+
+fn write_shim_start(asm: &mut String, name: &str) {
+    writeln!(asm, ".globl {}", name).unwrap();
+    writeln!(asm, ".type {},@function", name).unwrap();
+    writeln!(asm, ".section .text.{},\"ax\",@progbits", name).unwrap();
+    writeln!(asm, "{}:", name).unwrap();
+    asm.push_str(".cfi_startproc\n");
+    // indirect | pcrel | sdata4
+    writeln!(asm, ".cfi_personality 0x9b, .L{}_personality", name).unwrap();
+    // pcrel | sdata4
+    writeln!(asm, ".cfi_lsda 0x1b, .L{}_lsda", name).unwrap();
+    asm.push_str(".intel_syntax noprefix\n");
+}
+
+/// Ends the shim started by `write_shim_start` and writes its LSDA, which has a single call site
+/// entry from `.L{name}_try_begin` to `.L{name}_try_end` with the landing pad at
+/// `.L{name}_landing_pad`. This landing pad either catches all exceptions or is a cleanup.
+fn write_shim_end(asm: &mut String, name: &str, catch_all: bool) {
+    asm.push_str(".att_syntax\n");
+    asm.push_str(".cfi_endproc\n");
+    writeln!(asm, ".size {name}, .-{name}", name = name).unwrap();
+
+    writeln!(asm, ".section .gcc_except_table.{},\"a\",@progbits", name).unwrap();
+    asm.push_str(".p2align 2\n");
+    writeln!(asm, ".L{}_lsda:", name).unwrap();
+    // @LPStart encoding: omit
+    asm.push_str("    .byte 0xff\n");
+    if catch_all {
+        // @TType encoding: indirect | pcrel | sdata4
+        asm.push_str("    .byte 0x9b\n");
+        writeln!(asm, "    .uleb128 .L{name}_ttbase-.L{name}_ttbase_ref", name = name).unwrap();
+        writeln!(asm, ".L{}_ttbase_ref:", name).unwrap();
+    } else {
+        // @TType encoding: omit
+        asm.push_str("    .byte 0xff\n");
+    }
+    // Call site encoding: uleb128
+    asm.push_str("    .byte 0x01\n");
+    writeln!(asm, "    .uleb128 .L{name}_call_sites_end-.L{name}_call_sites", name = name).unwrap();
+    writeln!(asm, ".L{}_call_sites:", name).unwrap();
+    writeln!(asm, "    .uleb128 .L{name}_try_begin-{name}", name = name).unwrap();
+    writeln!(asm, "    .uleb128 .L{name}_try_end-.L{name}_try_begin", name = name).unwrap();
+    writeln!(asm, "    .uleb128 .L{name}_landing_pad-{name}", name = name).unwrap();
+    if catch_all {
+        // Action: the first action record
+        asm.push_str("    .byte 0x01\n");
+        writeln!(asm, ".L{}_call_sites_end:", name).unwrap();
+        // Action record: type filter 1 without a next action
+        asm.push_str("    .byte 0x01\n");
+        asm.push_str("    .byte 0x00\n");
+        asm.push_str(".p2align 2\n");
+        // Type info 1: null, which catches everything
+        asm.push_str("    .long 0\n");
+        writeln!(asm, ".L{}_ttbase:", name).unwrap();
+    } else {
+        // Action: none, which makes the landing pad a cleanup
+        asm.push_str("    .byte 0x00\n");
+        writeln!(asm, ".L{}_call_sites_end:", name).unwrap();
+    }
+
+    // A private reference to the personality function for the indirect encoding.
+    writeln!(asm, ".section .data.rel.ro.{},\"aw\",@progbits", name).unwrap();
+    asm.push_str(".p2align 3\n");
+    writeln!(asm, ".L{}_personality:", name).unwrap();
+    asm.push_str("    .quad rust_eh_personality\n");
+
+    asm.push_str(".text\n");
+    asm.push_str("\n\n");
+}
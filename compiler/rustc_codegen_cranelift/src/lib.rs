@@ -38,6 +38,7 @@
 use rustc_session::config::OutputFilenames;
 use rustc_session::Session;
 use rustc_span::Symbol;
+use rustc_target::spec::PanicStrategy;
 
 use cranelift_codegen::isa::TargetIsa;
 use cranelift_codegen::settings::{self, Configurable};
@@ -65,6 +66,7 @@
 mod global_asm;
 mod inline_asm;
 mod intrinsics;
+mod landing_pads;
 mod linkage;
 mod main_shim;
 mod num;
@@ -132,6 +134,8 @@ struct CodegenCx {
     should_write_ir: bool,
     global_asm: String,
     inline_asm_index: Cell<usize>,
+    supports_landing_pads: bool,
+    has_invoke_shim: bool,
     debug_context: Option<DebugContext>,
     unwind_context: UnwindContext,
     cgu_name: Symbol,
@@ -149,6 +153,13 @@ fn new(
 
         let unwind_context =
             UnwindContext::new(isa, matches!(backend_config.codegen_mode, CodegenMode::Aot));
+        // Landing pads are written in assembly as Cranelift doesn't support them. Assembly is not
+        // supported in JIT mode.
+        let supports_landing_pads = tcx.sess.panic_strategy() == PanicStrategy::Unwind
+            && matches!(backend_config.codegen_mode, CodegenMode::Aot)
+            && cfg!(feature = "inline_asm")
+            && tcx.sess.target.arch == "x86_64"
+            && tcx.sess.target.os == "linux";
         let debug_context = if debug_info && !tcx.sess.target.options.is_like_windows {
             Some(DebugContext::new(tcx, isa))
         } else {
@@ -160,6 +171,8 @@ fn new(
             should_write_ir: crate::pretty_clif::should_write_ir(tcx),
             global_asm: String::new(),
             inline_asm_index: Cell::new(0),
+            supports_landing_pads,
+            has_invoke_shim: false,
             debug_context,
             unwind_context,
             cgu_name,
@@ -28,7 +28,7 @@
 use rustc_data_structures::small_c_str::SmallCStr;
 use rustc_middle::dep_graph;
 use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrs;
-use rustc_middle::mir::mono::{Linkage, Visibility};
+use rustc_middle::mir::mono::{Linkage, MonoItem, Visibility};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::DebugInfo;
 use rustc_span::symbol::Symbol;
@@ -57,6 +57,35 @@ pub fn iter_globals(llmod: &llvm::Module) -> ValueIter<'_> {
     unsafe { ValueIter { cur: llvm::LLVMGetFirstGlobal(llmod), step: llvm::LLVMGetNextGlobal } }
 }
 
+/// Records the number of LLVM instructions of the functions defined in a codegen unit, before
+/// optimization, for `-Z dump-mono-bloat`. Functions codegenned in several codegen units are
+/// counted once in each of them.
+fn record_instruction_counts<'tcx>(
+    cx: &CodegenCx<'_, 'tcx>,
+    mono_items: &[(MonoItem<'tcx>, (Linkage, Visibility))],
+) {
+    let mut counts = cx.sess().codegen_instruction_counts.lock();
+    for &(mono_item, _) in mono_items {
+        if !matches!(mono_item, MonoItem::Fn(_)) {
+            continue;
+        }
+        let symbol_name = mono_item.symbol_name(cx.tcx).name;
+        let Some(llfn) = cx.get_defined_value(symbol_name) else { continue };
+
+        let mut count = 0;
+        let mut llbb = unsafe { Some(llvm::LLVMGetFirstBasicBlock(llfn)) };
+        while let Some(bb) = llbb {
+            let instructions = ValueIter {
+                cur: unsafe { llvm::LLVMGetFirstInstruction(bb) },
+                step: llvm::LLVMGetNextInstruction,
+            };
+            count += instructions.count();
+            llbb = unsafe { llvm::LLVMGetNextBasicBlock(bb) };
+        }
+        *counts.entry(symbol_name.to_string()).or_default() += count;
+    }
+}
+
 pub fn compile_codegen_unit(tcx: TyCtxt<'_>, cgu_name: Symbol) -> (ModuleCodegen<ModuleLlvm>, u64) {
     let start_time = Instant::now();
 
@@ -95,6 +124,10 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
                 mono_item.define::<Builder<'_, '_, '_>>(&cx);
             }
 
+            if cx.sess().opts.unstable_opts.dump_mono_bloat.enabled() {
+                record_instruction_counts(&cx, &mono_items);
+            }
+
             // If this codegen unit contains the main function, also create the
             // wrapper here
             if let Some(entry) = maybe_create_entry_wrapper::<Builder<'_, '_, '_>>(&cx) {
@@ -50,6 +50,17 @@ pub fn codegen_coverage(&self, bx: &mut Bx, coverage: Coverage, scope: SourceSco
                     code_region.expect("unreachable regions always have code regions"),
                 );
             }
+            CoverageKind::Branch { true_counter, false_counter } => {
+                bx.add_coverage_branch(
+                    instance,
+                    true_counter,
+                    false_counter,
+                    code_region.expect("branch regions always have code regions"),
+                );
+            }
+            CoverageKind::BlockMarker { id } => {
+                bug!("block markers are removed after borrowck: {:?}", id)
+            }
         }
     }
 }
@@ -79,6 +79,13 @@
 //! invocation) and allocate the corresponding strings together with a mapping
 //! for `DepNodeIndex as StringId`.
 //!
+//!
+//! ## Summaries
+//!
+//! With `-Z self-profile-summary`, the `SelfProfiler` also aggregates query and
+//! generic activity events in memory and writes a per-item table next to the
+//! raw event data at the end of the session. See the `summary` module.
+//!
 //! [mm]: https://github.com/rust-lang/measureme/
 
 use crate::cold_path;
@@ -99,6 +106,11 @@
 use parking_lot::RwLock;
 use smallvec::SmallVec;
 
+mod summary;
+
+pub use summary::SelfProfileSummaryFormat;
+use summary::{SummaryEventKind, SummaryRecorder, SummaryTimer};
+
 bitflags::bitflags! {
     struct EventFilter: u32 {
         const GENERIC_ACTIVITIES  = 1 << 0;
@@ -121,6 +133,14 @@ struct EventFilter: u32 {
                         Self::ARTIFACT_SIZES.bits;
 
         const ARGS = Self::QUERY_KEYS.bits | Self::FUNCTION_ARGS.bits;
+
+        // The events needed for `-Z self-profile-summary`.
+        const SUMMARY = Self::GENERIC_ACTIVITIES.bits |
+                        Self::QUERY_PROVIDERS.bits |
+                        Self::QUERY_CACHE_HITS.bits |
+                        Self::QUERY_BLOCKED.bits |
+                        Self::INCR_CACHE_LOADS.bits |
+                        Self::INCR_RESULT_HASHING.bits;
     }
 }
 
@@ -143,6 +163,7 @@ struct EventFilter: u32 {
 ];
 
 /// Something that uniquely identifies a query invocation.
+#[derive(Clone, Copy)]
 pub struct QueryInvocationId(pub u32);
 
 /// A reference to the SelfProfiler. It can be cloned and sent across thread
@@ -236,9 +257,9 @@ pub fn verbose_generic_activity_with_arg<A>(
     #[inline(always)]
     pub fn generic_activity(&self, event_label: &'static str) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
-            let event_id = EventId::from_label(event_label);
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            let event_label_id = profiler.get_or_alloc_cached_string(event_label);
+            let event_id = EventId::from_label(event_label_id);
+            TimingGuard::start_generic_activity(profiler, event_label, event_id)
         })
     }
 
@@ -264,14 +285,14 @@ pub fn generic_activity_with_arg<A>(
     {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
+            let event_label_id = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
                 let event_arg = profiler.get_or_alloc_cached_string(event_arg);
-                builder.from_label_and_arg(event_label, event_arg)
+                builder.from_label_and_arg(event_label_id, event_arg)
             } else {
-                builder.from_label(event_label)
+                builder.from_label(event_label_id)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_generic_activity(profiler, event_label, event_id)
         })
     }
 
@@ -307,7 +328,7 @@ pub fn generic_activity_with_arg_recorder<F>(
         // Ensure this event will only be recorded when self-profiling is turned on.
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
+            let event_label_id = profiler.get_or_alloc_cached_string(event_label);
 
             // Ensure the closure to create event arguments will only be called when argument
             // recording is turned on.
@@ -327,11 +348,11 @@ pub fn generic_activity_with_arg_recorder<F>(
                     );
                 }
 
-                builder.from_label_and_args(event_label, &recorder.args)
+                builder.from_label_and_args(event_label_id, &recorder.args)
             } else {
-                builder.from_label(event_label)
+                builder.from_label(event_label_id)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_generic_activity(profiler, event_label, event_id)
         })
     }
 
@@ -370,17 +391,17 @@ pub fn generic_activity_with_args(
     ) -> TimingGuard<'_> {
         self.exec(EventFilter::GENERIC_ACTIVITIES, |profiler| {
             let builder = EventIdBuilder::new(&profiler.profiler);
-            let event_label = profiler.get_or_alloc_cached_string(event_label);
+            let event_label_id = profiler.get_or_alloc_cached_string(event_label);
             let event_id = if profiler.event_filter_mask.contains(EventFilter::FUNCTION_ARGS) {
                 let event_args: Vec<_> = event_args
                     .iter()
                     .map(|s| profiler.get_or_alloc_cached_string(&s[..]))
                     .collect();
-                builder.from_label_and_args(event_label, &event_args)
+                builder.from_label_and_args(event_label_id, &event_args)
             } else {
-                builder.from_label(event_label)
+                builder.from_label(event_label_id)
             };
-            TimingGuard::start(profiler, profiler.generic_activity_event_kind, event_id)
+            TimingGuard::start_generic_activity(profiler, event_label, event_id)
         })
     }
 
@@ -399,6 +420,9 @@ pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
         #[inline(never)]
         #[cold]
         fn cold_call(profiler_ref: &SelfProfilerRef, query_invocation_id: QueryInvocationId) {
+            if let Some(summary) = &profiler_ref.profiler.as_ref().unwrap().summary {
+                summary.record_cache_hit(query_invocation_id);
+            }
             profiler_ref.instant_query_event(
                 |profiler| profiler.query_cache_hit_event_kind,
                 query_invocation_id,
@@ -532,6 +556,9 @@ pub struct SelfProfiler {
     query_blocked_event_kind: StringId,
     query_cache_hit_event_kind: StringId,
     artifact_size_event_kind: StringId,
+
+    /// Only present with `-Z self-profile-summary`.
+    summary: Option<SummaryRecorder>,
 }
 
 impl SelfProfiler {
@@ -540,6 +567,7 @@ pub fn new(
         crate_name: Option<&str>,
         event_filters: Option<&[String]>,
         counter_name: &str,
+        summary_format: Option<SelfProfileSummaryFormat>,
     ) -> Result<SelfProfiler, Box<dyn Error + Send + Sync>> {
         fs::create_dir_all(output_directory)?;
 
@@ -595,6 +623,12 @@ pub fn new(
             event_filter_mask = EventFilter::DEFAULT;
         }
 
+        let summary = summary_format.map(|format| {
+            // The summary is useless without the events it aggregates.
+            event_filter_mask |= EventFilter::SUMMARY;
+            SummaryRecorder::new(format, path)
+        });
+
         Ok(SelfProfiler {
             profiler,
             event_filter_mask,
@@ -606,6 +640,7 @@ pub fn new(
             query_blocked_event_kind,
             query_cache_hit_event_kind,
             artifact_size_event_kind,
+            summary,
         })
     }
 
@@ -661,13 +696,56 @@ pub fn query_key_recording_enabled(&self) -> bool {
         self.event_filter_mask.contains(EventFilter::QUERY_KEYS)
     }
 
+    pub fn summary_enabled(&self) -> bool {
+        self.summary.is_some()
+    }
+
+    /// Tells the summary which query the given invocations belong to. Must be
+    /// called for all queries before the profiler is dropped for the summary to
+    /// attribute query events correctly.
+    pub fn name_query_invocations<I>(&self, query_name: &'static str, query_invocation_ids: I)
+    where
+        I: Iterator<Item = QueryInvocationId>,
+    {
+        if let Some(summary) = &self.summary {
+            summary.name_query_invocations(query_name, query_invocation_ids);
+        }
+    }
+
     pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
         EventIdBuilder::new(&self.profiler)
     }
+
+    /// Maps the event kinds that are part of the summary to their `SummaryEventKind`.
+    /// Generic activities are handled by `TimingGuard::start_generic_activity`, which
+    /// knows their label.
+    fn summary_event_kind(&self, event_kind: StringId) -> Option<SummaryEventKind> {
+        if event_kind == self.query_event_kind {
+            Some(SummaryEventKind::Query)
+        } else if event_kind == self.query_blocked_event_kind {
+            Some(SummaryEventKind::QueryBlocked)
+        } else if event_kind == self.incremental_load_result_event_kind {
+            Some(SummaryEventKind::IncrCacheLoad)
+        } else if event_kind == self.incremental_result_hashing_event_kind {
+            Some(SummaryEventKind::IncrResultHashing)
+        } else {
+            None
+        }
+    }
+}
+
+impl Drop for SelfProfiler {
+    fn drop(&mut self) {
+        if let Some(summary) = &self.summary {
+            if let Err(e) = summary.write() {
+                eprintln!("failed to write self-profile summary: {e}");
+            }
+        }
+    }
 }
 
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>, Option<SummaryTimer<'a>>);
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -675,18 +753,46 @@ pub fn start(
         profiler: &'a SelfProfiler,
         event_kind: StringId,
         event_id: EventId,
+    ) -> TimingGuard<'a> {
+        let summary_kind = profiler.summary_event_kind(event_kind);
+        TimingGuard::start_with_summary(profiler, event_kind, event_id, summary_kind)
+    }
+
+    #[inline]
+    fn start_generic_activity(
+        profiler: &'a SelfProfiler,
+        event_label: &'static str,
+        event_id: EventId,
+    ) -> TimingGuard<'a> {
+        let summary_kind = SummaryEventKind::GenericActivity(event_label);
+        let event_kind = profiler.generic_activity_event_kind;
+        TimingGuard::start_with_summary(profiler, event_kind, event_id, Some(summary_kind))
+    }
+
+    #[inline]
+    fn start_with_summary(
+        profiler: &'a SelfProfiler,
+        event_kind: StringId,
+        event_id: EventId,
+        summary_kind: Option<SummaryEventKind>,
     ) -> TimingGuard<'a> {
         let thread_id = get_thread_id();
         let raw_profiler = &profiler.profiler;
+        let summary_timer =
+            profiler.summary.as_ref().zip(summary_kind).map(|(summary, kind)| summary.start(kind));
         let timing_guard =
             raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        TimingGuard(Some(timing_guard), summary_timer)
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard(guard, summary_timer) = self;
+        if let Some(guard) = guard {
             cold_path(|| {
+                if let Some(mut summary_timer) = summary_timer {
+                    summary_timer.set_query_invocation_id(query_invocation_id);
+                }
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
                 guard.finish_with_override_event_id(event_id);
@@ -696,7 +802,7 @@ pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocatio
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard(None, None)
     }
 
     #[inline(always)]
@@ -0,0 +1,382 @@
+//! Aggregation of self-profiling events for `-Z self-profile-summary`.
+//!
+//! The raw event data written by `measureme` needs external tools to be
+//! analyzed. When a summary is requested, the `SelfProfiler` additionally adds
+//! the duration of every query, generic activity and related event to running
+//! totals and, once the compilation session is over, writes them as a table
+//! with one row per query or activity, similar to the output of measureme's
+//! `summarize` tool.
+//!
+//! Events are recorded from any thread, so each thread adds them to its own
+//! totals, which are only merged when the summary is written. The lock around
+//! the totals of a thread is thus only contended while the summary is written.
+//!
+//! The name of a query is only known for its invocations once the session is
+//! over, so queries are totaled per invocation until then.
+
+use super::QueryInvocationId;
+use crate::fx::FxHashMap;
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+/// The format of the report written by `-Z self-profile-summary`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SelfProfileSummaryFormat {
+    /// A table meant to be read by humans.
+    Text,
+    /// A JSON object meant to be consumed by other tools.
+    Json,
+}
+
+impl SelfProfileSummaryFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            SelfProfileSummaryFormat::Text => "txt",
+            SelfProfileSummaryFormat::Json => "json",
+        }
+    }
+}
+
+/// The kinds of events that contribute to the summary.
+#[derive(Clone, Copy)]
+pub(super) enum SummaryEventKind {
+    GenericActivity(&'static str),
+    Query,
+    QueryBlocked,
+    IncrCacheLoad,
+    IncrResultHashing,
+}
+
+/// What the totals of a thread are kept for, until the summary is written.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum SummaryKey {
+    GenericActivity(&'static str),
+    /// A query invocation, which is only attributed to its query when the
+    /// summary is written.
+    QueryInvocation(u32),
+}
+
+/// One row of the summary.
+#[derive(Default)]
+struct SummaryRow {
+    self_time: Duration,
+    total_time: Duration,
+    invocation_count: usize,
+    cache_hits: usize,
+    blocked_time: Duration,
+    incremental_load_time: Duration,
+    incremental_hashing_time: Duration,
+}
+
+impl SummaryRow {
+    fn merge(&mut self, other: &SummaryRow) {
+        self.self_time += other.self_time;
+        self.total_time += other.total_time;
+        self.invocation_count += other.invocation_count;
+        self.cache_hits += other.cache_hits;
+        self.blocked_time += other.blocked_time;
+        self.incremental_load_time += other.incremental_load_time;
+        self.incremental_hashing_time += other.incremental_hashing_time;
+    }
+}
+
+/// The totals of the events recorded by one thread.
+#[derive(Default)]
+struct ThreadTotals {
+    rows: FxHashMap<SummaryKey, SummaryRow>,
+    /// The time spent in the children of each event that is running on this
+    /// thread, innermost last. Events on a thread are properly nested, so this
+    /// is what is subtracted from an event's duration to get its self time.
+    child_time: Vec<Duration>,
+}
+
+impl ThreadTotals {
+    fn start_event(&mut self) {
+        self.child_time.push(Duration::ZERO);
+    }
+
+    fn finish_event(
+        &mut self,
+        kind: SummaryEventKind,
+        key: Option<SummaryKey>,
+        duration: Duration,
+    ) {
+        let child_time = self.child_time.pop().unwrap_or_default();
+        let parent_child_time = self.child_time.last_mut();
+        let Some(key) = key else {
+            // The event is left out, so its children are counted as children
+            // of its parent instead.
+            if let Some(parent_child_time) = parent_child_time {
+                *parent_child_time += child_time;
+            }
+            return;
+        };
+        if let Some(parent_child_time) = parent_child_time {
+            *parent_child_time += duration;
+        }
+
+        let row = self.rows.entry(key).or_default();
+        row.self_time += duration.saturating_sub(child_time);
+        match kind {
+            SummaryEventKind::GenericActivity(_) | SummaryEventKind::Query => {
+                row.total_time += duration;
+                row.invocation_count += 1;
+            }
+            SummaryEventKind::QueryBlocked => row.blocked_time += duration,
+            SummaryEventKind::IncrCacheLoad => row.incremental_load_time += duration,
+            SummaryEventKind::IncrResultHashing => row.incremental_hashing_time += duration,
+        }
+    }
+
+    fn record_cache_hit(&mut self, invocation_id: u32) {
+        self.rows.entry(SummaryKey::QueryInvocation(invocation_id)).or_default().cache_hits += 1;
+    }
+}
+
+/// Distinguishes recorders, so that a thread doesn't keep adding to the
+/// totals it kept for the recorder of a previous session.
+static NEXT_RECORDER_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The totals this thread keeps for the last recorder it recorded an event
+    /// for, with the id of that recorder.
+    static THREAD_TOTALS: RefCell<Option<(usize, Arc<Mutex<ThreadTotals>>)>> = RefCell::new(None);
+}
+
+pub(super) struct SummaryRecorder {
+    id: usize,
+    format: SelfProfileSummaryFormat,
+    path: PathBuf,
+    /// The totals of every thread that recorded an event. Only locked when a
+    /// thread records its first event, and when the summary is written.
+    threads: Mutex<Vec<Arc<Mutex<ThreadTotals>>>>,
+    query_names: Mutex<FxHashMap<u32, &'static str>>,
+}
+
+/// Measures a single event for the summary. The event is recorded when this is dropped.
+pub(super) struct SummaryTimer<'a> {
+    recorder: &'a SummaryRecorder,
+    kind: SummaryEventKind,
+    invocation_id: Option<u32>,
+    start: Instant,
+}
+
+impl SummaryTimer<'_> {
+    pub(super) fn set_query_invocation_id(&mut self, query_invocation_id: QueryInvocationId) {
+        self.invocation_id = Some(query_invocation_id.0);
+    }
+}
+
+impl Drop for SummaryTimer<'_> {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed();
+        // Query events that were never associated with an invocation can't be
+        // attributed to any query, so they are left out.
+        let key = match self.kind {
+            SummaryEventKind::GenericActivity(label) => Some(SummaryKey::GenericActivity(label)),
+            _ => self.invocation_id.map(SummaryKey::QueryInvocation),
+        };
+        self.recorder.with_thread_totals(|totals| totals.finish_event(self.kind, key, duration));
+    }
+}
+
+impl SummaryRecorder {
+    pub(super) fn new(format: SelfProfileSummaryFormat, path: PathBuf) -> SummaryRecorder {
+        let path = path.with_extension(format!("summary.{}", format.extension()));
+        SummaryRecorder {
+            id: NEXT_RECORDER_ID.fetch_add(1, Ordering::Relaxed),
+            format,
+            path,
+            threads: Default::default(),
+            query_names: Default::default(),
+        }
+    }
+
+    fn with_thread_totals<R>(&self, f: impl FnOnce(&mut ThreadTotals) -> R) -> R {
+        THREAD_TOTALS.with(|thread_totals| {
+            let mut thread_totals = thread_totals.borrow_mut();
+            if !matches!(&*thread_totals, Some((id, _)) if *id == self.id) {
+                let totals = Arc::default();
+                self.threads.lock().push(Arc::clone(&totals));
+                *thread_totals = Some((self.id, totals));
+            }
+            let (_, totals) = thread_totals.as_ref().unwrap();
+            let mut totals = totals.lock();
+            f(&mut totals)
+        })
+    }
+
+    pub(super) fn start(&self, kind: SummaryEventKind) -> SummaryTimer<'_> {
+        self.with_thread_totals(|totals| totals.start_event());
+        SummaryTimer { recorder: self, kind, invocation_id: None, start: Instant::now() }
+    }
+
+    pub(super) fn record_cache_hit(&self, query_invocation_id: QueryInvocationId) {
+        self.with_thread_totals(|totals| totals.record_cache_hit(query_invocation_id.0));
+    }
+
+    pub(super) fn name_query_invocations(
+        &self,
+        query_name: &'static str,
+        query_invocation_ids: impl Iterator<Item = QueryInvocationId>,
+    ) {
+        let mut query_names = self.query_names.lock();
+        query_names.extend(query_invocation_ids.map(|id| (id.0, query_name)));
+    }
+
+    pub(super) fn write(&self) -> io::Result<()> {
+        let threads = self.threads.lock();
+        let threads: Vec<_> = threads.iter().map(|totals| totals.lock()).collect();
+        let rows = merge(threads.iter().map(|totals| &**totals), &self.query_names.lock());
+        let contents = match self.format {
+            SelfProfileSummaryFormat::Text => render_text(&rows),
+            SelfProfileSummaryFormat::Json => render_json(&rows),
+        };
+        fs::write(&self.path, contents)
+    }
+}
+
+/// Merges the totals of all threads into one row per query or generic activity.
+fn merge<'a>(
+    threads: impl Iterator<Item = &'a ThreadTotals>,
+    query_names: &FxHashMap<u32, &'static str>,
+) -> Vec<(&'static str, SummaryRow)> {
+    let mut rows: FxHashMap<&'static str, SummaryRow> = FxHashMap::default();
+    for totals in threads {
+        for (key, row) in &totals.rows {
+            let label = match *key {
+                SummaryKey::GenericActivity(label) => label,
+                SummaryKey::QueryInvocation(id) => {
+                    query_names.get(&id).copied().unwrap_or("<unknown>")
+                }
+            };
+            rows.entry(label).or_default().merge(row);
+        }
+    }
+
+    let mut rows: Vec<_> = rows.into_iter().collect();
+    rows.sort_by(|(a_label, a), (b_label, b)| {
+        b.self_time.cmp(&a.self_time).then_with(|| a_label.cmp(b_label))
+    });
+    rows
+}
+
+fn render_text(rows: &[(&'static str, SummaryRow)]) -> String {
+    let total_self_time: Duration = rows.iter().map(|(_, row)| row.self_time).sum();
+    let percent = |d: Duration| {
+        if total_self_time.is_zero() {
+            0.0
+        } else {
+            d.as_secs_f64() * 100.0 / total_self_time.as_secs_f64()
+        }
+    };
+
+    let header = [
+        "Item",
+        "Self time",
+        "% of total time",
+        "Time",
+        "Item count",
+        "Cache hits",
+        "Blocked time",
+        "Incremental load time",
+        "Incremental hashing time",
+    ];
+    let mut table: Vec<[String; 9]> = vec![header.map(str::to_owned)];
+    for (label, row) in rows {
+        table.push([
+            label.to_string(),
+            format!("{:.2?}", row.self_time),
+            format!("{:.3}", percent(row.self_time)),
+            format!("{:.2?}", row.total_time),
+            row.invocation_count.to_string(),
+            row.cache_hits.to_string(),
+            format!("{:.2?}", row.blocked_time),
+            format!("{:.2?}", row.incremental_load_time),
+            format!("{:.2?}", row.incremental_hashing_time),
+        ]);
+    }
+
+    let mut widths = [0; 9];
+    for row in &table {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let mut out = String::new();
+    for (i, row) in table.iter().enumerate() {
+        for (column, (cell, width)) in row.iter().zip(widths).enumerate() {
+            if column == 0 {
+                write!(out, "| {cell:<width$} ").unwrap();
+            } else {
+                write!(out, "| {cell:>width$} ").unwrap();
+            }
+        }
+        out.push_str("|\n");
+        if i == 0 {
+            for width in widths {
+                write!(out, "+-{}-", "-".repeat(width)).unwrap();
+            }
+            out.push_str("+\n");
+        }
+    }
+    writeln!(out, "\nTotal cpu time: {:?}", total_self_time).unwrap();
+    out
+}
+
+/// The JSON object written by `-Z self-profile-summary=json`.
+#[derive(Serialize)]
+struct JsonSummary<'a> {
+    total_time_ns: u64,
+    items: Vec<JsonSummaryItem<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonSummaryItem<'a> {
+    label: &'a str,
+    self_time_ns: u64,
+    total_time_ns: u64,
+    invocation_count: usize,
+    cache_hits: usize,
+    blocked_time_ns: u64,
+    incremental_load_time_ns: u64,
+    incremental_hashing_time_ns: u64,
+}
+
+fn render_json(rows: &[(&'static str, SummaryRow)]) -> String {
+    // A `u64` of nanoseconds covers more than 500 years.
+    let nanos = |d: Duration| d.as_nanos() as u64;
+    let total_self_time: Duration = rows.iter().map(|(_, row)| row.self_time).sum();
+    let summary = JsonSummary {
+        total_time_ns: nanos(total_self_time),
+        items: rows
+            .iter()
+            .map(|(label, row)| JsonSummaryItem {
+                label,
+                self_time_ns: nanos(row.self_time),
+                total_time_ns: nanos(row.total_time),
+                invocation_count: row.invocation_count,
+                cache_hits: row.cache_hits,
+                blocked_time_ns: nanos(row.blocked_time),
+                incremental_load_time_ns: nanos(row.incremental_load_time),
+                incremental_hashing_time_ns: nanos(row.incremental_hashing_time),
+            })
+            .collect(),
+    };
+    let mut out = serde_json::to_string(&summary).unwrap();
+    out.push('\n');
+    out
+}
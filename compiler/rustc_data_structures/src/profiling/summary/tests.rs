@@ -0,0 +1,140 @@
+use super::*;
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn activity(label: &'static str) -> (SummaryEventKind, Option<SummaryKey>) {
+    (SummaryEventKind::GenericActivity(label), Some(SummaryKey::GenericActivity(label)))
+}
+
+fn query_event(
+    kind: SummaryEventKind,
+    invocation_id: Option<u32>,
+) -> (SummaryEventKind, Option<SummaryKey>) {
+    (kind, invocation_id.map(SummaryKey::QueryInvocation))
+}
+
+fn finish(totals: &mut ThreadTotals, (kind, key): (SummaryEventKind, Option<SummaryKey>), d: u64) {
+    totals.finish_event(kind, key, ms(d));
+}
+
+#[test]
+fn test_merge() {
+    let mut thread0 = ThreadTotals::default();
+    thread0.start_event(); // outer
+    thread0.start_event(); // typeck
+    thread0.start_event(); // type_of
+    finish(&mut thread0, query_event(SummaryEventKind::Query, Some(2)), 10);
+    finish(&mut thread0, query_event(SummaryEventKind::Query, Some(1)), 30);
+    thread0.start_event();
+    finish(&mut thread0, query_event(SummaryEventKind::QueryBlocked, Some(1)), 10);
+    thread0.start_event();
+    finish(&mut thread0, query_event(SummaryEventKind::IncrCacheLoad, Some(2)), 2);
+    finish(&mut thread0, activity("outer"), 100);
+    thread0.record_cache_hit(2);
+    thread0.record_cache_hit(2);
+
+    let mut thread1 = ThreadTotals::default();
+    thread1.start_event(); // other
+    thread1.start_event(); // a query without an invocation id
+    thread1.start_event(); // type_of
+    finish(&mut thread1, query_event(SummaryEventKind::Query, Some(2)), 10);
+    finish(&mut thread1, query_event(SummaryEventKind::Query, None), 15);
+    finish(&mut thread1, activity("other"), 20);
+    thread1.record_cache_hit(3);
+    assert!(thread0.child_time.is_empty() && thread1.child_time.is_empty());
+
+    let query_names = [(1, "typeck"), (2, "type_of")].into_iter().collect();
+    let rows = merge([&thread0, &thread1].into_iter(), &query_names);
+
+    let labels: Vec<_> = rows.iter().map(|(label, _)| *label).collect();
+    assert_eq!(labels, ["outer", "typeck", "type_of", "other", "<unknown>"]);
+
+    // The time spent in `typeck` and waiting for it is not part of `outer`'s self time.
+    let outer = &rows[0].1;
+    assert_eq!(outer.self_time, ms(58));
+    assert_eq!(outer.total_time, ms(100));
+    assert_eq!(outer.invocation_count, 1);
+
+    // `type_of` runs inside `typeck` on thread 0.
+    let typeck = &rows[1].1;
+    assert_eq!(typeck.self_time, ms(30));
+    assert_eq!(typeck.total_time, ms(30));
+    assert_eq!(typeck.blocked_time, ms(10));
+    assert_eq!(typeck.invocation_count, 1);
+
+    let type_of = &rows[2].1;
+    assert_eq!(type_of.self_time, ms(22));
+    assert_eq!(type_of.total_time, ms(20));
+    assert_eq!(type_of.incremental_load_time, ms(2));
+    assert_eq!(type_of.invocation_count, 2);
+    assert_eq!(type_of.cache_hits, 2);
+
+    // The query that is left out doesn't count, but its child still does.
+    let other = &rows[3].1;
+    assert_eq!(other.self_time, ms(10));
+    assert_eq!(other.invocation_count, 1);
+
+    // Cache hits of a query that was never named.
+    let unknown = &rows[4].1;
+    assert_eq!(unknown.cache_hits, 1);
+    assert_eq!(unknown.invocation_count, 0);
+}
+
+#[test]
+fn test_render_json_escaping() {
+    let label = "a \"quoted\" \\ label\n\twith \u{1} control characters";
+    let row = SummaryRow { self_time: ms(3), invocation_count: 2, ..Default::default() };
+    let json = render_json(&[(label, row)]);
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["total_time_ns"], 3_000_000);
+    assert_eq!(value["items"][0]["label"], label);
+    assert_eq!(value["items"][0]["self_time_ns"], 3_000_000);
+    assert_eq!(value["items"][0]["invocation_count"], 2);
+}
+
+#[test]
+fn test_render_text() {
+    let row = SummaryRow { self_time: ms(3), invocation_count: 2, ..Default::default() };
+    let text = render_text(&[("typeck", row)]);
+
+    let lines: Vec<_> = text.lines().collect();
+    assert!(lines[0].starts_with("| Item   | Self time | % of total time |"));
+    assert!(lines[1].starts_with("+--------+-----------+-----------------+"));
+    assert!(lines[2].starts_with("| typeck |    3.00ms |         100.000 |"));
+    assert_eq!(lines[4], "Total cpu time: 3ms");
+}
+
+#[test]
+fn test_recorder_threads() {
+    let dir = tempfile::tempdir().unwrap();
+    let recorder = SummaryRecorder::new(SelfProfileSummaryFormat::Json, dir.path().join("crate"));
+    recorder.name_query_invocations("typeck", [QueryInvocationId(1)].into_iter());
+
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            let recorder = &recorder;
+            s.spawn(move || {
+                drop(recorder.start(SummaryEventKind::GenericActivity("activity")));
+                let mut timer = recorder.start(SummaryEventKind::Query);
+                timer.set_query_invocation_id(QueryInvocationId(1));
+                drop(timer);
+                // Not attributed to any query, so left out.
+                drop(recorder.start(SummaryEventKind::Query));
+                recorder.record_cache_hit(QueryInvocationId(1));
+            });
+        }
+    });
+    recorder.write().unwrap();
+
+    let json = std::fs::read_to_string(dir.path().join("crate.summary.json")).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let items = value["items"].as_array().unwrap();
+    let item = |label: &str| items.iter().find(|item| item["label"] == label).unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(item("activity")["invocation_count"], 4);
+    assert_eq!(item("typeck")["invocation_count"], 4);
+    assert_eq!(item("typeck")["cache_hits"], 4);
+}
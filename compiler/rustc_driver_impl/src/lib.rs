@@ -30,6 +30,8 @@
 use rustc_interface::{interface, Queries};
 use rustc_lint::LintStore;
 use rustc_metadata::locator;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
 use rustc_save_analysis as save;
 use rustc_save_analysis::DumpHandler;
 use rustc_session::config::{nightly_options, CG_OPTIONS, Z_OPTIONS};
@@ -42,8 +44,10 @@
 use rustc_span::source_map::{FileLoader, FileName};
 use rustc_span::symbol::sym;
 use rustc_target::json::ToJson;
+use rustc_target::spec::{Target, TargetTriple};
 
 use std::cmp::max;
+use std::collections::BTreeMap;
 use std::env;
 use std::ffi::OsString;
 use std::fs;
@@ -329,6 +333,11 @@ fn run_compiler(
                 return early_exit();
             }
 
+            if sess.opts.prints.iter().any(|req| req.needs_expansion()) {
+                queries.global_ctxt()?.enter(|tcx| print_expanded_crate_info(tcx));
+                return early_exit();
+            }
+
             // Make sure the `output_filenames` query is run for its side
             // effects of writing the dep-info and reporting errors.
             queries.global_ctxt()?.enter(|tcx| tcx.output_filenames(()));
@@ -345,6 +354,7 @@ fn run_compiler(
 
             queries.global_ctxt()?.enter(|tcx| {
                 let result = tcx.analysis(());
+                tcx.dump_trait_selection_tree();
                 if sess.opts.unstable_opts.save_analysis {
                     let crate_name = tcx.crate_name(LOCAL_CRATE);
                     sess.time("save_analysis", || {
@@ -370,6 +380,10 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if let Some(n) = sess.opts.unstable_opts.time_items {
+                queries.global_ctxt()?.enter(|tcx| print_item_timings(tcx, n));
+            }
+
             let linker = queries.linker()?;
             Ok(Some(linker))
         })?;
@@ -598,6 +612,9 @@ fn print_crate_info(
     if sess.opts.prints.iter().all(|&p| p == NativeStaticLibs || p == LinkArgs) {
         return Compilation::Continue;
     }
+    // Deps and Features need the expanded crate, so they are printed by
+    // `print_expanded_crate_info` once it's available.
+    let needs_expansion = sess.opts.prints.iter().any(|p| p.needs_expansion());
 
     let attrs = if parse_attrs {
         let result = parse_crate_attrs(sess);
@@ -623,6 +640,25 @@ fn print_crate_info(
             TargetSpec => {
                 println!("{}", serde_json::to_string_pretty(&sess.target.to_json()).unwrap());
             }
+            AllTargetSpecs => {
+                let mut targets = BTreeMap::new();
+                for name in rustc_target::spec::TARGETS {
+                    let triple = TargetTriple::from_triple(name);
+                    let target = Target::expect_builtin(&triple);
+                    targets.insert(name, target.to_json());
+                }
+                println!("{}", serde_json::to_string_pretty(&targets).unwrap());
+            }
+            AvailableLocales => {
+                let sysroot_candidates = rustc_session::filesearch::sysroot_candidates();
+                let user_provided_sysroot = sess.opts.maybe_sysroot.as_deref();
+                for locale in
+                    rustc_errors::available_locales(user_provided_sysroot, &sysroot_candidates)
+                {
+                    println!("{locale}");
+                }
+            }
+            IncrementalCacheStats => rustc_incremental::print_cache_stats(sess),
             FileNames | CrateName => {
                 let attrs = attrs.as_ref().unwrap();
                 let t_outputs = rustc_interface::util::build_output_filenames(attrs, sess);
@@ -672,6 +708,33 @@ fn print_crate_info(
                     println!("{cfg}");
                 }
             }
+            CheckCfg => {
+                // Printed in the syntax of `--check-cfg`, so the output can be passed back in.
+                let check_cfg = &sess.parse_sess.check_config;
+                let mut check_cfgs = vec![];
+                if let Some(names_valid) = &check_cfg.names_valid {
+                    // INSTABILITY: We are sorting the output below.
+                    #[allow(rustc::potential_query_instability)]
+                    for name in names_valid {
+                        check_cfgs.push(format!("names({name})"));
+                    }
+                }
+                // INSTABILITY: We are sorting the output below.
+                #[allow(rustc::potential_query_instability)]
+                for (name, values) in &check_cfg.values_valid {
+                    let mut values = values
+                        .iter()
+                        .map(|value| format!(", {:?}", value.as_str()))
+                        .collect::<Vec<_>>();
+                    values.sort();
+                    check_cfgs.push(format!("values({name}{})", values.concat()));
+                }
+
+                check_cfgs.sort();
+                for check_cfg in check_cfgs {
+                    println!("{check_cfg}");
+                }
+            }
             CallingConventions => {
                 let mut calling_conventions = rustc_target::spec::abi::all_names();
                 calling_conventions.sort_unstable();
@@ -688,6 +751,8 @@ fn print_crate_info(
             // Any output here interferes with Cargo's parsing of other printed output
             NativeStaticLibs => {}
             LinkArgs => {}
+            // Printed by `print_expanded_crate_info`
+            Deps | Features => {}
             SplitDebuginfo => {
                 use rustc_target::spec::SplitDebuginfo::{Off, Packed, Unpacked};
 
@@ -701,7 +766,63 @@ fn print_crate_info(
             }
         }
     }
-    Compilation::Stop
+    if needs_expansion { Compilation::Continue } else { Compilation::Stop }
+}
+
+/// Prints the `n` items that took the longest to type check, borrow check and optimize,
+/// for `-Z time-items`.
+fn print_item_timings(tcx: TyCtxt<'_>, n: usize) {
+    tcx.sess.item_timings.print(n, |def_id| {
+        let path = with_no_trimmed_paths!(tcx.def_path_str(def_id.to_def_id()));
+        let span = tcx.sess.source_map().span_to_embeddable_string(tcx.def_span(def_id));
+        format!("{path} ({span})")
+    });
+}
+
+/// Handles the print requests that need the crate to be expanded and its
+/// dependencies to be loaded. See `PrintRequest::needs_expansion`.
+fn print_expanded_crate_info(tcx: TyCtxt<'_>) {
+    use rustc_session::config::PrintRequest::*;
+    for req in &tcx.sess.opts.prints {
+        match *req {
+            Deps => {
+                let mut deps = tcx
+                    .crates(())
+                    .iter()
+                    .map(|&cnum| {
+                        let source = tcx.used_crate_source(cnum);
+                        let path = source
+                            .paths()
+                            .next()
+                            .map_or_else(|| "-".to_string(), |path| path.display().to_string());
+                        (tcx.crate_name(cnum), tcx.crate_hash(cnum), path)
+                    })
+                    .collect::<Vec<_>>();
+
+                deps.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()).then_with(|| a.2.cmp(&b.2)));
+                for (name, svh, path) in deps {
+                    println!("{name} {svh} {path}");
+                }
+            }
+            Features => {
+                let features = tcx.features();
+                let mut names = features
+                    .declared_lang_features
+                    .iter()
+                    .map(|&(name, ..)| name)
+                    .chain(features.declared_lib_features.iter().map(|&(name, _)| name))
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>();
+
+                names.sort();
+                names.dedup();
+                for name in names {
+                    println!("{name}");
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 /// Prints version information
@@ -116,3 +116,12 @@ incremental_copy_workproduct_to_cache =
     error copying object file `{$from}` to incremental directory as `{$to}`: {$err}
 
 incremental_delete_workproduct = file-system error deleting outdated file `{$path}`: {$err}
+
+incremental_cache_limit_failed =
+    failed to limit the size of the incremental compilation directory `{$path}`: {$err}
+
+incremental_evict_failed =
+    failed to evict `{$path}` from the incremental compilation directory: {$err}
+
+incremental_cache_stats_failed =
+    failed to read the incremental compilation directory `{$path}`: {$err}
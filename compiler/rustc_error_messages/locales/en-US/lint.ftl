@@ -473,6 +473,8 @@ lint_node_source = `forbid` level set here
 
 lint_command_line_source = `forbid` lint level was set on command line
 
+lint_lint_config_source = `forbid` lint level was set in the `--lint-config` file
+
 lint_malformed_attribute = malformed lint attribute input
 
 lint_bad_attribute_argument = bad attribute argument
@@ -488,6 +490,8 @@ lint_unsupported_group = `{$lint_group}` lint group is not supported with ´--fo
 
 lint_requested_level = requested on the command line with `{$level} {$lint_name}`
 
+lint_requested_level_lint_config = requested in the `--lint-config` file with `{$lint_name} = "{$level}"`
+
 lint_check_name_unknown = unknown lint: `{$lint_name}`
     .help = did you mean: `{$suggestion}`
 
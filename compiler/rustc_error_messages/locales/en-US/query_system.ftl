@@ -24,6 +24,10 @@ query_system_cycle_recursive_trait_alias = trait aliases cannot be recursive
 
 query_system_cycle_which_requires = ...which requires {$desc}...
 
+query_system_cycle_opaque_type = the hidden type of this opaque type is only known after type-checking the item which defines it; if the cycle requires an auto trait like `Send` of the opaque type, consider adding that trait to its bounds
+
+query_system_cycle_graph_write_failed = failed to write the query cycle graph to `{$path}`: {$error}
+
 query_system_query_overflow = queries overflow the depth limit!
     .help = consider increasing the recursion limit by adding a `#![recursion_limit = "{$suggested_limit}"]` attribute to your crate (`{$crate_name}`)
 
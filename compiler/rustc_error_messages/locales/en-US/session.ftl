@@ -93,3 +93,10 @@ session_invalid_int_literal_width = invalid width `{$width}` for integer literal
     .help = valid widths are 8, 16, 32, 64 and 128
 
 session_optimization_fuel_exhausted = optimization-fuel-exhausted: {$msg}
+
+session_suggestion_conflict = not applying suggestion because it overlaps with another suggestion
+    .label = conflicting suggestion for this code
+
+session_suggestions_file_modified = not applying suggestions to `{$path}` because it changed since it was compiled
+
+session_failed_to_apply_suggestions = failed to apply suggestions to `{$path}`: {$err}
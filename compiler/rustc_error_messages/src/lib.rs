@@ -160,12 +160,16 @@ fn from(mut errs: Vec<FluentError>) -> Self {
 /// Returns Fluent bundle with the user's locale resources from
 /// `$sysroot/share/locale/$requested_locale/*.ftl`.
 ///
+/// Messages that the requested locale doesn't translate are looked up in the less specific
+/// locales of its fallback chain (see [`locale_fallback_chain`]) before the built-in `en-US`
+/// messages are used.
+///
 /// If `-Z additional-ftl-path` was provided, load that resource and add it  to the bundle
 /// (overriding any conflicting messages).
 #[instrument(level = "trace")]
 pub fn fluent_bundle(
-    mut user_provided_sysroot: Option<PathBuf>,
-    mut sysroot_candidates: Vec<PathBuf>,
+    user_provided_sysroot: Option<PathBuf>,
+    sysroot_candidates: Vec<PathBuf>,
     requested_locale: Option<LanguageIdentifier>,
     additional_ftl_path: Option<&Path>,
     with_directionality_markers: bool,
@@ -196,37 +200,24 @@ pub fn fluent_bundle(
     // If the user requests the default locale then don't try to load anything.
     if !requested_fallback_locale && let Some(requested_locale) = requested_locale {
         let mut found_resources = false;
-        for sysroot in user_provided_sysroot.iter_mut().chain(sysroot_candidates.iter_mut()) {
-            sysroot.push("share");
-            sysroot.push("locale");
-            sysroot.push(requested_locale.to_string());
-            trace!(?sysroot);
-
-            if !sysroot.exists() {
-                trace!("skipping");
-                continue;
-            }
-
-            if !sysroot.is_dir() {
-                return Err(TranslationBundleError::LocaleIsNotDir);
-            }
-
-            for entry in sysroot.read_dir().map_err(TranslationBundleError::ReadLocalesDir)? {
-                let entry = entry.map_err(TranslationBundleError::ReadLocalesDirEntry)?;
-                let path = entry.path();
-                trace!(?path);
-                if path.extension().and_then(|s| s.to_str()) != Some("ftl") {
-                    trace!("skipping");
-                    continue;
+        for (i, locale) in locale_fallback_chain(&requested_locale).iter().enumerate() {
+            for sysroot in user_provided_sysroot.iter().chain(sysroot_candidates.iter()) {
+                let locale_dir = sysroot.join("share").join("locale").join(locale.to_string());
+                for resource in read_locale_dir(&locale_dir)? {
+                    trace!(?resource);
+                    if let Err(errs) = bundle.add_resource(resource) {
+                        // Messages of the less specific locales are only used where the more
+                        // specific locales that were loaded first don't translate them.
+                        let errs: Vec<_> = errs
+                            .into_iter()
+                            .filter(|e| i == 0 || !matches!(e, FluentError::Overriding { .. }))
+                            .collect();
+                        if !errs.is_empty() {
+                            return Err(errs.into());
+                        }
+                    }
+                    found_resources = true;
                 }
-
-                let resource_str =
-                    fs::read_to_string(path).map_err(TranslationBundleError::ReadFtl)?;
-                let resource =
-                    FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)?;
-                trace!(?resource);
-                bundle.add_resource(resource).map_err(TranslationBundleError::from)?;
-                found_resources = true;
             }
         }
 
@@ -248,6 +239,139 @@ pub fn fluent_bundle(
     Ok(Some(bundle))
 }
 
+/// Reads the `.ftl` files of `$sysroot/share/locale/$locale`, in the order of their names. A
+/// missing directory has no resources.
+fn read_locale_dir(locale_dir: &Path) -> Result<Vec<FluentResource>, TranslationBundleError> {
+    trace!(?locale_dir);
+    if !locale_dir.exists() {
+        trace!("skipping");
+        return Ok(Vec::new());
+    }
+
+    if !locale_dir.is_dir() {
+        return Err(TranslationBundleError::LocaleIsNotDir);
+    }
+
+    let mut paths = Vec::new();
+    for entry in locale_dir.read_dir().map_err(TranslationBundleError::ReadLocalesDir)? {
+        let entry = entry.map_err(TranslationBundleError::ReadLocalesDirEntry)?;
+        let path = entry.path();
+        trace!(?path);
+        if is_ftl_file(&path) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let resource_str = fs::read_to_string(path).map_err(TranslationBundleError::ReadFtl)?;
+            FluentResource::try_new(resource_str).map_err(TranslationBundleError::from)
+        })
+        .collect()
+}
+
+fn is_ftl_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some("ftl")
+}
+
+/// Returns `locale` followed by the less specific locales that provide the messages it doesn't
+/// translate, e.g. `zh-Hans-CN`, `zh-Hans` and `zh`. The built-in `en-US` messages are the
+/// fallback for all of them and aren't part of the chain.
+pub fn locale_fallback_chain(locale: &LanguageIdentifier) -> Vec<LanguageIdentifier> {
+    let mut chain = vec![locale.clone()];
+    let mut locale = locale.clone();
+    locale.clear_variants();
+    chain.push(locale.clone());
+    locale.region = None;
+    chain.push(locale.clone());
+    locale.script = None;
+    chain.push(locale);
+    chain.dedup();
+    chain
+}
+
+/// Returns the locales that have translations in `$sysroot/share/locale` of any of the
+/// sysroots, sorted and without duplicates. `en-US` is always available since its messages are
+/// built into the compiler.
+pub fn available_locales(
+    user_provided_sysroot: Option<&Path>,
+    sysroot_candidates: &[PathBuf],
+) -> Vec<LanguageIdentifier> {
+    let mut locales = vec![langid!("en-US")];
+    for sysroot in user_provided_sysroot.into_iter().chain(sysroot_candidates.iter().map(|p| &**p))
+    {
+        let Ok(entries) = sysroot.join("share").join("locale").read_dir() else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let has_ftl_files = path
+                .read_dir()
+                .map_or(false, |files| files.flatten().any(|file| is_ftl_file(&file.path())));
+            if has_ftl_files
+                && let Some(name) = path.file_name().and_then(|name| name.to_str())
+                && let Ok(locale) = name.parse::<LanguageIdentifier>()
+            {
+                locales.push(locale);
+            }
+        }
+    }
+    locales.sort_by_cached_key(|locale| locale.to_string());
+    locales.dedup();
+    locales
+}
+
+/// Returns the locale that the environment requests for messages, from the first of `LC_ALL`,
+/// `LC_MESSAGES` and `LANG` that is set. POSIX locale names like `zh_CN.UTF-8` are accepted, the
+/// `C` and `POSIX` locales don't request any language.
+pub fn locale_from_env() -> Option<LanguageIdentifier> {
+    let value = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))?;
+    // `language[_territory][.codeset][@modifier]`
+    let name = value.split(['.', '@']).next()?;
+    if name == "C" || name == "POSIX" {
+        return None;
+    }
+    name.replace('_', "-").parse().ok()
+}
+
+/// Returns the most specific locale in the fallback chain of `requested` that is `available`, or
+/// `None` if the built-in `en-US` messages would be used anyway.
+pub fn negotiate_locale(
+    requested: &LanguageIdentifier,
+    available: &[LanguageIdentifier],
+) -> Option<LanguageIdentifier> {
+    locale_fallback_chain(requested)
+        .into_iter()
+        .find(|locale| available.contains(locale))
+        .filter(|locale| *locale != langid!("en-US"))
+}
+
+/// Returns the ids of the messages and attributes in `resources` that `bundle` doesn't
+/// translate, as `message` or `message.attribute`.
+pub fn missing_messages(bundle: &FluentBundle, resources: &[&str]) -> Vec<String> {
+    let mut missing = Vec::new();
+    for resource in resources {
+        let resource = FluentResource::try_new(resource.to_string())
+            .expect("failed to parse fallback fluent resource");
+        for entry in resource.entries() {
+            let fluent_syntax::ast::Entry::Message(message) = entry else { continue };
+            let id = message.id.name;
+            let Some(translated) = bundle.get_message(id) else {
+                missing.push(id.to_string());
+                continue;
+            };
+            for attr in &message.attributes {
+                if translated.get_attribute(attr.id.name).is_none() {
+                    missing.push(format!("{id}.{}", attr.id.name));
+                }
+            }
+        }
+    }
+    missing
+}
+
 fn register_functions(bundle: &mut FluentBundle) {
     bundle
         .add_function("STREQ", |positional, _named| match positional {
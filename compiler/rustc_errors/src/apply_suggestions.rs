@@ -0,0 +1,209 @@
+//! Applying structured suggestions to the source files they refer to.
+//!
+//! This backs `-Z apply-suggestions`: the `Handler` collects the suggestions of
+//! every emitted diagnostic whose applicability is good enough, and at the end of
+//! the session they are turned into per-file [`FileFix`]es here. Suggestions that
+//! overlap a suggestion accepted earlier are rejected as conflicts instead of
+//! being applied on top of each other.
+
+use crate::CodeSuggestion;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, Pos, SourceFile, Span};
+
+use std::fmt::Write;
+use std::ops::Range;
+use std::path::PathBuf;
+
+#[cfg(test)]
+mod tests;
+
+/// A single replacement within a file, in byte offsets into the file as stored on disk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Replacement {
+    range: Range<usize>,
+    snippet: String,
+}
+
+impl Replacement {
+    fn overlaps(&self, other: &Replacement) -> bool {
+        if self.range.is_empty() && other.range.is_empty() {
+            // Two insertions at the same position can't be ordered reliably.
+            return self.range.start == other.range.start;
+        }
+        self.range.start < other.range.end && other.range.start < self.range.end
+    }
+}
+
+/// The fixed contents of a single source file.
+pub struct FileFix {
+    pub path: PathBuf,
+    pub original: String,
+    pub fixed: String,
+    replacements: Vec<Replacement>,
+}
+
+/// The result of planning which suggestions to apply.
+#[derive(Default)]
+pub struct FixPlan {
+    pub files: Vec<FileFix>,
+    /// Primary spans of suggestions that were skipped because they overlap another one.
+    pub conflicts: Vec<Span>,
+    /// Files whose contents on disk no longer match what was compiled.
+    pub modified_files: Vec<PathBuf>,
+}
+
+struct PendingFile {
+    source_file: Lrc<SourceFile>,
+    replacements: Vec<Replacement>,
+}
+
+/// Resolves the parts of `suggestion` to replacements in local files. Returns `None` if
+/// the suggestion can't be applied at all, e.g. because it points into an external crate
+/// or offers several alternatives.
+fn resolve_suggestion(
+    sm: &SourceMap,
+    suggestion: &CodeSuggestion,
+) -> Option<Vec<(PathBuf, Lrc<SourceFile>, Replacement)>> {
+    // With more than one substitution there's no way of telling which one the user wants.
+    let [substitution] = &suggestion.substitutions[..] else { return None };
+    substitution
+        .parts
+        .iter()
+        .map(|part| {
+            if part.span.is_dummy() {
+                return None;
+            }
+            let source_file = sm.lookup_source_file(part.span.lo());
+            let FileName::Real(ref name) = source_file.name else { return None };
+            let path = name.local_path()?.to_path_buf();
+            let lo = source_file.original_relative_byte_pos(part.span.lo()).to_usize();
+            let hi = source_file.original_relative_byte_pos(part.span.hi()).to_usize();
+            Some((path, source_file, Replacement { range: lo..hi, snippet: part.snippet.clone() }))
+        })
+        .collect()
+}
+
+/// Decides which of `suggestions` can be applied together and computes the fixed contents
+/// of every affected file. Nothing is written to disk.
+pub fn plan_fixes(sm: &SourceMap, suggestions: &[CodeSuggestion]) -> FixPlan {
+    let mut plan = FixPlan::default();
+    let mut pending: FxIndexMap<PathBuf, PendingFile> = Default::default();
+
+    for suggestion in suggestions {
+        let Some(parts) = resolve_suggestion(sm, suggestion) else { continue };
+
+        let mut new_parts = vec![];
+        let mut conflict = false;
+        for (i, (path, source_file, replacement)) in parts.iter().enumerate() {
+            let existing = pending.get(path).map_or(&[][..], |file| &file.replacements[..]);
+            if existing.contains(replacement) {
+                // The same suggestion is often emitted several times, e.g. for every
+                // expansion of a macro. Applying it once is enough.
+                continue;
+            }
+            let overlaps_sibling = parts[..i]
+                .iter()
+                .any(|(other_path, _, other)| other_path == path && other.overlaps(replacement));
+            if overlaps_sibling || existing.iter().any(|other| other.overlaps(replacement)) {
+                conflict = true;
+                break;
+            }
+            new_parts.push((path, source_file, replacement));
+        }
+
+        if conflict {
+            plan.conflicts.push(suggestion.substitutions[0].parts[0].span);
+            continue;
+        }
+        for (path, source_file, replacement) in new_parts {
+            pending
+                .entry(path.clone())
+                .or_insert_with(|| PendingFile {
+                    source_file: source_file.clone(),
+                    replacements: vec![],
+                })
+                .replacements
+                .push(replacement.clone());
+        }
+    }
+
+    for (path, mut file) in pending {
+        let Ok(original) = std::fs::read_to_string(&path) else {
+            plan.modified_files.push(path);
+            continue;
+        };
+        if !file.source_file.src_hash.matches(&original) {
+            plan.modified_files.push(path);
+            continue;
+        }
+
+        file.replacements.sort_by_key(|replacement| replacement.range.start);
+        let mut fixed = String::with_capacity(original.len());
+        let mut last = 0;
+        for replacement in &file.replacements {
+            fixed.push_str(&original[last..replacement.range.start]);
+            fixed.push_str(&replacement.snippet);
+            last = replacement.range.end;
+        }
+        fixed.push_str(&original[last..]);
+
+        plan.files.push(FileFix { path, original, fixed, replacements: file.replacements });
+    }
+
+    plan
+}
+
+impl FileFix {
+    /// Renders the changes to this file as a unified diff without context lines.
+    pub fn unified_diff(&self) -> String {
+        let src = &self.original[..];
+        let line_start = |pos: usize| src[..pos].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = |pos: usize| src[pos..].find('\n').map_or(src.len(), |i| pos + i + 1);
+        let line_count = |s: &str| s.lines().count();
+
+        // Group replacements that touch the same lines into hunks.
+        let mut hunks: Vec<(Range<usize>, Vec<&Replacement>)> = vec![];
+        for replacement in &self.replacements {
+            let lines = line_start(replacement.range.start)..line_end(replacement.range.end);
+            match hunks.last_mut() {
+                Some((hunk_lines, members)) if lines.start < hunk_lines.end => {
+                    hunk_lines.end = hunk_lines.end.max(lines.end);
+                    members.push(replacement);
+                }
+                _ => hunks.push((lines, vec![replacement])),
+            }
+        }
+
+        let path = self.path.display();
+        let mut diff = format!("--- {path}\n+++ {path}\n");
+        let mut line_delta: isize = 0;
+        for (lines, members) in hunks {
+            let old = &src[lines.clone()];
+            let mut new = String::new();
+            let mut last = lines.start;
+            for replacement in members {
+                new.push_str(&src[last..replacement.range.start]);
+                new.push_str(&replacement.snippet);
+                last = replacement.range.end;
+            }
+            new.push_str(&src[last..lines.end]);
+
+            let old_start = src[..lines.start].matches('\n').count() + 1;
+            let new_start = (old_start as isize + line_delta) as usize;
+            let (old_len, new_len) = (line_count(old), line_count(&new));
+            line_delta += new_len as isize - old_len as isize;
+
+            writeln!(diff, "@@ -{old_start},{old_len} +{new_start},{new_len} @@").unwrap();
+            for line in old.lines() {
+                writeln!(diff, "-{line}").unwrap();
+            }
+            for line in new.lines() {
+                writeln!(diff, "+{line}").unwrap();
+            }
+        }
+        diff
+    }
+}
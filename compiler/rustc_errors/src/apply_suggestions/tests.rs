@@ -0,0 +1,113 @@
+use super::*;
+
+use crate::{Applicability, Substitution, SubstitutionPart, SuggestionStyle};
+use rustc_span::source_map::FilePathMapping;
+use rustc_span::{BytePos, DUMMY_SP};
+
+use std::path::Path;
+
+const SRC: &str = "fn main() {\n    let x = 1;\n    let y = 2;\n}\n";
+
+fn suggestion(substitutions: Vec<Vec<(Span, &str)>>) -> CodeSuggestion {
+    CodeSuggestion {
+        substitutions: substitutions
+            .into_iter()
+            .map(|parts| Substitution {
+                parts: parts
+                    .into_iter()
+                    .map(|(span, snippet)| SubstitutionPart { span, snippet: snippet.to_string() })
+                    .collect(),
+            })
+            .collect(),
+        msg: "test".into(),
+        style: SuggestionStyle::ShowCode,
+        applicability: Applicability::MachineApplicable,
+    }
+}
+
+/// Writes `SRC` to a file named after the test, loads it into a new source map and calls `f`
+/// with a function turning byte offsets in `SRC` into spans.
+fn with_source_file(name: &str, f: impl FnOnce(&SourceMap, &Path, &dyn Fn(u32, u32) -> Span)) {
+    rustc_span::create_default_session_globals_then(|| {
+        let path = std::env::temp_dir()
+            .join(format!("apply_suggestions_{}_{name}.rs", std::process::id()));
+        std::fs::write(&path, SRC).unwrap();
+        let sm = SourceMap::new(FilePathMapping::empty());
+        let start_pos = sm.load_file(&path).unwrap().start_pos;
+        let span = |lo, hi| Span::with_root_ctxt(start_pos + BytePos(lo), start_pos + BytePos(hi));
+        f(&sm, &path, &span);
+        std::fs::remove_file(&path).unwrap();
+    })
+}
+
+#[test]
+fn plan_and_diff() {
+    with_source_file("plan_and_diff", |sm, path, span| {
+        let rename = suggestion(vec![vec![(span(20, 21), "_x"), (span(35, 36), "_y")]]);
+        let suggestions = [
+            rename.clone(),
+            suggestion(vec![vec![(span(24, 25), "10")]]),
+            suggestion(vec![vec![(span(11, 11), "\n    // main")]]),
+            // Overlaps the first part of `rename`.
+            suggestion(vec![vec![(span(20, 25), "z = 1")]]),
+            // Inserts at the same position as an earlier insertion.
+            suggestion(vec![vec![(span(11, 11), " // main")]]),
+            // Its own parts overlap.
+            suggestion(vec![vec![(span(39, 40), "3"), (span(39, 40), "4")]]),
+            // Emitted twice, e.g. by a macro, but only applied once.
+            rename,
+            // Several alternatives, or a part outside of any file, are never applied.
+            suggestion(vec![vec![(span(39, 40), "3")], vec![(span(39, 40), "4")]]),
+            suggestion(vec![vec![(DUMMY_SP, "3")]]),
+        ];
+        let plan = plan_fixes(sm, &suggestions);
+
+        assert_eq!(plan.conflicts, [span(20, 25), span(11, 11), span(39, 40)]);
+        assert!(plan.modified_files.is_empty());
+        let [file] = &plan.files[..] else { panic!("expected one file") };
+        assert_eq!(file.path, path);
+        assert_eq!(file.original, SRC);
+        assert_eq!(file.fixed, "fn main() {\n    // main\n    let _x = 10;\n    let _y = 2;\n}\n");
+
+        // The replacements on the same line are in the same hunk, and the start of the later
+        // hunks in the fixed file accounts for the inserted line.
+        let path = path.display();
+        assert_eq!(
+            file.unified_diff(),
+            format!(
+                "--- {path}\n+++ {path}\n\
+                 @@ -1,1 +1,2 @@\n-fn main() {{\n+fn main() {{\n+    // main\n\
+                 @@ -2,1 +3,1 @@\n-    let x = 1;\n+    let _x = 10;\n\
+                 @@ -3,1 +4,1 @@\n-    let y = 2;\n+    let _y = 2;\n"
+            )
+        );
+    })
+}
+
+#[test]
+fn multiline_replacement_diff() {
+    with_source_file("multiline_replacement_diff", |sm, _, span| {
+        // Joins the two `let` lines, so a single hunk covers both of them.
+        let suggestions = [suggestion(vec![vec![(span(25, 34), "; let")]])];
+        let plan = plan_fixes(sm, &suggestions);
+
+        let [file] = &plan.files[..] else { panic!("expected one file") };
+        assert_eq!(file.fixed, "fn main() {\n    let x = 1; let y = 2;\n}\n");
+        assert!(file.unified_diff().ends_with(
+            "@@ -2,2 +2,1 @@\n-    let x = 1;\n-    let y = 2;\n+    let x = 1; let y = 2;\n"
+        ));
+    })
+}
+
+#[test]
+fn modified_file() {
+    with_source_file("modified_file", |sm, path, span| {
+        std::fs::write(path, "fn main() {}\n").unwrap();
+        let suggestions = [suggestion(vec![vec![(span(20, 21), "_x")]])];
+        let plan = plan_fixes(sm, &suggestions);
+
+        assert!(plan.files.is_empty());
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.modified_files, [path]);
+    })
+}
@@ -23,7 +23,7 @@
 use rustc_lint_defs::pluralize;
 
 use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
-use rustc_data_structures::sync::Lrc;
+use rustc_data_structures::sync::{self, Lrc};
 use rustc_error_messages::{FluentArgs, SpanLabel};
 use rustc_span::hygiene::{ExpnKind, MacroKind};
 use std::borrow::Cow;
@@ -604,6 +604,78 @@ fn emit_diagnostic(&mut self, d: &Diagnostic) {
     }
 }
 
+/// An emitter that forwards everything to several other emitters, e.g. to print
+/// human-readable diagnostics to the terminal while also writing JSON to a file.
+///
+/// The first emitter is the primary one: it decides whether explanations and
+/// colors are shown, and its bundles are used for translation.
+pub struct FanOutEmitter {
+    emitters: Vec<Box<dyn Emitter + sync::Send>>,
+}
+
+impl FanOutEmitter {
+    pub fn new(
+        primary: Box<dyn Emitter + sync::Send>,
+        others: Vec<Box<dyn Emitter + sync::Send>>,
+    ) -> Self {
+        let mut emitters = vec![primary];
+        emitters.extend(others);
+        FanOutEmitter { emitters }
+    }
+
+    fn primary(&self) -> &(dyn Emitter + sync::Send) {
+        &*self.emitters[0]
+    }
+}
+
+impl Translate for FanOutEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.primary().fluent_bundle()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        self.primary().fallback_fluent_bundle()
+    }
+}
+
+impl Emitter for FanOutEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        for emitter in &mut self.emitters {
+            emitter.emit_diagnostic(diag);
+        }
+    }
+
+    fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
+        for emitter in &mut self.emitters {
+            emitter.emit_artifact_notification(path, artifact_type);
+        }
+    }
+
+    fn emit_future_breakage_report(&mut self, diags: Vec<Diagnostic>) {
+        for emitter in &mut self.emitters {
+            emitter.emit_future_breakage_report(diags.clone());
+        }
+    }
+
+    fn emit_unused_externs(&mut self, lint_level: rustc_lint_defs::Level, unused_externs: &[&str]) {
+        for emitter in &mut self.emitters {
+            emitter.emit_unused_externs(lint_level, unused_externs);
+        }
+    }
+
+    fn should_show_explain(&self) -> bool {
+        self.primary().should_show_explain()
+    }
+
+    fn supports_color(&self) -> bool {
+        self.primary().supports_color()
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        self.primary().source_map()
+    }
+}
+
 /// Maximum number of suggestions to be shown
 ///
 /// Arbitrary, but taken from trait import suggestion limit
@@ -36,11 +36,13 @@
 use rustc_data_structures::sync::{self, Lock, Lrc};
 use rustc_data_structures::AtomicRef;
 pub use rustc_error_messages::{
-    fallback_fluent_bundle, fluent, fluent_bundle, DelayDm, DiagnosticMessage, FluentBundle,
+    available_locales, fallback_fluent_bundle, fluent, fluent_bundle, locale_from_env,
+    missing_messages, negotiate_locale, DelayDm, DiagnosticMessage, FluentBundle,
     LanguageIdentifier, LazyFallbackBundle, MultiSpan, SpanLabel, SubdiagnosticMessage,
     DEFAULT_LOCALE_RESOURCES,
 };
 pub use rustc_lint_defs::{pluralize, Applicability};
+use rustc_span::hygiene::ExpnKind;
 use rustc_span::source_map::SourceMap;
 use rustc_span::HashStableContext;
 use rustc_span::{Loc, Span};
@@ -56,6 +58,7 @@
 use termcolor::{Color, ColorSpec};
 
 pub mod annotate_snippet_emitter_writer;
+pub mod apply_suggestions;
 mod diagnostic;
 mod diagnostic_builder;
 mod diagnostic_impls;
@@ -64,6 +67,7 @@
 pub mod json;
 mod lock;
 pub mod registry;
+pub mod sarif;
 mod snippet;
 mod styled_buffer;
 #[cfg(test)]
@@ -437,6 +441,14 @@ struct HandlerInner {
 
     future_breakage_diagnostics: Vec<Diagnostic>,
 
+    /// Suggestions collected for `-Z apply-suggestions`, see [`HandlerFlags::apply_suggestions`].
+    applicable_suggestions: Vec<CodeSuggestion>,
+
+    /// Lints buffered for `-Z diagnostic-grouping`, see [`HandlerFlags::diagnostic_grouping`].
+    /// Keyed by the hash computed in `diagnostic_group_key`, each group holds the first
+    /// diagnostic, the number of times it was emitted and the distinct call sites.
+    grouped_diagnostics: FxIndexMap<u128, (Diagnostic, usize, Vec<Span>)>,
+
     /// The [`Self::unstable_expect_diagnostics`] should be empty when this struct is
     /// dropped. However, it can have values if the compilation is stopped early
     /// or is only partially executed. To avoid ICEs, like in rust#94953 we only
@@ -505,11 +517,38 @@ pub struct HandlerFlags {
     pub deduplicate_diagnostics: bool,
     /// Track where errors are created. Enabled with `-Ztrack-diagnostics`.
     pub track_diagnostics: bool,
+    /// If set, collect the suggestions of emitted diagnostics that are at least this
+    /// applicable so they can be applied at the end of the session.
+    /// (rustc: see `-Z apply-suggestions`)
+    pub apply_suggestions: Option<Applicability>,
+    /// If true, identical warning-level lints emitted from expansions of the same
+    /// macro are reported once at the end, listing all of their call sites.
+    /// (rustc: see `-Z diagnostic-grouping`)
+    pub diagnostic_grouping: bool,
+}
+
+/// Returns the key under which `-Z diagnostic-grouping` groups `diagnostic` along
+/// with the call site of the macro it originates from, or `None` if it isn't a
+/// warning-level lint emitted from a macro expansion.
+fn diagnostic_group_key(diagnostic: &Diagnostic) -> Option<(u128, Span)> {
+    if !diagnostic.is_lint || !matches!(diagnostic.level, Warning(_)) {
+        return None;
+    }
+    let span = diagnostic.span.primary_span()?;
+    let expn_data = span.ctxt().outer_expn_data();
+    let ExpnKind::Macro(..) = expn_data.kind else { return None };
+
+    let mut args: Vec<_> = diagnostic.args().collect();
+    args.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut hasher = StableHasher::new();
+    (&diagnostic.message, args, &diagnostic.code, expn_data.def_site).hash(&mut hasher);
+    Some((hasher.finish(), span.source_callsite()))
 }
 
 impl Drop for HandlerInner {
     fn drop(&mut self) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
 
         if !self.has_errors() {
             let bugs = std::mem::replace(&mut self.delayed_span_bugs, Vec::new());
@@ -610,6 +649,8 @@ pub fn with_emitter_and_flags(
                 emitted_diagnostics: Default::default(),
                 stashed_diagnostics: Default::default(),
                 future_breakage_diagnostics: Vec::new(),
+                applicable_suggestions: Vec::new(),
+                grouped_diagnostics: Default::default(),
                 check_unstable_expect_diagnostics: false,
                 unstable_expect_diagnostics: Vec::new(),
                 fulfilled_expectations: Default::default(),
@@ -662,6 +703,7 @@ pub fn reset_err_count(&self) {
         inner.emitted_diagnostic_codes = Default::default();
         inner.emitted_diagnostics = Default::default();
         inner.stashed_diagnostics = Default::default();
+        inner.grouped_diagnostics = Default::default();
     }
 
     /// Stash a given diagnostic with the given `Span` and [`StashKey`] as the key.
@@ -1098,6 +1140,16 @@ pub fn take_future_breakage_diagnostics(&self) -> Vec<Diagnostic> {
         std::mem::take(&mut self.inner.borrow_mut().future_breakage_diagnostics)
     }
 
+    /// Takes the suggestions collected for `-Z apply-suggestions`. The stashed diagnostics and the
+    /// lints buffered by `-Z diagnostic-grouping` are emitted first, so that their suggestions are
+    /// collected too.
+    pub fn take_applicable_suggestions(&self) -> Vec<CodeSuggestion> {
+        let mut inner = self.inner.borrow_mut();
+        inner.emit_stashed_diagnostics();
+        inner.emit_grouped_diagnostics();
+        std::mem::take(&mut inner.applicable_suggestions)
+    }
+
     pub fn abort_if_errors(&self) {
         self.inner.borrow_mut().abort_if_errors()
     }
@@ -1311,6 +1363,26 @@ fn emit_stashed_diagnostics(&mut self) -> Option<ErrorGuaranteed> {
         reported
     }
 
+    /// Emits the lints buffered by `-Z diagnostic-grouping`, once per group.
+    fn emit_grouped_diagnostics(&mut self) {
+        if self.grouped_diagnostics.is_empty() {
+            return;
+        }
+        // Don't group the diagnostics again while emitting them.
+        let grouping = std::mem::replace(&mut self.flags.diagnostic_grouping, false);
+        let groups = std::mem::take(&mut self.grouped_diagnostics);
+        for (_, (mut diag, count, call_sites)) in groups {
+            if count > 1 {
+                diag.span_note(
+                    MultiSpan::from_spans(call_sites),
+                    format!("this lint was emitted {count} times by expansions of the same macro"),
+                );
+            }
+            self.emit_diagnostic(&mut diag);
+        }
+        self.flags.diagnostic_grouping = grouping;
+    }
+
     // FIXME(eddyb) this should ideally take `diagnostic` by value.
     fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaranteed> {
         // The `LintExpectationId` can be stable or unstable depending on when it was created.
@@ -1364,6 +1436,20 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
             return None;
         }
 
+        if self.flags.diagnostic_grouping
+            && let Some((key, call_site)) = diagnostic_group_key(diagnostic)
+        {
+            let (_, count, call_sites) = self
+                .grouped_diagnostics
+                .entry(key)
+                .or_insert_with(|| (diagnostic.clone(), 0, vec![]));
+            *count += 1;
+            if !call_sites.contains(&call_site) {
+                call_sites.push(call_site);
+            }
+            return None;
+        }
+
         let mut guaranteed = None;
         (*TRACK_DIAGNOSTICS)(diagnostic, &mut |diagnostic| {
             if let Some(ref code) = diagnostic.code {
@@ -1397,6 +1483,16 @@ fn emit_diagnostic(&mut self, diagnostic: &mut Diagnostic) -> Option<ErrorGuaran
                 diagnostic.children.drain_filter(already_emitted_sub).for_each(|_| {});
 
                 self.emitter.emit_diagnostic(diagnostic);
+                if let Some(applicability) = self.flags.apply_suggestions
+                    && let Ok(suggestions) = &diagnostic.suggestions
+                {
+                    self.applicable_suggestions.extend(
+                        suggestions
+                            .iter()
+                            .filter(|sugg| sugg.applicability <= applicability)
+                            .cloned(),
+                    );
+                }
                 if diagnostic.is_error() {
                     self.deduplicated_err_count += 1;
                 } else if let Warning(_) = diagnostic.level {
@@ -1439,6 +1535,7 @@ fn delayed_bug_count(&self) -> usize {
 
     fn print_error_count(&mut self, registry: &Registry) {
         self.emit_stashed_diagnostics();
+        self.emit_grouped_diagnostics();
 
         let warnings = match self.deduplicated_warn_count {
             0 => String::new(),
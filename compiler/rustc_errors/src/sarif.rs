@@ -0,0 +1,544 @@
+//! A SARIF emitter for errors.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic as soon as
+//! it is emitted, a SARIF log is a single document describing the whole
+//! compilation. Diagnostics are therefore converted to SARIF `result`s as they
+//! arrive and the complete log is written out when the emitter is dropped.
+//!
+//! The output follows version 2.1.0 of the [SARIF specification].
+//!
+//! [SARIF specification]: https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::translation::{to_fluent_args, Translate};
+use crate::{
+    CodeSuggestion, DiagnosticId, FluentBundle, LazyFallbackBundle, Level, MultiSpan, SpanLabel,
+    SubDiagnostic,
+};
+use rustc_lint_defs::Applicability;
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_data_structures::sync::Lrc;
+use rustc_error_messages::FluentArgs;
+use rustc_span::Span;
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+pub struct SarifEmitter {
+    dst: Box<dyn Write + Send>,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+    fluent_bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+    pretty: bool,
+    /// One rule per distinct error code or lint name, in order of first appearance.
+    rules: FxIndexMap<String, ReportingDescriptor>,
+    results: Vec<SarifResult>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        pretty: bool,
+    ) -> SarifEmitter {
+        SarifEmitter::new(
+            Box::new(io::BufWriter::new(io::stderr())),
+            registry,
+            source_map,
+            fluent_bundle,
+            fallback_bundle,
+            pretty,
+        )
+    }
+
+    pub fn basic(
+        pretty: bool,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+    ) -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(
+            None,
+            Lrc::new(SourceMap::new(file_path_mapping)),
+            fluent_bundle,
+            fallback_bundle,
+            pretty,
+        )
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+        fluent_bundle: Option<Lrc<FluentBundle>>,
+        fallback_bundle: LazyFallbackBundle,
+        pretty: bool,
+    ) -> SarifEmitter {
+        SarifEmitter {
+            dst,
+            registry,
+            sm: source_map,
+            fluent_bundle,
+            fallback_bundle,
+            pretty,
+            rules: Default::default(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Returns the index of the rule describing `code`, registering it on first use.
+    fn rule_index(&mut self, code: &DiagnosticId) -> (String, usize) {
+        let id = match code {
+            DiagnosticId::Error(s) => s.clone(),
+            DiagnosticId::Lint { name, .. } => name.clone(),
+        };
+        if let Some(index) = self.rules.get_index_of(&id) {
+            return (id, index);
+        }
+
+        let rule = match code {
+            DiagnosticId::Error(s) => {
+                let explanation = self
+                    .registry
+                    .as_ref()
+                    .and_then(|registry| registry.try_find_description(s).ok().flatten());
+                ReportingDescriptor {
+                    id: id.clone(),
+                    full_description: explanation
+                        .map(|text| MultiformatMessageString { text: text.to_owned() }),
+                    help_uri: Some(format!("https://doc.rust-lang.org/error-index.html#{s}")),
+                    properties: RuleProperties { kind: "error-code" },
+                }
+            }
+            DiagnosticId::Lint { .. } => ReportingDescriptor {
+                id: id.clone(),
+                full_description: None,
+                help_uri: None,
+                properties: RuleProperties { kind: "lint" },
+            },
+        };
+        let (index, _) = self.rules.insert_full(id.clone(), rule);
+        (id, index)
+    }
+
+    fn write_log(&mut self) -> io::Result<()> {
+        let log = SarifLog {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: ToolComponent {
+                        name: "rustc",
+                        information_uri: "https://www.rust-lang.org/",
+                        rules: self.rules.values().collect(),
+                    },
+                },
+                column_kind: "unicodeCodePoints",
+                results: &self.results,
+            }],
+        };
+        if self.pretty {
+            writeln!(&mut self.dst, "{}", serde_json::to_string_pretty(&log).unwrap())
+        } else {
+            writeln!(&mut self.dst, "{}", serde_json::to_string(&log).unwrap())
+        }
+        .and_then(|_| self.dst.flush())
+    }
+}
+
+impl Translate for SarifEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        self.fluent_bundle.as_ref()
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        // Failure notes such as "aborting due to previous error" summarize the
+        // compilation rather than describe the code, so they have no place in the log.
+        if let Level::FailureNote = diag.level {
+            return;
+        }
+        let result = SarifResult::from_errors_diagnostic(diag, self);
+        self.results.push(result);
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        // Panicking in `drop` would abort if we are already unwinding, and the
+        // diagnostics have been reported by then anyway, so only warn about it.
+        if let Err(e) = self.write_log() {
+            eprintln!("failed to print SARIF log: {e}");
+        }
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(Serialize)]
+struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    /// rustc reports columns as offsets in characters, not UTF-16 code units.
+    column_kind: &'static str,
+    results: &'a [SarifResult],
+}
+
+#[derive(Serialize)]
+struct Tool<'a> {
+    driver: ToolComponent<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent<'a> {
+    name: &'static str,
+    information_uri: &'static str,
+    rules: Vec<&'a ReportingDescriptor>,
+}
+
+/// A SARIF rule, describing an error code or a lint.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<MultiformatMessageString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    properties: RuleProperties,
+}
+
+#[derive(Serialize)]
+struct RuleProperties {
+    /// Either "error-code" or "lint".
+    kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct MultiformatMessageString {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    /// "error", "warning", "note" or "none".
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    /// Locations mentioned by the secondary labels and subdiagnostics.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<Location>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<Fix>,
+    properties: ResultProperties,
+}
+
+#[derive(Serialize)]
+struct ResultProperties {
+    /// The rustc diagnostic level, e.g. "error: internal compiler error".
+    level: &'static str,
+    /// Messages of subdiagnostics that have no span of their own.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    notes: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    /// 1-based.
+    start_line: usize,
+    /// 1-based, character offset.
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: u32,
+    byte_length: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fix {
+    description: Message,
+    artifact_changes: Vec<ArtifactChange>,
+    properties: FixProperties,
+}
+
+#[derive(Serialize)]
+struct FixProperties {
+    applicability: Applicability,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactChange {
+    artifact_location: ArtifactLocation,
+    replacements: Vec<Replacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Replacement {
+    deleted_region: Region,
+    inserted_content: ArtifactContent,
+}
+
+#[derive(Serialize)]
+struct ArtifactContent {
+    text: String,
+}
+
+fn sarif_level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::DelayedBug | Level::Fatal | Level::Error { .. } => "error",
+        Level::Warning(_) => "warning",
+        Level::Note | Level::OnceNote | Level::Help | Level::FailureNote => "note",
+        Level::Allow | Level::Expect(_) => "none",
+    }
+}
+
+impl SarifResult {
+    fn from_errors_diagnostic(diag: &crate::Diagnostic, se: &mut SarifEmitter) -> SarifResult {
+        let args = to_fluent_args(diag.args());
+        let (rule_id, rule_index) = match diag.code.as_ref().map(|code| se.rule_index(code)) {
+            Some((id, index)) => (Some(id), Some(index)),
+            None => (None, None),
+        };
+
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for span_label in diag.span.span_labels() {
+            let is_primary = span_label.is_primary;
+            if let Some(location) = Location::from_span_label(span_label, &args, se) {
+                if is_primary {
+                    locations.push(location);
+                } else {
+                    related_locations.push(location);
+                }
+            }
+        }
+
+        let mut notes = vec![];
+        for child in &diag.children {
+            let child_locations = Location::from_sub_diagnostic(child, &args, se);
+            if child_locations.is_empty() {
+                let message = se.translate_messages(&child.message, &args);
+                notes.push(format!("{}: {}", child.level.to_str(), message));
+            } else {
+                related_locations.extend(child_locations);
+            }
+        }
+        for (id, location) in related_locations.iter_mut().enumerate() {
+            location.id = Some(id);
+        }
+
+        let fixes = diag
+            .suggestions
+            .iter()
+            .flatten()
+            .flat_map(|sugg| Fix::from_suggestion(sugg, &args, se))
+            .collect();
+
+        let message = se.translate_messages(&diag.message, &args).to_string();
+        SarifResult {
+            rule_id,
+            rule_index,
+            level: sarif_level(diag.level),
+            message: Message { text: message },
+            locations,
+            related_locations,
+            fixes,
+            properties: ResultProperties { level: diag.level.to_str(), notes },
+        }
+    }
+}
+
+impl Location {
+    fn from_span_label(
+        span: SpanLabel,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> Option<Location> {
+        let message = span
+            .label
+            .as_ref()
+            .map(|m| se.translate_message(m, args).unwrap())
+            .map(|m| Message { text: m.to_string() });
+        Some(Location {
+            id: None,
+            physical_location: PhysicalLocation::from_span(span.span, se)?,
+            message,
+        })
+    }
+
+    fn from_multispan(msp: &MultiSpan, args: &FluentArgs<'_>, se: &SarifEmitter) -> Vec<Location> {
+        msp.span_labels()
+            .into_iter()
+            .filter_map(|span_label| Self::from_span_label(span_label, args, se))
+            .collect()
+    }
+
+    /// Subdiagnostics become related locations, labelled with their own message
+    /// (e.g. "note: required by a bound in `foo`").
+    fn from_sub_diagnostic(
+        diag: &SubDiagnostic,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> Vec<Location> {
+        let message = se.translate_messages(&diag.message, args);
+        let message = format!("{}: {}", diag.level.to_str(), message);
+        let msp = diag.render_span.as_ref().unwrap_or(&diag.span);
+        let mut locations = Self::from_multispan(msp, args, se);
+        for location in &mut locations {
+            location.message = Some(Message {
+                text: match location.message.take() {
+                    Some(label) => format!("{message} ({})", label.text),
+                    None => message.clone(),
+                },
+            });
+        }
+        locations
+    }
+}
+
+impl PhysicalLocation {
+    fn from_span(span: Span, se: &SarifEmitter) -> Option<PhysicalLocation> {
+        if span.is_dummy() {
+            return None;
+        }
+        let start = se.sm.lookup_char_pos(span.lo());
+        Some(PhysicalLocation {
+            artifact_location: ArtifactLocation::for_file(&start.file.name, se),
+            region: Region::from_span(span, se),
+        })
+    }
+}
+
+impl ArtifactLocation {
+    fn for_file(name: &rustc_span::FileName, se: &SarifEmitter) -> ArtifactLocation {
+        // SARIF locations are URI references, which always use forward slashes.
+        let uri = se.sm.filename_for_diagnostics(name).to_string().replace('\\', "/");
+        ArtifactLocation { uri }
+    }
+}
+
+impl Region {
+    fn from_span(span: Span, se: &SarifEmitter) -> Region {
+        let start = se.sm.lookup_char_pos(span.lo());
+        let end = se.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        Region {
+            start_line: start.line,
+            start_column: start.col.0 + 1,
+            end_line: end.line,
+            end_column: end.col.0 + 1,
+            byte_offset: byte_start,
+            byte_length: byte_end - byte_start,
+        }
+    }
+}
+
+impl Fix {
+    /// Every substitution of a suggestion is an alternative way of fixing the
+    /// diagnostic, so each of them becomes a separate SARIF `fix`.
+    fn from_suggestion(
+        suggestion: &CodeSuggestion,
+        args: &FluentArgs<'_>,
+        se: &SarifEmitter,
+    ) -> Vec<Fix> {
+        let description = se.translate_message(&suggestion.msg, args).unwrap().to_string();
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                let mut changes: FxIndexMap<String, Vec<Replacement>> = Default::default();
+                for part in substitution.parts.iter().filter(|part| !part.span.is_dummy()) {
+                    let file = se.sm.lookup_char_pos(part.span.lo()).file;
+                    let uri = ArtifactLocation::for_file(&file.name, se).uri;
+                    changes.entry(uri).or_default().push(Replacement {
+                        deleted_region: Region::from_span(part.span, se),
+                        inserted_content: ArtifactContent { text: part.snippet.clone() },
+                    });
+                }
+                Fix {
+                    description: Message { text: description.clone() },
+                    artifact_changes: changes
+                        .into_iter()
+                        .map(|(uri, replacements)| ArtifactChange {
+                            artifact_location: ArtifactLocation { uri },
+                            replacements,
+                        })
+                        .collect(),
+                    properties: FixProperties { applicability: suggestion.applicability },
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,98 @@
+use super::*;
+
+use crate::sarif::SarifEmitter;
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::tests::Shared;
+use crate::Handler;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+/// Runs `f` against a handler backed by a SARIF emitter and returns the log it wrote.
+fn sarif_log(code: &str, f: impl FnOnce(&Handler)) -> Value {
+    rustc_span::create_default_session_globals_then(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+        let fallback_bundle =
+            crate::fallback_fluent_bundle(rustc_error_messages::DEFAULT_LOCALE_RESOURCES, false);
+
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let se = SarifEmitter::new(
+            Box::new(Shared { data: output.clone() }),
+            None,
+            sm,
+            None,
+            fallback_bundle,
+            false,
+        );
+
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        f(&handler);
+        drop(handler);
+
+        let bytes = output.lock().unwrap();
+        serde_json::from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+    })
+}
+
+#[test]
+fn empty_log() {
+    let log = sarif_log("", |_| {});
+    assert_eq!(log["version"], "2.1.0");
+    assert_eq!(log["runs"][0]["tool"]["driver"]["name"], "rustc");
+    assert_eq!(log["runs"][0]["results"], Value::Array(vec![]));
+}
+
+#[test]
+fn result_with_rule_and_location() {
+    let log = sarif_log("\nmod foo;\nmod bar;\n", |handler| {
+        let span = Span::with_root_ctxt(BytePos(5), BytePos(12));
+        handler
+            .struct_span_err_with_code(span, "foo", DiagnosticId::Error("E0001".to_owned()))
+            .emit();
+    });
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "E0001");
+
+    let result = &run["results"][0];
+    assert_eq!(result["ruleId"], "E0001");
+    assert_eq!(result["ruleIndex"], 0);
+    assert_eq!(result["level"], "error");
+    assert_eq!(result["message"]["text"], "foo");
+
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "test.rs");
+    assert_eq!(location["region"]["startLine"], 2);
+    assert_eq!(location["region"]["startColumn"], 5);
+    assert_eq!(location["region"]["endLine"], 3);
+    assert_eq!(location["region"]["endColumn"], 4);
+    assert_eq!(location["region"]["byteOffset"], 5);
+    assert_eq!(location["region"]["byteLength"], 7);
+}
+
+#[test]
+fn suggestion_becomes_fix() {
+    let log = sarif_log("let x = 1;\n", |handler| {
+        let span = Span::with_root_ctxt(BytePos(4), BytePos(5));
+        handler
+            .struct_span_warn(span, "unused variable")
+            .span_note(span, "defined here")
+            .span_suggestion(span, "prefix it", "_x", Applicability::MachineApplicable)
+            .emit();
+    });
+    let result = &log["runs"][0]["results"][0];
+    assert_eq!(result["level"], "warning");
+    assert_eq!(result["relatedLocations"][0]["message"]["text"], "note: defined here");
+
+    let fix = &result["fixes"][0];
+    assert_eq!(fix["description"]["text"], "prefix it");
+    assert_eq!(fix["properties"]["applicability"], "MachineApplicable");
+    let replacement = &fix["artifactChanges"][0]["replacements"][0];
+    assert_eq!(replacement["insertedContent"]["text"], "_x");
+    assert_eq!(replacement["deletedRegion"]["startColumn"], 5);
+}
@@ -6,6 +6,24 @@
 use rustc_error_messages::fluent_bundle::resolver::errors::{ReferenceKind, ResolverError};
 use rustc_error_messages::langid;
 use rustc_error_messages::DiagnosticMessage;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+/// A writer whose output can be inspected by the test after the emitter that
+/// owns it is done, used by the tests of the JSON and SARIF emitters.
+pub(crate) struct Shared<T> {
+    pub(crate) data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
 
 struct Dummy {
     bundle: FluentBundle,
@@ -65,7 +65,7 @@
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_session::config;
-use rustc_session::Session;
+use rustc_session::{ItemTimingKind, Session};
 use rustc_span::def_id::{DefId, LocalDefId};
 use rustc_span::Span;
 
@@ -155,7 +155,7 @@ fn typeck_const_arg<'tcx>(
     (did, param_did): (LocalDefId, DefId),
 ) -> &ty::TypeckResults<'tcx> {
     let fallback = move || tcx.type_of(param_did);
-    typeck_with_fallback(tcx, did, fallback)
+    tcx.sess.time_item(ItemTimingKind::Typeck, did, || typeck_with_fallback(tcx, did, fallback))
 }
 
 fn typeck<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> &ty::TypeckResults<'tcx> {
@@ -163,7 +163,9 @@ fn typeck<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> &ty::TypeckResults<'tc
         tcx.typeck_const_arg((def_id, param_did))
     } else {
         let fallback = move || tcx.type_of(def_id.to_def_id());
-        typeck_with_fallback(tcx, def_id, fallback)
+        tcx.sess.time_item(ItemTimingKind::Typeck, def_id, || {
+            typeck_with_fallback(tcx, def_id, fallback)
+        })
     }
 }
 
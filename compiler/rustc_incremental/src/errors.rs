@@ -362,3 +362,24 @@ pub struct DeleteWorkProduct<'a> {
     pub path: &'a Path,
     pub err: std::io::Error,
 }
+
+#[derive(Diagnostic)]
+#[diag(incremental_cache_limit_failed)]
+pub struct CacheLimitFailed<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(incremental_evict_failed)]
+pub struct EvictFailed<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(incremental_cache_stats_failed)]
+pub struct CacheStatsFailed<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
@@ -27,6 +27,7 @@
 pub use persist::in_incr_comp_dir_sess;
 pub use persist::load_query_result_cache;
 pub use persist::prepare_session_directory;
+pub use persist::print_cache_stats;
 pub use persist::save_dep_graph;
 pub use persist::save_work_product_index;
 pub use persist::LoadResult;
@@ -50,6 +50,20 @@
 //! any more and will delete those. It will also delete any finalized session
 //! directories for a given crate except for the most recent one.
 //!
+//! ## Size Limit
+//!
+//! Garbage collection keeps one finalized session directory per crate, but the
+//! incremental compilation directory is usually shared by many crates, and the
+//! crate directories of crates which are not compiled any more are never
+//! collected. With `-Z incremental-cache-limit`, the compiler additionally
+//! evicts the least recently used session directories of any crate after
+//! finalizing its own, until the whole directory fits in the limit. Since every
+//! session starts from a copy of the most recent session directory, the
+//! timestamp of a session directory tells when it was last used. The work
+//! products of a session directory are evicted before the rest of it, so that
+//! its dep-graph and query cache can still be reused. Session directories
+//! which are in use, including the current one, are left alone.
+//!
 //! ## Synchronization
 //!
 //! There is some synchronization needed in order for the compiler to be able to
@@ -368,6 +382,10 @@ pub fn finalize_session_directory(sess: &Session, svh: Svh) {
     }
 
     let _ = garbage_collect_session_directories(sess);
+
+    if let Some(limit) = sess.opts.unstable_opts.incremental_cache_limit {
+        enforce_cache_limit(sess, limit);
+    }
 }
 
 pub fn delete_all_session_dir_contents(sess: &Session) -> io::Result<()> {
@@ -858,6 +876,230 @@ fn all_except_most_recent(
     }
 }
 
+/// A session directory in the incremental compilation directory, as accounted by
+/// `-Z incremental-cache-limit` and `--print=incremental-cache-stats`.
+struct CacheEntry {
+    path: PathBuf,
+    /// When the session directory was created. Every compilation session of a crate starts from
+    /// a copy of the most recent session directory, so this is also when the cached data was last
+    /// used.
+    timestamp: SystemTime,
+    finalized: bool,
+    /// The total size of the files in the session directory.
+    size: u64,
+    /// The size of the files which are removed when evicting the work products, i.e. all files
+    /// but the dep-graph and the query cache.
+    work_products_size: u64,
+}
+
+/// What to remove from a session directory to bring the cache below its size limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Eviction {
+    /// Remove the work products, but keep the dep-graph and the query cache, so that the next
+    /// session of the crate can still reuse the results of the queries and only has to redo
+    /// codegen.
+    WorkProducts,
+    /// Remove the whole session directory.
+    Directory,
+}
+
+/// Collects the session directories of all crates in the incremental compilation directory.
+fn collect_cache_entries(incr_dir: &Path) -> io::Result<Vec<CacheEntry>> {
+    let crate_dirs = match incr_dir.read_dir() {
+        Ok(crate_dirs) => crate_dirs,
+        // Nothing was cached yet.
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+
+    let mut entries = vec![];
+    for crate_dir in crate_dirs {
+        // Ignore any errors, other compilation sessions may be deleting directories.
+        let Ok(crate_dir) = crate_dir else { continue };
+        let Ok(session_dirs) = crate_dir.path().read_dir() else { continue };
+        for session_dir in session_dirs {
+            let Ok(session_dir) = session_dir else { continue };
+            let directory_name = session_dir.file_name();
+            let directory_name = directory_name.to_string_lossy();
+            let Ok(timestamp) = extract_timestamp_from_session_dir(&directory_name) else {
+                // This is something we don't know, leave it alone.
+                continue;
+            };
+            let path = session_dir.path();
+            let Ok((size, work_products_size)) = session_dir_size(&path) else { continue };
+
+            entries.push(CacheEntry {
+                path,
+                timestamp,
+                finalized: is_finalized(&directory_name),
+                size,
+                work_products_size,
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Returns the total size of the files in a session directory, and the size of those of them
+/// which are removed when evicting its work products.
+fn session_dir_size(session_dir: &Path) -> io::Result<(u64, u64)> {
+    let mut size = 0;
+    let mut work_products_size = 0;
+    for file in session_dir.read_dir()? {
+        let file = file?;
+        let file_size = file.metadata()?.len();
+        size += file_size;
+        let file_name = file.file_name();
+        if file_name != DEP_GRAPH_FILENAME && file_name != QUERY_CACHE_FILENAME {
+            work_products_size += file_size;
+        }
+    }
+    Ok((size, work_products_size))
+}
+
+/// Selects what to evict from `candidates`, which are sorted from the least recently used one, to
+/// bring the `total` size of the cache to at most `limit`. The evictions are in the same order as
+/// the candidates they apply to, and the candidates after the last eviction are kept.
+///
+/// The work products of a session directory are evicted before the rest of it, which allows
+/// to keep the dep-graph and the query cache if evicting the work products is enough.
+fn select_evictions(candidates: &[CacheEntry], mut total: u64, limit: u64) -> Vec<Eviction> {
+    let mut evictions = vec![];
+    for candidate in candidates {
+        if total <= limit {
+            break;
+        }
+        total -= candidate.work_products_size;
+        if total <= limit {
+            evictions.push(Eviction::WorkProducts);
+        } else {
+            total -= candidate.size - candidate.work_products_size;
+            evictions.push(Eviction::Directory);
+        }
+    }
+    evictions
+}
+
+/// Evicts the least recently used session directories, or their work products, of all crates in
+/// the incremental compilation directory until the cache fits in `-Z incremental-cache-limit`.
+///
+/// The session directory of the current session, and session directories which are in use by
+/// other compilation sessions, are never evicted, so the cache can stay above the limit.
+fn enforce_cache_limit(sess: &Session, limit: u64) {
+    let _timer = sess.timer("incr_comp_enforce_cache_limit");
+
+    let incr_dir = sess.opts.incremental.as_ref().unwrap();
+    let entries = match collect_cache_entries(incr_dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            sess.emit_warning(errors::CacheLimitFailed { path: incr_dir, err });
+            return;
+        }
+    };
+    let total = entries.iter().map(|entry| entry.size).sum();
+    debug!("enforce_cache_limit() - cache size: {}, limit: {}", total, limit);
+    if total <= limit {
+        return;
+    }
+
+    // Like when garbage collecting, we hold on to the exclusive locks of the session directories
+    // until they are evicted, so that no other session starts copying from them.
+    let session_directory = sess.incr_comp_session_dir().clone();
+    let mut candidates = vec![];
+    let mut locks = vec![];
+    for entry in entries {
+        if !entry.finalized || entry.path == session_directory {
+            continue;
+        }
+        match flock::Lock::new(
+            &lock_file_path(&entry.path),
+            false, // don't wait
+            false, // don't create the lock-file
+            true,  // get an exclusive lock
+        ) {
+            Ok(lock) => {
+                candidates.push(entry);
+                locks.push(lock);
+            }
+            Err(_) => {
+                debug!("enforce_cache_limit() - not evicting, still in use: {:?}", entry.path);
+            }
+        }
+    }
+    candidates.sort_by_key(|entry| entry.timestamp);
+
+    let evictions = select_evictions(&candidates, total, limit);
+    for (entry, eviction) in candidates.iter().zip(evictions) {
+        debug!("enforce_cache_limit() - evicting {:?} of `{}`", eviction, entry.path.display());
+        let result = match eviction {
+            Eviction::WorkProducts => evict_work_products(&entry.path),
+            Eviction::Directory => safe_remove_dir_all(&entry.path),
+        };
+        match result {
+            Ok(()) if eviction == Eviction::Directory => {
+                delete_session_dir_lock_file(sess, &lock_file_path(&entry.path));
+            }
+            Ok(()) => {}
+            Err(err) => sess.emit_warning(errors::EvictFailed { path: &entry.path, err }),
+        }
+    }
+
+    // Let's make it explicit that the file locks are released at this point,
+    // or rather, that we held on to them until here
+    drop(locks);
+}
+
+/// Removes all files of a session directory but the dep-graph and the query cache. The work
+/// product index is removed first, so that the next session doesn't look for the missing files.
+fn evict_work_products(session_dir: &Path) -> io::Result<()> {
+    safe_remove_file(&in_incr_comp_dir(session_dir, WORK_PRODUCTS_FILENAME))?;
+    for file in session_dir.read_dir()? {
+        let file = file?;
+        let file_name = file.file_name();
+        if file_name != DEP_GRAPH_FILENAME && file_name != QUERY_CACHE_FILENAME {
+            safe_remove_file(&file.path())?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints the size of the incremental compilation directory and of each of its session
+/// directories, for `--print=incremental-cache-stats`.
+pub fn print_cache_stats(sess: &Session) {
+    let incr_dir = sess.opts.incremental.as_ref().unwrap();
+    let mut entries = match collect_cache_entries(incr_dir) {
+        Ok(entries) => entries,
+        Err(err) => {
+            sess.emit_err(errors::CacheStatsFailed { path: incr_dir, err });
+            return;
+        }
+    };
+    // The most recently used first, i.e. in the reverse order of eviction.
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+
+    let total: u64 = entries.iter().map(|entry| entry.size).sum();
+    println!("directory: {}", incr_dir.display());
+    match sess.opts.unstable_opts.incremental_cache_limit {
+        Some(limit) => println!("limit: {limit} bytes"),
+        None => println!("limit: none"),
+    }
+    println!("total: {total} bytes");
+    println!("session directories: {}", entries.len());
+
+    let now = SystemTime::now();
+    for entry in &entries {
+        let age = now.duration_since(entry.timestamp).unwrap_or_default().as_secs();
+        let crate_dir = entry.path.parent().unwrap().file_name().unwrap().to_string_lossy();
+        let session_dir = entry.path.file_name().unwrap().to_string_lossy();
+        let in_progress = if entry.finalized { "" } else { ", in progress" };
+        println!(
+            "{crate_dir}/{session_dir}: {} bytes ({} bytes of work products), \
+             last used {age}s ago{in_progress}",
+            entry.size, entry.work_products_size,
+        );
+    }
+}
+
 /// Since paths of artifacts within session directories can get quite long, we
 /// need to support deleting files with very long paths. The regular
 /// WinApi functions only support paths up to 260 characters, however. In order
@@ -82,3 +82,33 @@ fn test_find_source_directory_in_iter() {
         None
     );
 }
+
+#[test]
+fn test_select_evictions() {
+    let entry = |size, work_products_size| CacheEntry {
+        path: PathBuf::new(),
+        timestamp: UNIX_EPOCH,
+        finalized: true,
+        size,
+        work_products_size,
+    };
+    let candidates = [entry(100, 80), entry(50, 40), entry(30, 20)];
+
+    // Under the limit
+    assert_eq!(select_evictions(&candidates, 200, 200), vec![]);
+
+    // Evicting the work products of the least recently used is enough
+    assert_eq!(select_evictions(&candidates, 200, 150), vec![Eviction::WorkProducts]);
+
+    // The whole least recently used, then the work products of the next one
+    assert_eq!(
+        select_evictions(&candidates, 200, 70),
+        vec![Eviction::Directory, Eviction::WorkProducts]
+    );
+
+    // Everything, which still isn't enough because of the current session
+    assert_eq!(
+        select_evictions(&candidates, 250, 10),
+        vec![Eviction::Directory, Eviction::Directory, Eviction::Directory]
+    );
+}
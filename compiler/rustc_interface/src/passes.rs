@@ -969,6 +969,7 @@ pub fn start_codegen<'tcx>(
     if tcx.sess.opts.output_types.should_codegen() {
         rustc_incremental::assert_module_sources::assert_module_sources(tcx);
         rustc_symbol_mangling::test::report_symbol_names(tcx);
+        rustc_monomorphize::dump_mono_bloat(tcx);
     }
 
     info!("Post-codegen\n{:?}", tcx.debug_stats());
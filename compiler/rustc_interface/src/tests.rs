@@ -2,21 +2,24 @@
 use crate::interface::parse_cfgspecs;
 
 use rustc_data_structures::fx::FxHashSet;
-use rustc_errors::{emitter::HumanReadableErrorType, registry, ColorConfig};
+use rustc_data_structures::profiling::SelfProfileSummaryFormat;
+use rustc_errors::{emitter::HumanReadableErrorType, registry, Applicability, ColorConfig};
 use rustc_session::config::rustc_optgroups;
 use rustc_session::config::Input;
 use rustc_session::config::InstrumentXRay;
 use rustc_session::config::TraitSolver;
+use rustc_session::config::TranslateLang;
 use rustc_session::config::{build_configuration, build_session_options, to_crate_config};
 use rustc_session::config::{
     BranchProtection, Externs, OomStrategy, OutputType, OutputTypes, PAuthKey, PacRet,
     ProcMacroExecutionStrategy, SymbolManglingVersion, WasiExecModel,
 };
 use rustc_session::config::{CFGuard, ExternEntry, LinkerPluginLto, LtoCli, SwitchWithOptPath};
+use rustc_session::config::{CoverageMacroSite, CoverageOptions, InstrumentCoverage, Passes};
 use rustc_session::config::{DumpMonoStatsFormat, MirSpanview};
 use rustc_session::config::{ErrorOutputType, ExternLocation, LocationDetail, Options, Strip};
-use rustc_session::config::{InstrumentCoverage, Passes};
 use rustc_session::lint::Level;
+use rustc_session::lint_config::LintConfig;
 use rustc_session::search_paths::SearchPath;
 use rustc_session::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
 use rustc_session::CompilerIO;
@@ -623,6 +626,10 @@ macro_rules! tracked {
 
     // Make sure that changing a [TRACKED_NO_CRATE_HASH] option leaves the crate hash unchanged but changes the incremental hash.
     // tidy-alphabetical-start
+    tracked!(
+        lint_config,
+        LintConfig { lints: vec![("unused".into(), Level::Deny)], overrides: vec![] }
+    );
     tracked!(
         real_rust_source_base_dir,
         Some("/home/bors/rust/.rustup/toolchains/nightly/lib/rustlib/src/rust".into())
@@ -646,9 +653,12 @@ macro_rules! untracked {
 
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
+    untracked!(apply_suggestions, Some(Applicability::MachineApplicable));
+    untracked!(apply_suggestions_dry_run, true);
     untracked!(assert_incr_state, Some(String::from("loaded")));
     untracked!(deduplicate_diagnostics, false);
     untracked!(dep_tasks, true);
+    untracked!(diagnostic_grouping, true);
     untracked!(dlltool, Some(PathBuf::from("custom_dlltool.exe")));
     untracked!(dont_buffer_diagnostics, true);
     untracked!(dump_dep_graph, true);
@@ -659,13 +669,17 @@ macro_rules! untracked {
     untracked!(dump_mir_exclude_pass_number, true);
     untracked!(dump_mir_graphviz, true);
     untracked!(dump_mir_spanview, Some(MirSpanview::Statement));
+    untracked!(dump_mono_bloat, SwitchWithOptPath::Enabled(Some("mono-bloat-dir/".into())));
     untracked!(dump_mono_stats, SwitchWithOptPath::Enabled(Some("mono-items-dir/".into())));
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
+    untracked!(dump_trait_selection_tree, Some(String::from("abc")));
+    untracked!(dump_trait_selection_tree_graphviz, true);
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
+    untracked!(incremental_cache_limit, Some(1024));
     untracked!(incremental_info, true);
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
@@ -690,18 +704,22 @@ macro_rules! untracked {
     untracked!(proc_macro_backtrace, true);
     untracked!(proc_macro_execution_strategy, ProcMacroExecutionStrategy::CrossThread);
     untracked!(profile_closures, true);
+    untracked!(query_cycle_graph, Some(PathBuf::from("abc")));
     untracked!(query_dep_graph, true);
     untracked!(save_analysis, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
+    untracked!(self_profile_summary, Some(SelfProfileSummaryFormat::Json));
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
     untracked!(temps_dir, Some(String::from("abc")));
     untracked!(threads, 99);
+    untracked!(time_items, Some(10));
     untracked!(time_llvm_passes, true);
     untracked!(time_passes, true);
     untracked!(trace_macros, true);
     untracked!(track_diagnostics, true);
+    untracked!(translate_report_missing, true);
     untracked!(trim_diagnostic_paths, false);
     untracked!(ui_testing, true);
     untracked!(unpretty, Some("expanded".to_string()));
@@ -734,7 +752,12 @@ macro_rules! tracked {
             pac_ret: Some(PacRet { leaf: true, key: PAuthKey::B })
         })
     );
+    tracked!(cgu_partitioning_strategy, Some("size".to_string()));
     tracked!(codegen_backend, Some("abc".to_string()));
+    tracked!(
+        coverage_options,
+        CoverageOptions { branch: true, macros: Some(CoverageMacroSite::DefSite) }
+    );
     tracked!(crate_attr, vec!["abc".to_string()]);
     tracked!(debug_info_for_profiling, true);
     tracked!(debug_macros, true);
@@ -806,6 +829,7 @@ macro_rules! tracked {
     tracked!(tiny_const_eval_limit, true);
     tracked!(tls_model, Some(TlsModel::GeneralDynamic));
     tracked!(trait_solver, TraitSolver::Chalk);
+    tracked!(translate_lang, Some(TranslateLang::Auto));
     tracked!(translate_remapped_path_to_local_path, false);
     tracked!(trap_unreachable, Some(false));
     tracked!(treat_err_as_bug, NonZeroUsize::new(1));
@@ -9,11 +9,11 @@
 use rustc_session as session;
 use rustc_session::config::CheckCfg;
 use rustc_session::config::{self, CrateType};
-use rustc_session::config::{ErrorOutputType, OutputFilenames};
+use rustc_session::config::{ErrorOutputType, OutputFilenames, TranslateLang};
 use rustc_session::filesearch::sysroot_candidates;
 use rustc_session::lint::{self, BuiltinLintDiagnostics, LintBuffer};
 use rustc_session::parse::CrateConfig;
-use rustc_session::{early_error, filesearch, output, Session};
+use rustc_session::{early_error, early_warn, filesearch, output, Session};
 use rustc_span::edition::Edition;
 use rustc_span::lev_distance::find_best_match_for_name;
 use rustc_span::source_map::FileLoader;
@@ -56,7 +56,7 @@ pub fn add_configuration(
 }
 
 pub fn create_session(
-    sopts: config::Options,
+    mut sopts: config::Options,
     cfg: FxHashSet<(String, Option<String>)>,
     check_cfg: CheckCfg,
     file_loader: Option<Box<dyn FileLoader + Send + Sync + 'static>>,
@@ -76,10 +76,25 @@ pub fn create_session(
     // target_override is documented to be called before init(), so this is okay
     let target_override = codegen_backend.target_override(&sopts);
 
+    // With `-Z translate-lang=auto`, use the locale that the environment requests if translations
+    // for it are installed. It is recorded as if it was passed with `-Z translate-lang`, so that
+    // changing the environment invalidates the diagnostics saved by incremental compilation.
+    if let Some(TranslateLang::Auto) = sopts.unstable_opts.translate_lang {
+        let available =
+            rustc_errors::available_locales(sopts.maybe_sysroot.as_deref(), &sysroot_candidates());
+        sopts.unstable_opts.translate_lang = rustc_errors::locale_from_env()
+            .and_then(|env_locale| rustc_errors::negotiate_locale(&env_locale, &available))
+            .map(TranslateLang::Lang);
+    }
+    let translate_lang = match &sopts.unstable_opts.translate_lang {
+        Some(TranslateLang::Lang(lang)) => Some(lang.clone()),
+        Some(TranslateLang::Auto) | None => None,
+    };
+
     let bundle = match rustc_errors::fluent_bundle(
         sopts.maybe_sysroot.clone(),
         sysroot_candidates().to_vec(),
-        sopts.unstable_opts.translate_lang.clone(),
+        translate_lang,
         sopts.unstable_opts.translate_additional_ftl.as_deref(),
         sopts.unstable_opts.translate_directionality_markers,
     ) {
@@ -89,6 +104,25 @@ pub fn create_session(
         }
     };
 
+    if sopts.unstable_opts.translate_report_missing {
+        if let Some(bundle) = &bundle {
+            let missing =
+                rustc_errors::missing_messages(bundle, rustc_errors::DEFAULT_LOCALE_RESOURCES);
+            if !missing.is_empty() {
+                early_warn(
+                    sopts.error_format,
+                    &format!(
+                        "{} diagnostic messages are not translated to `{}` and will be shown in \
+                         `en-US`:\n    {}",
+                        missing.len(),
+                        bundle.locales[0],
+                        missing.join("\n    ")
+                    ),
+                );
+            }
+        }
+    }
+
     let mut sess = session::build_session(
         sopts,
         io,
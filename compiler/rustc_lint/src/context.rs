@@ -334,6 +334,40 @@ pub fn check_lint_name_cmdline(
         lint_name: &str,
         level: Level,
         registered_tools: &RegisteredTools,
+    ) {
+        self.check_requested_lint_name(
+            sess,
+            lint_name,
+            level,
+            registered_tools,
+            RequestedLevel::CommandLine { level, lint_name: lint_name.to_string() },
+        )
+    }
+
+    /// Checks the validity of a lint name set in the `--lint-config` file.
+    pub fn check_lint_name_lint_config(
+        &self,
+        sess: &Session,
+        lint_name: &str,
+        level: Level,
+        registered_tools: &RegisteredTools,
+    ) {
+        self.check_requested_lint_name(
+            sess,
+            lint_name,
+            level,
+            registered_tools,
+            RequestedLevel::LintConfig { level: level.as_str(), lint_name: lint_name.to_string() },
+        )
+    }
+
+    fn check_requested_lint_name(
+        &self,
+        sess: &Session,
+        lint_name: &str,
+        level: Level,
+        registered_tools: &RegisteredTools,
+        sub: RequestedLevel,
     ) {
         let (tool_name, lint_name_only) = parse_lint_and_tool_name(lint_name);
         if lint_name_only == crate::WARNINGS.name_lower() && matches!(level, Level::ForceWarn(_)) {
@@ -343,30 +377,16 @@ pub fn check_lint_name_cmdline(
         let lint_name = lint_name.to_string();
         match self.check_lint_name(lint_name_only, tool_name, registered_tools) {
             CheckLintNameResult::Warning(msg, _) => {
-                sess.emit_warning(CheckNameWarning {
-                    msg,
-                    sub: RequestedLevel { level, lint_name },
-                });
+                sess.emit_warning(CheckNameWarning { msg, sub });
             }
             CheckLintNameResult::NoLint(suggestion) => {
-                sess.emit_err(CheckNameUnknown {
-                    lint_name: lint_name.clone(),
-                    suggestion,
-                    sub: RequestedLevel { level, lint_name },
-                });
+                sess.emit_err(CheckNameUnknown { lint_name, suggestion, sub });
             }
             CheckLintNameResult::Tool(Err((Some(_), new_name))) => {
-                sess.emit_warning(CheckNameDeprecated {
-                    lint_name: lint_name.clone(),
-                    new_name,
-                    sub: RequestedLevel { level, lint_name },
-                });
+                sess.emit_warning(CheckNameDeprecated { lint_name, new_name, sub });
             }
             CheckLintNameResult::NoTool => {
-                sess.emit_err(CheckNameUnknownTool {
-                    tool_name: tool_name.unwrap(),
-                    sub: RequestedLevel { level, lint_name },
-                });
+                sess.emit_err(CheckNameUnknownTool { tool_name: tool_name.unwrap(), sub });
             }
             _ => {}
         };
@@ -24,7 +24,7 @@
 use rustc_session::lint::{BufferedEarlyLint, LintBuffer, LintPass};
 use rustc_session::Session;
 use rustc_span::symbol::Ident;
-use rustc_span::Span;
+use rustc_span::{Span, DUMMY_SP};
 
 macro_rules! lint_callback { ($cx:expr, $f:ident, $($args:expr),*) => ({
     $cx.pass.$f(&$cx.context, $($args),*);
@@ -78,6 +78,22 @@ fn with_lint_attrs<F>(&mut self, id: ast::NodeId, attrs: &'a [ast::Attribute], f
         lint_callback!(self, exit_lint_attrs, attrs);
         self.context.builder.pop(push);
     }
+
+    /// Like `with_lint_attrs`, but first applies the `--lint-config` path overrides for the file
+    /// containing `span` if the node starts a new file.
+    fn with_lint_config_and_attrs<F>(
+        &mut self,
+        span: Span,
+        id: ast::NodeId,
+        attrs: &'a [ast::Attribute],
+        f: F,
+    ) where
+        F: FnOnce(&mut Self),
+    {
+        let push = self.context.builder.push_lint_config(span);
+        self.with_lint_attrs(id, attrs, f);
+        self.context.builder.pop_lint_config(push);
+    }
 }
 
 impl<'a, T: EarlyLintPass> ast_visit::Visitor<'a> for EarlyContextAndPass<'a, T> {
@@ -89,7 +105,12 @@ fn visit_param(&mut self, param: &'a ast::Param) {
     }
 
     fn visit_item(&mut self, it: &'a ast::Item) {
-        self.with_lint_attrs(it.id, &it.attrs, |cx| {
+        // The contents of an out-of-line module are in a different file than its declaration.
+        let span = match &it.kind {
+            ast::ItemKind::Mod(_, ast::ModKind::Loaded(_, _, spans)) => spans.inner_span,
+            _ => it.span,
+        };
+        self.with_lint_config_and_attrs(span, it.id, &it.attrs, |cx| {
             lint_callback!(cx, check_item, it);
             ast_visit::walk_item(cx, it);
             lint_callback!(cx, check_item_post, it);
@@ -333,6 +354,8 @@ impl EarlyLintPass for RuntimeCombinedEarlyLintPass<'_> {
 /// This trait generalizes over those nodes.
 pub trait EarlyCheckNode<'a>: Copy {
     fn id(self) -> ast::NodeId;
+    /// A span in the file the node starts, for the `--lint-config` path overrides.
+    fn span(self) -> Span;
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b;
@@ -345,6 +368,9 @@ impl<'a> EarlyCheckNode<'a> for &'a ast::Crate {
     fn id(self) -> ast::NodeId {
         ast::CRATE_NODE_ID
     }
+    fn span(self) -> Span {
+        self.spans.inner_span
+    }
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b,
@@ -365,6 +391,9 @@ impl<'a> EarlyCheckNode<'a> for (ast::NodeId, &'a [ast::Attribute], &'a [P<ast::
     fn id(self) -> ast::NodeId {
         self.0
     }
+    fn span(self) -> Span {
+        self.2.first().map_or(DUMMY_SP, |item| item.span)
+    }
     fn attrs<'b>(self) -> &'b [ast::Attribute]
     where
         'a: 'b,
@@ -420,7 +449,9 @@ pub fn check_ast_node_inner<'a, T: EarlyLintPass>(
 ) {
     let mut cx = EarlyContextAndPass { context, pass };
 
-    cx.with_lint_attrs(check_node.id(), check_node.attrs(), |cx| check_node.check(cx));
+    cx.with_lint_config_and_attrs(check_node.span(), check_node.id(), check_node.attrs(), |cx| {
+        check_node.check(cx)
+    });
 
     // All of the buffered lints should have been emitted at this point.
     // If not, that means that we somehow buffered a lint for a node id
@@ -27,7 +27,7 @@
 use rustc_session::parse::{add_feature_diagnostics, feature_err};
 use rustc_session::Session;
 use rustc_span::symbol::{sym, Symbol};
-use rustc_span::{Span, DUMMY_SP};
+use rustc_span::{BytePos, Span, DUMMY_SP};
 
 use crate::errors::{
     MalformedAttribute, MalformedAttributeSub, OverruledAttribute, OverruledAttributeSub,
@@ -159,6 +159,9 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
     if owner == hir::CRATE_OWNER_ID {
         levels.add_command_line();
     }
+    if let Some(config_levels) = tcx.lint_config_levels_on(owner) {
+        levels.add_lint_config_levels(&config_levels);
+    }
 
     match attrs.map.range(..) {
         // There is only something to do if there are attributes at all.
@@ -193,6 +196,8 @@ fn shallow_lint_levels_on(tcx: TyCtxt<'_>, owner: hir::OwnerId) -> ShallowLintLe
 pub struct TopDown {
     sets: LintLevelSets,
     cur: LintStackIndex,
+    /// Start of the source file of the current node, for `--lint-config` path overrides.
+    cur_file: Option<BytePos>,
 }
 
 pub trait LintLevelsProvider {
@@ -443,7 +448,7 @@ pub(crate) fn new(
     ) -> Self {
         let mut builder = LintLevelsBuilder {
             sess,
-            provider: TopDown { sets: LintLevelSets::new(), cur: COMMAND_LINE },
+            provider: TopDown { sets: LintLevelSets::new(), cur: COMMAND_LINE, cur_file: None },
             warn_about_weird_lints,
             store,
             registered_tools,
@@ -501,6 +506,43 @@ pub(crate) fn pop(&mut self, push: BuilderPush) {
         self.provider.cur = push.prev;
         std::mem::forget(push);
     }
+
+    /// Pushes the levels that the path overrides of `--lint-config` set for the file containing
+    /// `span`, if it is not the file of the enclosing node. This must be called before `push`
+    /// for the attributes of the node, so that the attributes take precedence.
+    ///
+    /// Don't forget to call `pop_lint_config`!
+    pub(crate) fn push_lint_config(&mut self, span: Span) -> LintConfigPush {
+        let push = LintConfigPush { prev: self.provider.cur, prev_file: self.provider.cur_file };
+        if self.sess.opts.lint_config.overrides.is_empty() {
+            return push;
+        }
+
+        let file = self.sess.source_map().lookup_source_file(span.source_callsite().lo());
+        if self.provider.cur_file != Some(file.start_pos) {
+            self.provider.cur_file = Some(file.start_pos);
+            if let Some(levels) = self.sess.lint_config_levels_for_file(&file) {
+                self.provider.cur = self
+                    .provider
+                    .sets
+                    .list
+                    .push(LintSet { specs: FxHashMap::default(), parent: push.prev });
+                self.add_lint_config_levels(&levels);
+            }
+        }
+        push
+    }
+
+    /// Called after `push_lint_config` when the node is exited.
+    pub(crate) fn pop_lint_config(&mut self, push: LintConfigPush) {
+        self.provider.cur = push.prev;
+        self.provider.cur_file = push.prev_file;
+    }
+}
+
+pub(crate) struct LintConfigPush {
+    prev: LintStackIndex,
+    prev_file: Option<BytePos>,
 }
 
 #[cfg(debug_assertions)]
@@ -551,6 +593,64 @@ fn add_command_line(&mut self) {
                 }
             }
         }
+
+        self.add_lint_config();
+    }
+
+    /// Applies the crate-wide levels of `--lint-config`, which the command-line flags take
+    /// precedence over. The lint names of the path overrides are checked here as well.
+    fn add_lint_config(&mut self) {
+        let sess = self.sess;
+        let overrides = sess.opts.lint_config.overrides.iter().flat_map(|o| &o.lints);
+        for &(ref lint_name, level) in sess.opts.lint_config.lints.iter().chain(overrides) {
+            self.store.check_lint_name_lint_config(sess, lint_name, level, self.registered_tools);
+        }
+
+        for &(ref lint_name, level) in &sess.opts.lint_config.lints {
+            let Ok(ids) = self.store.find_lints(lint_name) else {
+                // errors handled above
+                continue
+            };
+            let src = LintLevelSource::LintConfig(Symbol::intern(lint_name), level);
+            for id in ids {
+                if let Some(
+                    (Level::ForceWarn(_) | Level::Forbid, _)
+                    | (_, LintLevelSource::CommandLine(..)),
+                ) = self.current_specs().get(&id)
+                {
+                    continue;
+                }
+
+                if self.check_gated_lint(id, DUMMY_SP) {
+                    self.insert(id, (level, src));
+                }
+            }
+        }
+    }
+
+    /// Applies the levels that the path overrides of `--lint-config` set for the file of the
+    /// current node. These take precedence over the crate-wide levels of the file, but not over
+    /// the command-line flags, and cannot lower a forbidden or force-warned lint.
+    fn add_lint_config_levels(&mut self, levels: &[(String, Level)]) {
+        for &(ref lint_name, level) in levels {
+            let Ok(ids) = self.store.find_lints(lint_name) else {
+                // errors handled in `add_lint_config`
+                continue
+            };
+            let src = LintLevelSource::LintConfig(Symbol::intern(lint_name), level);
+            for id in ids {
+                if let (Level::ForceWarn(_) | Level::Forbid, _)
+                | (_, LintLevelSource::CommandLine(..)) =
+                    self.provider.get_lint_level(id.lint, self.sess)
+                {
+                    continue;
+                }
+
+                if self.check_gated_lint(id, DUMMY_SP) {
+                    self.insert(id, (level, src));
+                }
+            }
+        }
     }
 
     /// Attempts to insert the `id` to `level_src` map entry. If unsuccessful
@@ -579,7 +679,8 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                 let fcw_warning = match old_src {
                     LintLevelSource::Default => false,
                     LintLevelSource::Node { name, .. } => self.store.is_lint_group(name),
-                    LintLevelSource::CommandLine(symbol, _) => self.store.is_lint_group(symbol),
+                    LintLevelSource::CommandLine(symbol, _)
+                    | LintLevelSource::LintConfig(symbol, _) => self.store.is_lint_group(symbol),
                 };
                 debug!(
                     "fcw_warning={:?}, specs.get(&id) = {:?}, old_src={:?}, id_name={:?}",
@@ -596,6 +697,7 @@ fn insert_spec(&mut self, id: LintId, (mut level, src): LevelAndSource) {
                         OverruledAttributeSub::NodeSource { span, reason }
                     }
                     LintLevelSource::CommandLine(_, _) => OverruledAttributeSub::CommandLineSource,
+                    LintLevelSource::LintConfig(_, _) => OverruledAttributeSub::LintConfigSource,
                 };
                 if !fcw_warning {
                     self.sess.emit_err(OverruledAttribute {
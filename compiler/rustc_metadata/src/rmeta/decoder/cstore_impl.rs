@@ -15,7 +15,7 @@
 use rustc_middle::middle::stability::DeprecationEntry;
 use rustc_middle::ty::fast_reject::SimplifiedType;
 use rustc_middle::ty::query::{ExternProviders, Providers};
-use rustc_middle::ty::{self, TyCtxt, Visibility};
+use rustc_middle::ty::{self, TyCtxt, UnusedGenericParams, Visibility};
 use rustc_session::cstore::{CrateSource, CrateStore};
 use rustc_session::{Session, StableCrateId};
 use rustc_span::hygiene::{ExpnHash, ExpnId};
@@ -182,9 +182,9 @@ fn into_args(self) -> (DefId, DefId) {
 }
 
 impl<'tcx> IntoArgs for ty::InstanceDef<'tcx> {
-    type Other = ();
-    fn into_args(self) -> (DefId, ()) {
-        (self.def_id(), ())
+    type Other = ty::InstanceDef<'tcx>;
+    fn into_args(self) -> (DefId, ty::InstanceDef<'tcx>) {
+        (self.def_id(), self)
     }
 }
 
@@ -220,7 +220,22 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     lookup_default_body_stability => { table }
     lookup_deprecation_entry => { table }
     params_in_repr => { table }
-    unused_generic_params => { table }
+    unused_generic_params => {
+        // Only the items with unused parameters are recorded. As in the local analysis, the
+        // instances without a polymorphic MIR body of their item use all their parameters.
+        if other.has_polymorphic_mir_body()
+            && !matches!(other, ty::InstanceDef::Intrinsic(..) | ty::InstanceDef::Virtual(..))
+        {
+            cdata
+                .root
+                .tables
+                .unused_generic_params
+                .get(cdata, def_id.index)
+                .map_or_else(UnusedGenericParams::new_all_used, |lazy| lazy.decode((cdata, tcx)))
+        } else {
+            UnusedGenericParams::new_all_used()
+        }
+    }
     opt_def_kind => { table_direct }
     impl_parent => { table }
     impl_polarity => { table_direct }
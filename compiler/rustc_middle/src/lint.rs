@@ -2,8 +2,10 @@
 
 use rustc_data_structures::fx::FxHashMap;
 use rustc_data_structures::sorted_map::SortedMap;
+use rustc_data_structures::sync::Lrc;
 use rustc_errors::{Diagnostic, DiagnosticBuilder, DiagnosticId, DiagnosticMessage, MultiSpan};
-use rustc_hir::{HirId, ItemLocalId};
+use rustc_hir as hir;
+use rustc_hir::{HirId, ItemLocalId, OwnerId, OwnerNode, CRATE_OWNER_ID};
 use rustc_session::lint::{
     builtin::{self, FORBIDDEN_LINT_GROUPS},
     FutureIncompatibilityReason, Level, Lint, LintId,
@@ -34,6 +36,10 @@ pub enum LintLevelSource {
     /// The provided `Level` is the level specified on the command line.
     /// (The actual level may be lower due to `--cap-lints`.)
     CommandLine(Symbol, Level),
+
+    /// Lint level was set by the `--lint-config` file, either for the whole crate or for the
+    /// file containing the node. The provided `Level` is the level specified in the file.
+    LintConfig(Symbol, Level),
 }
 
 impl LintLevelSource {
@@ -42,6 +48,7 @@ pub fn name(&self) -> Symbol {
             LintLevelSource::Default => symbol::kw::Default,
             LintLevelSource::Node { name, .. } => name,
             LintLevelSource::CommandLine(name, _) => name,
+            LintLevelSource::LintConfig(name, _) => name,
         }
     }
 
@@ -50,6 +57,7 @@ pub fn span(&self) -> Span {
             LintLevelSource::Default => DUMMY_SP,
             LintLevelSource::Node { span, .. } => span,
             LintLevelSource::CommandLine(_, _) => DUMMY_SP,
+            LintLevelSource::LintConfig(_, _) => DUMMY_SP,
         }
     }
 }
@@ -99,7 +107,12 @@ pub fn reveal_actual_level(
     }
 
     // Ensure that we never exceed the `--cap-lints` argument unless the source is a --force-warn
-    level = if let LintLevelSource::CommandLine(_, Level::ForceWarn(_)) = src {
+    let force_warn = matches!(
+        src,
+        LintLevelSource::CommandLine(_, Level::ForceWarn(_))
+            | LintLevelSource::LintConfig(_, Level::ForceWarn(_))
+    );
+    level = if force_warn {
         level
     } else {
         cmp::min(level, sess.opts.lint_cap.unwrap_or(Level::Forbid))
@@ -182,6 +195,11 @@ pub fn maybe_lint_level_root_bounded(self, mut id: HirId, bound: HirId) -> HirId
             if hir.attrs(id).iter().any(|attr| Level::from_attr(attr).is_some()) {
                 return id;
             }
+            if id.local_id == ItemLocalId::from_u32(0)
+                && self.lint_config_levels_on(id.owner).is_some()
+            {
+                return id;
+            }
             let next = hir.parent_id(id);
             if next == id {
                 bug!("lint traversal reached the root of the crate");
@@ -189,6 +207,32 @@ pub fn maybe_lint_level_root_bounded(self, mut id: HirId, bound: HirId) -> HirId
             id = next;
         }
     }
+
+    /// Returns the lint levels that the path overrides of `--lint-config` set on `owner`.
+    /// They are only set on the outermost owners of each source file, nested owners from the
+    /// same file inherit them from their parent.
+    pub fn lint_config_levels_on(self, owner: OwnerId) -> Option<Lrc<[(String, Level)]>> {
+        if self.sess.opts.lint_config.overrides.is_empty() {
+            return None;
+        }
+        let file_of = |owner| {
+            // The contents of an out-of-line module are in a different file than its declaration.
+            let span = match self.hir().owner(owner) {
+                OwnerNode::Item(&hir::Item { kind: hir::ItemKind::Mod(module), .. })
+                | OwnerNode::Crate(module) => module.spans.inner_span,
+                _ => self.def_span(owner.def_id),
+            };
+            self.sess.source_map().lookup_source_file(span.source_callsite().lo())
+        };
+        let file = file_of(owner);
+        if owner != CRATE_OWNER_ID {
+            let parent = self.hir().get_parent_item(owner.into());
+            if file_of(parent).start_pos == file.start_pos {
+                return None;
+            }
+        }
+        self.sess.lint_config_levels_for_file(&file)
+    }
 }
 
 /// This struct represents a lint expectation and holds all required information
@@ -249,6 +293,20 @@ pub fn explain_lint_level_source(
                 ));
             }
         }
+        LintLevelSource::LintConfig(lint_config_val, orig_level) => {
+            let level_str = orig_level.as_str();
+            if lint_config_val.as_str() == name {
+                err.note_once(&format!(
+                    "requested in the `--lint-config` file with `{} = \"{}\"`",
+                    name, level_str
+                ));
+            } else {
+                err.note_once(&format!(
+                    "`{} = \"{}\"` implied by `{} = \"{}\"` in the `--lint-config` file",
+                    name, level_str, lint_config_val, level_str
+                ));
+            }
+        }
         LintLevelSource::Node { name: lint_attr_name, span, reason, .. } => {
             if let Some(rationale) = reason {
                 err.note(rationale.as_str());
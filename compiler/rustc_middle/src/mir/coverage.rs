@@ -1,7 +1,7 @@
 //! Metadata from source code coverage analysis and instrumentation.
 
 use rustc_macros::HashStable;
-use rustc_span::Symbol;
+use rustc_span::{Span, Symbol};
 
 use std::fmt::{self, Debug, Formatter};
 
@@ -81,6 +81,16 @@ pub struct InjectedExpressionIndex {}
     pub struct MappedExpressionIndex {}
 }
 
+rustc_index::newtype_index! {
+    /// Identifies a `CoverageKind::BlockMarker` injected while building the MIR of a function with
+    /// `-Z coverage-options=branch`, so the `InstrumentCoverage` pass can find the blocks the
+    /// branches of the function jump to.
+    #[derive(HashStable)]
+    #[max = 0xFFFF_FFFF]
+    #[debug_format = "BlockMarkerId({})"]
+    pub struct BlockMarkerId {}
+}
+
 impl From<CounterValueReference> for ExpressionOperandId {
     #[inline]
     fn from(v: CounterValueReference) -> ExpressionOperandId {
@@ -108,6 +118,17 @@ pub enum CoverageKind {
         rhs: ExpressionOperandId,
     },
     Unreachable,
+    /// Marks its basic block for a `BranchSpan` or `MatchArmSpan`. Only exists until borrowck is
+    /// done, and is never codegenned.
+    BlockMarker {
+        id: BlockMarkerId,
+    },
+    /// Adds a branch region, counting how many times its condition was true and false, to the
+    /// coverage map. Like expressions, this doesn't generate any code.
+    Branch {
+        true_counter: ExpressionOperandId,
+        false_counter: ExpressionOperandId,
+    },
 }
 
 impl CoverageKind {
@@ -117,6 +138,9 @@ pub fn as_operand_id(&self) -> ExpressionOperandId {
             Counter { id, .. } => ExpressionOperandId::from(id),
             Expression { id, .. } => ExpressionOperandId::from(id),
             Unreachable => bug!("Unreachable coverage cannot be part of an expression"),
+            BlockMarker { .. } | Branch { .. } => {
+                bug!("{:?} cannot be part of an expression", self)
+            }
         }
     }
 
@@ -142,6 +166,10 @@ fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
                 rhs.index(),
             ),
             Unreachable => write!(fmt, "Unreachable"),
+            BlockMarker { id } => write!(fmt, "BlockMarker({:?})", id.index()),
+            Branch { true_counter, false_counter } => {
+                write!(fmt, "Branch({:?}, {:?})", true_counter.index(), false_counter.index())
+            }
         }
     }
 }
@@ -166,6 +194,36 @@ fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// The branches of a function, recorded while building its MIR with `-Z coverage-options=branch`,
+/// and turned into branch regions by the `InstrumentCoverage` pass.
+#[derive(Clone, Debug, TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct BranchInfo {
+    /// The number of `CoverageKind::BlockMarker`s injected in the function. Their ids range from
+    /// 0 to `num_block_markers - 1`.
+    pub num_block_markers: usize,
+    pub branch_spans: Vec<BranchSpan>,
+    pub match_arm_spans: Vec<MatchArmSpan>,
+}
+
+/// A boolean condition at `span`, which jumps to the block marked by `true_marker` when it is true,
+/// and to the block marked by `false_marker` when it is false.
+#[derive(Clone, Debug, TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct BranchSpan {
+    pub span: Span,
+    pub true_marker: BlockMarkerId,
+    pub false_marker: BlockMarkerId,
+}
+
+/// The `match` arm at `span`, whose body starts in the block marked by `arm_marker`. Its branch is
+/// taken each time that block runs, and not taken each other time the block marked by
+/// `match_marker`, which starts matching the scrutinee, runs.
+#[derive(Clone, Debug, TyEncodable, TyDecodable, Hash, HashStable, TypeFoldable, TypeVisitable)]
+pub struct MatchArmSpan {
+    pub span: Span,
+    pub arm_marker: BlockMarkerId,
+    pub match_marker: BlockMarkerId,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, TyEncodable, TyDecodable, Hash, HashStable)]
 #[derive(TypeFoldable, TypeVisitable)]
 pub enum Op {
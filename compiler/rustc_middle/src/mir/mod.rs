@@ -305,6 +305,10 @@ pub struct Body<'tcx> {
     /// This is only `Some` if the function that this body comes from was annotated with `rustc_custom_mir`.
     pub injection_phase: Option<MirPhase>,
 
+    /// The branches recorded while building the MIR of this function, for the
+    /// `InstrumentCoverage` pass. Only present with `-Z coverage-options=branch`.
+    pub coverage_branch_info: Option<Box<coverage::BranchInfo>>,
+
     pub tainted_by_errors: Option<ErrorGuaranteed>,
 }
 
@@ -352,6 +356,7 @@ pub fn new(
             required_consts: Vec::new(),
             is_polymorphic: false,
             injection_phase: None,
+            coverage_branch_info: None,
             tainted_by_errors,
         };
         body.is_polymorphic = body.has_non_region_param();
@@ -380,6 +385,7 @@ pub fn new_cfg_only(basic_blocks: IndexVec<BasicBlock, BasicBlockData<'tcx>>) ->
             var_debug_info: Vec::new(),
             is_polymorphic: false,
             injection_phase: None,
+            coverage_branch_info: None,
             tainted_by_errors: None,
         };
         body.is_polymorphic = body.has_non_region_param();
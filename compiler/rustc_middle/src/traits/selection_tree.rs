@@ -0,0 +1,318 @@
+//! The candidates considered by trait selection for the obligations of an item, recorded for
+//! `-Z dump-trait-selection-tree`.
+//!
+//! The tree is made of strings since it outlives the inference contexts of the obligations. An
+//! obligation is a child of the obligation it was required for, and each child remembers the
+//! candidate of its parent that required it, if known.
+
+use crate::ty::print::with_no_trimmed_paths;
+use crate::ty::TyCtxt;
+use rustc_data_structures::fx::FxHashMap;
+use rustc_graphviz as dot;
+use rustc_span::def_id::LocalDefId;
+use std::io::{self, Write};
+
+#[derive(Default)]
+pub struct SelectionTree {
+    /// Whether the obligations of a body are recorded, see [`TyCtxt::records_trait_selection`].
+    recorded_bodies: FxHashMap<LocalDefId, bool>,
+    roots: Vec<SelectionNode>,
+}
+
+/// An obligation, with every attempt to select it.
+#[derive(Clone, Debug)]
+pub struct SelectionNode {
+    pub obligation: String,
+    pub span: Option<String>,
+    pub attempts: Vec<SelectionAttempt>,
+    /// The nested obligations, with the candidate that required them.
+    pub children: Vec<(Option<String>, SelectionNode)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SelectionAttempt {
+    pub candidates: Vec<CandidateOutcome>,
+    /// Whether the result was taken from a cache, so no candidates were considered.
+    pub cached: bool,
+    pub result: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidateOutcome {
+    pub candidate: String,
+    /// Why the candidate was rejected, or how far it got.
+    pub outcome: String,
+}
+
+impl SelectionNode {
+    pub fn new(obligation: String, span: Option<String>) -> SelectionNode {
+        SelectionNode { obligation, span, attempts: Vec::new(), children: Vec::new() }
+    }
+
+    fn add_attempt(&mut self, attempt: SelectionAttempt) {
+        // Obligations are often selected again with the same outcome, e.g. while they're
+        // ambiguous, so only keep distinct attempts.
+        if !self.attempts.contains(&attempt) {
+            self.attempts.push(attempt);
+        }
+    }
+
+    /// Merges `other`, which is the same obligation, into `self`.
+    fn merge(&mut self, other: SelectionNode) {
+        for attempt in other.attempts {
+            self.add_attempt(attempt);
+        }
+        for (via, child) in other.children {
+            self.add_child(via, child);
+        }
+    }
+
+    pub fn add_child(&mut self, via: Option<String>, child: SelectionNode) {
+        match self.children.iter_mut().find(|(v, c)| *v == via && c.obligation == child.obligation)
+        {
+            Some((_, existing)) => existing.merge(child),
+            None => self.children.push((via, child)),
+        }
+    }
+
+    fn child_mut(&mut self, via: Option<String>, obligation: &str) -> &mut SelectionNode {
+        let index =
+            match self.children.iter().position(|(v, c)| *v == via && c.obligation == obligation) {
+                Some(index) => index,
+                None => {
+                    self.children.push((via, SelectionNode::new(obligation.to_string(), None)));
+                    self.children.len() - 1
+                }
+            };
+        &mut self.children[index].1
+    }
+}
+
+impl SelectionTree {
+    /// Adds an attempt to select the last obligation of `path`, where each obligation was
+    /// required by the one before it, through the given candidate.
+    pub fn add_attempt(
+        &mut self,
+        path: &[(Option<String>, String)],
+        span: Option<String>,
+        attempt: SelectionAttempt,
+    ) {
+        let [(_, root), rest @ ..] = path else {
+            return;
+        };
+        let mut node = match self.roots.iter().position(|r| r.obligation == *root) {
+            Some(index) => &mut self.roots[index],
+            None => {
+                self.roots.push(SelectionNode::new(root.clone(), None));
+                self.roots.last_mut().unwrap()
+            }
+        };
+        for (via, obligation) in rest {
+            node = node.child_mut(via.clone(), obligation);
+        }
+        if node.span.is_none() {
+            node.span = span;
+        }
+        node.add_attempt(attempt);
+    }
+
+    /// Adds a whole tree of obligations, e.g. from a solver that evaluates nested obligations
+    /// recursively.
+    pub fn add_root(&mut self, root: SelectionNode) {
+        match self.roots.iter_mut().find(|r| r.obligation == root.obligation) {
+            Some(existing) => existing.merge(root),
+            None => self.roots.push(root),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roots.is_empty()
+    }
+
+    /// Writes the tree indented, one line per obligation, candidate and result.
+    pub fn write_text(&self, w: &mut dyn Write) -> io::Result<()> {
+        fn write_node(
+            w: &mut dyn Write,
+            node: &SelectionNode,
+            parent_span: Option<&String>,
+            depth: usize,
+        ) -> io::Result<()> {
+            let indent = "    ".repeat(depth);
+            write!(w, "{indent}`{}`", node.obligation)?;
+            match &node.span {
+                Some(span) if Some(span) != parent_span => writeln!(w, " at {span}")?,
+                _ => writeln!(w)?,
+            }
+            let span = node.span.as_ref().or(parent_span);
+
+            let mut written_vias = Vec::new();
+            for (i, attempt) in node.attempts.iter().enumerate() {
+                for candidate in &attempt.candidates {
+                    writeln!(w, "{indent}  - `{}`: {}", candidate.candidate, candidate.outcome)?;
+                    // Children are shown below the last mention of the candidate requiring them.
+                    let mentioned_later = node.attempts[i + 1..]
+                        .iter()
+                        .any(|a| a.candidates.iter().any(|c| c.candidate == candidate.candidate));
+                    if !mentioned_later && !written_vias.contains(&&candidate.candidate) {
+                        written_vias.push(&candidate.candidate);
+                        for (_, child) in node
+                            .children
+                            .iter()
+                            .filter(|(via, _)| via.as_ref() == Some(&candidate.candidate))
+                        {
+                            write_node(w, child, span, depth + 1)?;
+                        }
+                    }
+                }
+                let cached = if attempt.cached { " (cached)" } else { "" };
+                writeln!(w, "{indent}  => {}{cached}", attempt.result)?;
+            }
+            for (via, child) in &node.children {
+                if via.as_ref().map_or(true, |via| !written_vias.contains(&via)) {
+                    if let Some(via) = via {
+                        writeln!(w, "{indent}  required by `{via}`:")?;
+                    }
+                    write_node(w, child, span, depth + 1)?;
+                }
+            }
+            Ok(())
+        }
+
+        for root in &self.roots {
+            write_node(w, root, None, 0)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the tree as a Graphviz graph, with an edge from each obligation to its candidates
+    /// and from each candidate to the obligations it requires.
+    pub fn write_graphviz(&self, mut w: &mut dyn Write) -> io::Result<()> {
+        let mut graph = Graph { nodes: Vec::new(), edges: Vec::new() };
+        for root in &self.roots {
+            graph.add_node(root);
+        }
+        dot::render(&graph, &mut w)
+    }
+}
+
+struct Graph {
+    /// The label of each node and whether it is an obligation rather than a candidate.
+    nodes: Vec<(String, bool)>,
+    edges: Vec<(usize, usize, String)>,
+}
+
+impl Graph {
+    fn add_node(&mut self, node: &SelectionNode) -> usize {
+        let mut label = node.obligation.clone();
+        for attempt in &node.attempts {
+            label.push_str("\n=> ");
+            label.push_str(&attempt.result);
+        }
+        let index = self.nodes.len();
+        self.nodes.push((label, true));
+
+        let mut candidates = FxHashMap::default();
+        for attempt in &node.attempts {
+            for candidate in &attempt.candidates {
+                let target = *candidates.entry(&candidate.candidate).or_insert_with(|| {
+                    self.nodes.push((candidate.candidate.clone(), false));
+                    self.nodes.len() - 1
+                });
+                self.edges.push((index, target, candidate.outcome.clone()));
+            }
+        }
+        for (via, child) in &node.children {
+            let child = self.add_node(child);
+            match via.as_ref().and_then(|via| candidates.get(via)) {
+                Some(&candidate) => self.edges.push((candidate, child, String::new())),
+                None => self.edges.push((index, child, via.clone().unwrap_or_default())),
+            }
+        }
+        index
+    }
+}
+
+impl<'a> dot::Labeller<'a> for Graph {
+    type Node = usize;
+    type Edge = (usize, usize, String);
+
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new("trait_selection_tree").unwrap()
+    }
+
+    fn node_id(&'a self, n: &usize) -> dot::Id<'a> {
+        dot::Id::new(format!("node_{n}")).unwrap()
+    }
+
+    fn node_shape(&'a self, n: &usize) -> Option<dot::LabelText<'a>> {
+        let shape = if self.nodes[*n].1 { "box" } else { "ellipse" };
+        Some(dot::LabelText::LabelStr(shape.into()))
+    }
+
+    fn node_label(&'a self, n: &usize) -> dot::LabelText<'a> {
+        dot::LabelText::LabelStr(self.nodes[*n].0.as_str().into())
+    }
+
+    fn edge_label(&'a self, e: &Self::Edge) -> dot::LabelText<'a> {
+        dot::LabelText::LabelStr(e.2.clone().into())
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for Graph {
+    type Node = usize;
+    type Edge = (usize, usize, String);
+
+    fn nodes(&'a self) -> dot::Nodes<'a, usize> {
+        (0..self.nodes.len()).collect()
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, Self::Edge> {
+        self.edges.iter().cloned().collect()
+    }
+
+    fn source(&'a self, e: &Self::Edge) -> usize {
+        e.0
+    }
+
+    fn target(&'a self, e: &Self::Edge) -> usize {
+        e.1
+    }
+}
+
+impl<'tcx> TyCtxt<'tcx> {
+    /// Whether the obligations of `body_id` are recorded in the trait selection tree, i.e. if
+    /// its item is the one requested with `-Z dump-trait-selection-tree`.
+    pub fn records_trait_selection(self, body_id: LocalDefId) -> bool {
+        let Some(item) = &self.sess.opts.unstable_opts.dump_trait_selection_tree else {
+            return false;
+        };
+        if let Some(&recorded) = self.selection_tree.lock().recorded_bodies.get(&body_id) {
+            return recorded;
+        }
+        // Closures and inline consts are selected as part of their item.
+        let root = self.typeck_root_def_id(body_id.to_def_id());
+        let path = with_no_trimmed_paths!(self.def_path_str(root));
+        let recorded = !path.is_empty() && path == item.strip_prefix("crate::").unwrap_or(item);
+        self.selection_tree.lock().recorded_bodies.insert(body_id, recorded);
+        recorded
+    }
+
+    /// Prints the trait selection tree to stdout, for `-Z dump-trait-selection-tree`.
+    pub fn dump_trait_selection_tree(self) {
+        let Some(item) = &self.sess.opts.unstable_opts.dump_trait_selection_tree else {
+            return;
+        };
+        let tree = self.selection_tree.lock();
+        let mut stdout = io::stdout().lock();
+        let _: io::Result<()> = try {
+            if tree.is_empty() {
+                writeln!(stdout, "no obligations of `{item}` were selected")?;
+            } else if self.sess.opts.unstable_opts.dump_trait_selection_tree_graphviz {
+                tree.write_graphviz(&mut stdout)?;
+            } else {
+                writeln!(stdout, "trait selection tree of `{item}`:")?;
+                tree.write_text(&mut stdout)?;
+            }
+        };
+    }
+}
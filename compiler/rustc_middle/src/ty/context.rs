@@ -461,6 +461,10 @@ pub struct GlobalCtxt<'tcx> {
     /// Merge this with `selection_cache`?
     pub evaluation_cache: traits::EvaluationCache<'tcx>,
 
+    /// The candidates considered for the obligations of the item requested with
+    /// `-Z dump-trait-selection-tree`.
+    pub selection_tree: Lock<traits::SelectionTree>,
+
     /// Data layout specification for the current target.
     pub data_layout: TargetDataLayout,
 
@@ -624,6 +628,7 @@ pub fn create_global_ctxt(
             pred_rcache: Default::default(),
             selection_cache: Default::default(),
             evaluation_cache: Default::default(),
+            selection_tree: Default::default(),
             data_layout,
             alloc_map: Lock::new(interpret::AllocMap::new()),
         }
@@ -640,9 +640,67 @@ pub fn polymorphize(self, tcx: TyCtxt<'tcx>) -> Self {
             return self;
         }
 
-        let polymorphized_substs = polymorphize(tcx, self.def, self.substs);
+        // The shims keyed by a type are shared by the closures and generators which only differ
+        // by their unused parameters, like their instances.
+        let def = match self.def {
+            InstanceDef::DropGlue(def_id, Some(ty)) => InstanceDef::DropGlue(
+                def_id,
+                Some(ty.fold_with(&mut PolymorphizationFolder { tcx })),
+            ),
+            InstanceDef::CloneShim(def_id, ty) => {
+                InstanceDef::CloneShim(def_id, ty.fold_with(&mut PolymorphizationFolder { tcx }))
+            }
+            def => def,
+        };
+        let polymorphized_substs = if def == self.def {
+            polymorphize(tcx, self.def, self.substs)
+        } else {
+            self.substs.fold_with(&mut PolymorphizationFolder { tcx })
+        };
         debug!("polymorphize: self={:?} polymorphized_substs={:?}", self, polymorphized_substs);
-        Self { def: self.def, substs: polymorphized_substs }
+        Self { def, substs: polymorphized_substs }
+    }
+}
+
+/// Polymorphizes the closures and generators in a type, see `polymorphize`.
+struct PolymorphizationFolder<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> ty::TypeFolder<'tcx> for PolymorphizationFolder<'tcx> {
+    fn tcx<'a>(&'a self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        debug!("fold_ty: ty={:?}", ty);
+        match *ty.kind() {
+            ty::Closure(def_id, substs) => {
+                let polymorphized_substs = polymorphize(
+                    self.tcx,
+                    ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)),
+                    substs,
+                );
+                if substs == polymorphized_substs {
+                    ty
+                } else {
+                    self.tcx.mk_closure(def_id, polymorphized_substs)
+                }
+            }
+            ty::Generator(def_id, substs, movability) => {
+                let polymorphized_substs = polymorphize(
+                    self.tcx,
+                    ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)),
+                    substs,
+                );
+                if substs == polymorphized_substs {
+                    ty
+                } else {
+                    self.tcx.mk_generator(def_id, polymorphized_substs, movability)
+                }
+            }
+            _ => ty.super_fold_with(self),
+        }
     }
 }
 
@@ -670,47 +728,6 @@ fn polymorphize<'tcx>(
     let has_upvars = upvars_ty.map_or(false, |ty| !ty.tuple_fields().is_empty());
     debug!("polymorphize: upvars_ty={:?} has_upvars={:?}", upvars_ty, has_upvars);
 
-    struct PolymorphizationFolder<'tcx> {
-        tcx: TyCtxt<'tcx>,
-    }
-
-    impl<'tcx> ty::TypeFolder<'tcx> for PolymorphizationFolder<'tcx> {
-        fn tcx<'a>(&'a self) -> TyCtxt<'tcx> {
-            self.tcx
-        }
-
-        fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
-            debug!("fold_ty: ty={:?}", ty);
-            match *ty.kind() {
-                ty::Closure(def_id, substs) => {
-                    let polymorphized_substs = polymorphize(
-                        self.tcx,
-                        ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)),
-                        substs,
-                    );
-                    if substs == polymorphized_substs {
-                        ty
-                    } else {
-                        self.tcx.mk_closure(def_id, polymorphized_substs)
-                    }
-                }
-                ty::Generator(def_id, substs, movability) => {
-                    let polymorphized_substs = polymorphize(
-                        self.tcx,
-                        ty::InstanceDef::Item(ty::WithOptConstParam::unknown(def_id)),
-                        substs,
-                    );
-                    if substs == polymorphized_substs {
-                        ty
-                    } else {
-                        self.tcx.mk_generator(def_id, polymorphized_substs, movability)
-                    }
-                }
-                _ => ty.super_fold_with(self),
-            }
-        }
-    }
-
     InternalSubsts::for_item(tcx, def_id, |param, _| {
         let is_unused = unused.is_unused(param.index);
         debug!("polymorphize: param={:?} is_unused={:?}", param, is_unused);
@@ -731,6 +748,12 @@ fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
                     ty::GenericArg::from(polymorphized_upvars_ty)
                 },
 
+            // Layout case: If parameter is a type parameter only used for its size and
+            // alignment..
+            ty::GenericParamDefKind::Type { .. } if unused.is_used_only_for_layout(param.index) =>
+                // ..then use a type with the same layout, shared by all types with this layout.
+                layout_representative(tcx, substs[param.index as usize].expect_ty()).into(),
+
             // Simple case: If parameter is a const or type parameter..
             ty::GenericParamDefKind::Const { .. } | ty::GenericParamDefKind::Type { .. } if
                 // ..and is within range and unused..
@@ -744,6 +767,22 @@ fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
     })
 }
 
+/// Returns an array of unsigned integers with the same size and alignment as `ty`, or `ty` itself
+/// if its layout can't be computed or no integer type has its alignment.
+fn layout_representative<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Ty<'tcx> {
+    let param_env = ty::ParamEnv::reveal_all();
+    let Ok(layout) = tcx.layout_of(param_env.and(ty)) else { return ty };
+    let ints = [tcx.types.u8, tcx.types.u16, tcx.types.u32, tcx.types.u64, tcx.types.u128];
+    ints.into_iter()
+        .find_map(|int| {
+            let int_layout = tcx.layout_of(param_env.and(int)).ok()?;
+            (int_layout.align.abi == layout.align.abi
+                && int_layout.size.bytes() == int_layout.align.abi.bytes())
+            .then(|| tcx.mk_array(int, layout.size.bytes() / int_layout.size.bytes()))
+        })
+        .unwrap_or(ty)
+}
+
 fn needs_fn_once_adapter_shim(
     actual_closure_kind: ty::ClosureKind,
     trait_closure_kind: ty::ClosureKind,
@@ -776,28 +815,46 @@ fn needs_fn_once_adapter_shim(
     }
 }
 
-// Set bits represent unused generic parameters.
-// An empty set indicates that all parameters are used.
+/// The generic parameters of an item which are unused, or only used for their size and alignment.
+/// Empty sets indicate that all parameters are used.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Decodable, Encodable, HashStable)]
-pub struct UnusedGenericParams(FiniteBitSet<u32>);
+pub struct UnusedGenericParams {
+    unused: FiniteBitSet<u32>,
+    layout_only: FiniteBitSet<u32>,
+}
 
 impl UnusedGenericParams {
     pub fn new_all_unused(amount: u32) -> Self {
-        let mut bitset = FiniteBitSet::new_empty();
-        bitset.set_range(0..amount);
-        Self(bitset)
+        let mut unused = FiniteBitSet::new_empty();
+        unused.set_range(0..amount);
+        Self { unused, layout_only: FiniteBitSet::new_empty() }
     }
 
     pub fn new_all_used() -> Self {
-        Self(FiniteBitSet::new_empty())
+        Self { unused: FiniteBitSet::new_empty(), layout_only: FiniteBitSet::new_empty() }
     }
 
     pub fn mark_used(&mut self, idx: u32) {
-        self.0.clear(idx);
+        self.unused.clear(idx);
+        self.layout_only.clear(idx);
+    }
+
+    /// Marks a parameter as used for its size or alignment, unless it is already used otherwise.
+    pub fn mark_used_for_layout(&mut self, idx: u32) {
+        if self.is_unused(idx) {
+            self.unused.clear(idx);
+            self.layout_only.set(idx);
+        }
     }
 
     pub fn is_unused(&self, idx: u32) -> bool {
-        self.0.contains(idx).unwrap_or(false)
+        self.unused.contains(idx).unwrap_or(false)
+    }
+
+    /// Whether the parameter is only used for its size or alignment, in which case instances can
+    /// be shared by all the types with the same layout.
+    pub fn is_used_only_for_layout(&self, idx: u32) -> bool {
+        self.layout_only.contains(idx).unwrap_or(false)
     }
 
     pub fn is_used(&self, idx: u32) -> bool {
@@ -805,6 +862,6 @@ pub fn is_used(&self, idx: u32) -> bool {
     }
 
     pub fn all_used(&self) -> bool {
-        self.0.is_empty()
+        self.unused.is_empty() && self.layout_only.is_empty()
     }
 }
@@ -225,6 +225,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     crate::mir::coverage::InjectedExpressionId,
     crate::mir::coverage::InjectedExpressionIndex,
     crate::mir::coverage::MappedExpressionIndex,
+    crate::mir::coverage::BlockMarkerId,
     crate::mir::Local,
     crate::mir::Promoted,
     crate::traits::Reveal,
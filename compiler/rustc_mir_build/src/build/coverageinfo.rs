@@ -0,0 +1,118 @@
+use rustc_middle::mir::coverage::{
+    BlockMarkerId, BranchInfo, BranchSpan, CoverageKind, MatchArmSpan,
+};
+use rustc_middle::mir::{BasicBlock, Coverage, SourceInfo, Statement, StatementKind};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::LocalDefId;
+use rustc_span::Span;
+
+use crate::build::{Builder, CFG};
+
+/// Records the branches of a function with `-Z coverage-options=branch`, and injects block markers
+/// into the blocks they jump to, so the `InstrumentCoverage` pass can count them.
+pub(crate) struct BranchInfoBuilder {
+    num_block_markers: usize,
+    branch_spans: Vec<BranchSpan>,
+    match_arm_spans: Vec<MatchArmSpan>,
+}
+
+impl BranchInfoBuilder {
+    /// Creates a new branch info builder, but only if branch coverage instrumentation is enabled
+    /// and `def_id` is a function-like item, which may be instrumented.
+    pub(crate) fn new_if_enabled(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Option<Self> {
+        if tcx.sess.instrument_coverage_branch()
+            && tcx.hir().get_by_def_id(def_id).fn_kind().is_some()
+        {
+            Some(Self { num_block_markers: 0, branch_spans: vec![], match_arm_spans: vec![] })
+        } else {
+            None
+        }
+    }
+
+    fn inject_block_marker(
+        &mut self,
+        cfg: &mut CFG<'_>,
+        source_info: SourceInfo,
+        block: BasicBlock,
+    ) -> BlockMarkerId {
+        let id = BlockMarkerId::from_usize(self.num_block_markers);
+        self.num_block_markers += 1;
+
+        let marker_statement = Statement {
+            source_info,
+            kind: StatementKind::Coverage(Box::new(Coverage {
+                kind: CoverageKind::BlockMarker { id },
+                code_region: None,
+            })),
+        };
+        cfg.push(block, marker_statement);
+
+        id
+    }
+
+    pub(crate) fn into_done(self) -> Option<Box<BranchInfo>> {
+        let Self { num_block_markers, branch_spans, match_arm_spans } = self;
+
+        if num_block_markers == 0 {
+            assert!(branch_spans.is_empty() && match_arm_spans.is_empty());
+            return None;
+        }
+
+        Some(Box::new(BranchInfo { num_block_markers, branch_spans, match_arm_spans }))
+    }
+}
+
+impl Builder<'_, '_> {
+    /// If branch coverage is enabled, records the boolean condition at `span`, which jumps to
+    /// `then_block` when true and to `else_block` when false.
+    pub(crate) fn visit_coverage_branch_condition(
+        &mut self,
+        span: Span,
+        then_block: BasicBlock,
+        else_block: BasicBlock,
+    ) {
+        let Some(branch_info) = self.coverage_branch_info.as_mut() else { return };
+        // Conditions written by macros or desugarings aren't branches of the source code.
+        if span.from_expansion() {
+            return;
+        }
+
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        let true_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, then_block);
+        let false_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, else_block);
+
+        branch_info.branch_spans.push(BranchSpan { span, true_marker, false_marker });
+    }
+
+    /// If branch coverage is enabled, marks `block`, where a `match` at `span` starts testing its
+    /// scrutinee, to count the arms recorded with `visit_coverage_match_arm`.
+    pub(crate) fn visit_coverage_match_start(
+        &mut self,
+        span: Span,
+        block: BasicBlock,
+    ) -> Option<BlockMarkerId> {
+        let branch_info = self.coverage_branch_info.as_mut()?;
+        if span.from_expansion() {
+            return None;
+        }
+
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        Some(branch_info.inject_block_marker(&mut self.cfg, source_info, block))
+    }
+
+    /// Records the `match` arm at `span`, whose body starts in `arm_block`, for the match started
+    /// in the block marked by `match_marker`.
+    pub(crate) fn visit_coverage_match_arm(
+        &mut self,
+        match_marker: BlockMarkerId,
+        span: Span,
+        arm_block: BasicBlock,
+    ) {
+        let Some(branch_info) = self.coverage_branch_info.as_mut() else { return };
+
+        let source_info = SourceInfo { span, scope: self.source_scope };
+        let arm_marker = branch_info.inject_block_marker(&mut self.cfg, source_info, arm_block);
+
+        branch_info.match_arm_spans.push(MatchArmSpan { span, arm_marker, match_marker });
+    }
+}
@@ -59,6 +59,7 @@ pub(super) fn build_custom_mir<'tcx>(
         is_polymorphic: false,
         tainted_by_errors: None,
         injection_phase: None,
+        coverage_branch_info: None,
         pass_count: 0,
     };
 
@@ -178,6 +178,7 @@ pub(crate) fn expr_into_dest(
                     this.cfg.start_new_block(),
                 );
 
+                let lhs_span = this.thir[lhs].span;
                 let lhs = unpack!(block = this.as_local_operand(block, &this.thir[lhs]));
                 let blocks = match op {
                     LogicalOp::And => (else_block, shortcircuit_block),
@@ -185,6 +186,9 @@ pub(crate) fn expr_into_dest(
                 };
                 let term = TerminatorKind::if_(lhs, blocks.0, blocks.1);
                 this.cfg.terminate(block, source_info, term);
+                // Only the LHS is a branch here: the value of the RHS is the value of the whole
+                // expression.
+                this.visit_coverage_branch_condition(lhs_span, blocks.0, blocks.1);
 
                 this.cfg.push_assign_constant(
                     shortcircuit_block,
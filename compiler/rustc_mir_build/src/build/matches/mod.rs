@@ -16,6 +16,7 @@
 };
 use rustc_index::bit_set::BitSet;
 use rustc_middle::middle::region;
+use rustc_middle::mir::coverage::BlockMarkerId;
 use rustc_middle::mir::*;
 use rustc_middle::thir::{self, *};
 use rustc_middle::ty::{self, CanonicalUserTypeAnnotation, Ty};
@@ -64,6 +65,51 @@ pub(crate) fn then_else_break(
 
                 rhs_then_block.unit()
             }
+            // With branch coverage, `||` is lowered like `&&` rather than into a temporary, so each
+            // of its operands is a condition of its own.
+            ExprKind::LogicalOp { op: LogicalOp::Or, lhs, rhs }
+                if this.coverage_branch_info.is_some() =>
+            {
+                let local_scope = this.local_scope();
+                let (lhs_success_block, failure_block) =
+                    this.in_if_then_scope(local_scope, expr_span, |this| {
+                        this.then_else_break(
+                            block,
+                            &this.thir[lhs],
+                            temp_scope_override,
+                            local_scope,
+                            variable_source_info,
+                        )
+                    });
+
+                let rhs_success_block = unpack!(this.then_else_break(
+                    failure_block,
+                    &this.thir[rhs],
+                    temp_scope_override,
+                    break_scope,
+                    variable_source_info,
+                ));
+
+                // The success block of the RHS may already contain statements which must not run
+                // on the success path of the LHS, so both join in a new block.
+                let success_block = this.cfg.start_new_block();
+                this.cfg.goto(lhs_success_block, variable_source_info, success_block);
+                this.cfg.goto(rhs_success_block, variable_source_info, success_block);
+                success_block.unit()
+            }
+            // The temporaries of the condition are already dropped at the end of its scope, so
+            // with branch coverage, look through the `DropTemps` of `if` conditions to find the
+            // operands of `&&` and `||`.
+            ExprKind::Use { source } if this.coverage_branch_info.is_some() => {
+                let source = &this.thir[source];
+                this.then_else_break(
+                    block,
+                    source,
+                    temp_scope_override,
+                    break_scope,
+                    variable_source_info,
+                )
+            }
             ExprKind::Scope { region_scope, lint_level, value } => {
                 let region_scope = (region_scope, this.source_info(expr_span));
                 this.in_scope(region_scope, lint_level, |this| {
@@ -98,6 +144,7 @@ pub(crate) fn then_else_break(
 
                 let source_info = this.source_info(expr_span);
                 this.cfg.terminate(block, source_info, term);
+                this.visit_coverage_branch_condition(expr_span, then_block, else_block);
                 this.break_for_else(else_block, break_scope, source_info);
 
                 then_block.unit()
@@ -168,6 +215,10 @@ pub(crate) fn match_expr(
         let scrutinee_place =
             unpack!(block = self.lower_scrutinee(block, scrutinee, scrutinee_span,));
 
+        // A `match` with a single arm has no branches.
+        let match_start_marker =
+            if arms.len() > 1 { self.visit_coverage_match_start(span, block) } else { None };
+
         let mut arm_candidates = self.create_match_candidates(&scrutinee_place, &arms);
 
         let match_has_guard = arm_candidates.iter().any(|(_, candidate)| candidate.has_guard);
@@ -191,6 +242,7 @@ pub(crate) fn match_expr(
             arm_candidates,
             self.source_info(span),
             fake_borrow_temps,
+            match_start_marker,
         )
     }
 
@@ -323,6 +375,7 @@ fn lower_match_arms(
         arm_candidates: Vec<(&'_ Arm<'tcx>, Candidate<'_, 'tcx>)>,
         outer_source_info: SourceInfo,
         fake_borrow_temps: Vec<(Place<'tcx>, Local)>,
+        match_start_marker: Option<BlockMarkerId>,
     ) -> BlockAnd<()> {
         let arm_end_blocks: Vec<_> = arm_candidates
             .into_iter()
@@ -368,6 +421,11 @@ fn lower_match_arms(
                         this.source_scope = source_scope;
                     }
 
+                    if let Some(match_start_marker) = match_start_marker {
+                        let pattern_span = arm.pattern.span;
+                        this.visit_coverage_match_arm(match_start_marker, pattern_span, arm_block);
+                    }
+
                     this.expr_into_dest(destination, arm_block, &&this.thir[arm.body])
                 })
             })
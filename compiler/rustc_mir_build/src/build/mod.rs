@@ -213,6 +213,8 @@ struct Builder<'a, 'tcx> {
     unit_temp: Option<Place<'tcx>>,
 
     var_debug_info: Vec<VarDebugInfo<'tcx>>,
+
+    coverage_branch_info: Option<coverageinfo::BranchInfoBuilder>,
 }
 
 type CaptureMap<'tcx> = SortedIndexMultiMap<usize, hir::HirId, Capture<'tcx>>;
@@ -751,6 +753,7 @@ fn new(
             var_indices: Default::default(),
             unit_temp: None,
             var_debug_info: vec![],
+            coverage_branch_info: coverageinfo::BranchInfoBuilder::new_if_enabled(tcx, def.did),
         };
 
         assert_eq!(builder.cfg.start_new_block(), START_BLOCK);
@@ -770,7 +773,7 @@ fn finish(self) -> Body<'tcx> {
             }
         }
 
-        Body::new(
+        let mut body = Body::new(
             MirSource::item(self.def_id),
             self.cfg.basic_blocks,
             self.source_scopes,
@@ -781,7 +784,10 @@ fn finish(self) -> Body<'tcx> {
             self.fn_span,
             self.generator_kind,
             self.typeck_results.tainted_by_errors,
-        )
+        );
+        body.coverage_branch_info =
+            self.coverage_branch_info.and_then(|builder| builder.into_done());
+        body
     }
 
     fn args_and_body(
@@ -1046,6 +1052,7 @@ pub(crate) fn parse_float_into_scalar(
 
 mod block;
 mod cfg;
+mod coverageinfo;
 mod custom;
 mod expr;
 mod matches;
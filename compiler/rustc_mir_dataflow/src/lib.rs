@@ -37,6 +37,7 @@
 mod errors;
 mod framework;
 pub mod impls;
+pub mod loops;
 pub mod move_paths;
 pub mod rustc_peek;
 pub mod storage;
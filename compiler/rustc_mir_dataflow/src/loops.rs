@@ -0,0 +1,156 @@
+//! Natural loops of a MIR body.
+//!
+//! A back edge is an edge `latch -> header` of the control-flow graph where `header` dominates
+//! `latch`. The natural loop of `header` is made of `header`, and of all the blocks which can reach
+//! one of its latches without going through `header`. The loops of a body are either disjoint or
+//! nested.
+//!
+//! The cycles of the control-flow graph which have no back edge, i.e. which can be entered from
+//! several blocks, are not loops. Rust code can't create such cycles, but MIR optimizations could.
+
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::{self, BasicBlock, Body};
+
+rustc_index::newtype_index!(
+    /// This index uniquely identifies a loop of a body.
+    pub struct LoopIndex {}
+);
+
+#[derive(Debug)]
+pub struct Loop {
+    /// The block dominating all the blocks of the loop, which is entered from outside the loop and
+    /// from each of the latches.
+    pub header: BasicBlock,
+    /// The blocks with a back edge to the header.
+    pub latches: Vec<BasicBlock>,
+    /// The blocks of the loop, including the header and the blocks of the nested loops.
+    pub blocks: BitSet<BasicBlock>,
+    /// The innermost loop containing this one.
+    pub parent: Option<LoopIndex>,
+    /// The number of loops containing this one.
+    pub depth: usize,
+}
+
+impl Loop {
+    pub fn contains(&self, block: BasicBlock) -> bool {
+        self.blocks.contains(block)
+    }
+
+    /// The edges leaving the loop, as `(source, target)` pairs.
+    pub fn exits<'a>(
+        &'a self,
+        body: &'a Body<'_>,
+    ) -> impl Iterator<Item = (BasicBlock, BasicBlock)> + 'a {
+        self.blocks.iter().flat_map(move |block| {
+            body.basic_blocks[block]
+                .terminator()
+                .successors()
+                .filter(|&target| !self.contains(target))
+                .map(move |target| (block, target))
+        })
+    }
+
+    /// The predecessors of the header from outside the loop.
+    pub fn entries<'a>(&'a self, body: &'a Body<'_>) -> impl Iterator<Item = BasicBlock> + 'a {
+        body.basic_blocks.predecessors()[self.header]
+            .iter()
+            .copied()
+            .filter(|&pred| !self.contains(pred))
+    }
+}
+
+#[derive(Debug)]
+pub struct Loops {
+    loops: IndexVec<LoopIndex, Loop>,
+    /// The innermost loop containing each block.
+    innermost: IndexVec<BasicBlock, Option<LoopIndex>>,
+}
+
+impl Loops {
+    pub fn new(body: &Body<'_>) -> Self {
+        Self::with_dominators(body, &body.basic_blocks.dominators())
+    }
+
+    pub fn with_dominators(body: &Body<'_>, dominators: &Dominators<BasicBlock>) -> Self {
+        let predecessors = body.basic_blocks.predecessors();
+
+        // Find the back edges, grouped by header. Visiting the headers in reverse postorder finds
+        // the loops containing a loop before the loop itself.
+        let mut loops: IndexVec<LoopIndex, Loop> = IndexVec::new();
+        for (header, _) in mir::traversal::reverse_postorder(body) {
+            let latches: Vec<_> = predecessors[header]
+                .iter()
+                .copied()
+                .filter(|&pred| dominators.is_reachable(pred) && dominators.dominates(header, pred))
+                .collect();
+            if !latches.is_empty() {
+                loops.push(Loop {
+                    header,
+                    latches,
+                    blocks: BitSet::new_empty(body.basic_blocks.len()),
+                    parent: None,
+                    depth: 0,
+                });
+            }
+        }
+
+        // Collect the blocks reaching the latches backwards, stopping at the header.
+        for lp in &mut loops {
+            lp.blocks.insert(lp.header);
+            let mut stack = lp.latches.clone();
+            while let Some(block) = stack.pop() {
+                if lp.blocks.insert(block) {
+                    stack.extend(
+                        predecessors[block].iter().filter(|&&pred| dominators.is_reachable(pred)),
+                    );
+                }
+            }
+        }
+
+        // The loops containing a loop have a lower index than the loop itself, so the innermost
+        // loop containing a block is the last one to claim it.
+        let mut innermost = IndexVec::from_elem(None, &body.basic_blocks);
+        for index in loops.indices() {
+            let parent = innermost[loops[index].header];
+            loops[index].parent = parent;
+            loops[index].depth = parent.map_or(1, |parent| loops[parent].depth + 1);
+            for block in loops[index].blocks.iter() {
+                innermost[block] = Some(index);
+            }
+        }
+
+        Loops { loops, innermost }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.loops.is_empty()
+    }
+
+    /// The loops, in an order where each loop comes after the loops containing it.
+    pub fn iter_enumerated(&self) -> impl DoubleEndedIterator<Item = (LoopIndex, &Loop)> + '_ {
+        self.loops.iter_enumerated()
+    }
+
+    /// The innermost loop containing `block`.
+    pub fn innermost_loop(&self, block: BasicBlock) -> Option<LoopIndex> {
+        self.innermost[block]
+    }
+
+    /// The number of loops containing `block`.
+    pub fn loop_depth(&self, block: BasicBlock) -> usize {
+        self.innermost[block].map_or(0, |index| self.loops[index].depth)
+    }
+}
+
+impl std::ops::Index<LoopIndex> for Loops {
+    type Output = Loop;
+
+    fn index(&self, index: LoopIndex) -> &Loop {
+        &self.loops[index]
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,124 @@
+//! Tests for the natural loops of mock bodies, which only have a control-flow graph.
+
+use rustc_index::vec::{Idx, IndexVec};
+use rustc_middle::mir::{self, BasicBlock};
+use rustc_middle::ty;
+use rustc_span::DUMMY_SP;
+
+use super::*;
+
+/// Creates a `mir::Body` whose block `i` jumps to each of `successors[i]`.
+fn mock_body<'tcx>(successors: &[&[usize]]) -> mir::Body<'tcx> {
+    let source_info = mir::SourceInfo::outermost(DUMMY_SP);
+    let dummy_place = mir::Place { local: mir::RETURN_PLACE, projection: ty::List::empty() };
+
+    let blocks: IndexVec<_, _> = successors
+        .iter()
+        .map(|targets| {
+            let kind = match **targets {
+                [] => mir::TerminatorKind::Return,
+                [target] => mir::TerminatorKind::Goto { target: BasicBlock::new(target) },
+                [ref values @ .., otherwise] => mir::TerminatorKind::SwitchInt {
+                    discr: mir::Operand::Copy(dummy_place),
+                    targets: mir::SwitchTargets::new(
+                        values.iter().enumerate().map(|(i, &bb)| (i as u128, BasicBlock::new(bb))),
+                        BasicBlock::new(otherwise),
+                    ),
+                },
+            };
+            mir::BasicBlockData {
+                statements: vec![],
+                terminator: Some(mir::Terminator { source_info, kind }),
+                is_cleanup: false,
+            }
+        })
+        .collect();
+
+    mir::Body::new_cfg_only(blocks)
+}
+
+fn blocks(loop_: &Loop) -> Vec<usize> {
+    loop_.blocks.iter().map(BasicBlock::index).collect()
+}
+
+fn bb(index: usize) -> BasicBlock {
+    BasicBlock::new(index)
+}
+
+#[test]
+fn nested_loops() {
+    let body = mock_body(&[&[1], &[2, 5], &[3, 4], &[2], &[1], &[]]);
+    let loops = Loops::new(&body);
+
+    let all: Vec<_> = loops.iter_enumerated().collect();
+    let [(outer_index, outer), (inner_index, inner)] = all[..] else {
+        panic!("expected two loops, found {all:?}");
+    };
+
+    assert_eq!(outer.header, bb(1));
+    assert_eq!(outer.latches, [bb(4)]);
+    assert_eq!(blocks(outer), [1, 2, 3, 4]);
+    assert_eq!(outer.parent, None);
+    assert_eq!(outer.depth, 1);
+    assert_eq!(outer.entries(&body).collect::<Vec<_>>(), [bb(0)]);
+    assert_eq!(outer.exits(&body).collect::<Vec<_>>(), [(bb(1), bb(5))]);
+
+    assert_eq!(inner.header, bb(2));
+    assert_eq!(inner.latches, [bb(3)]);
+    assert_eq!(blocks(inner), [2, 3]);
+    assert_eq!(inner.parent, Some(outer_index));
+    assert_eq!(inner.depth, 2);
+    assert_eq!(inner.entries(&body).collect::<Vec<_>>(), [bb(1)]);
+    assert_eq!(inner.exits(&body).collect::<Vec<_>>(), [(bb(2), bb(4))]);
+
+    assert_eq!(loops.innermost_loop(bb(0)), None);
+    assert_eq!(loops.innermost_loop(bb(1)), Some(outer_index));
+    assert_eq!(loops.innermost_loop(bb(3)), Some(inner_index));
+    assert_eq!(loops.innermost_loop(bb(4)), Some(outer_index));
+    assert_eq!(loops.innermost_loop(bb(5)), None);
+    let depths: Vec<_> = (0..6).map(|i| loops.loop_depth(bb(i))).collect();
+    assert_eq!(depths, [0, 1, 2, 2, 1, 0]);
+}
+
+#[test]
+fn shared_header() {
+    // Both `bb2` and `bb3` jump back to `bb1`, which makes a single loop with two latches.
+    let body = mock_body(&[&[1], &[2, 3, 4], &[1], &[1], &[]]);
+    let loops = Loops::new(&body);
+
+    let all: Vec<_> = loops.iter_enumerated().map(|(_, loop_)| loop_).collect();
+    let [loop_] = all[..] else {
+        panic!("expected one loop, found {all:?}");
+    };
+    assert_eq!(loop_.header, bb(1));
+    assert_eq!(loop_.latches, [bb(2), bb(3)]);
+    assert_eq!(blocks(loop_), [1, 2, 3]);
+    assert_eq!(loop_.exits(&body).collect::<Vec<_>>(), [(bb(1), bb(4))]);
+    let depths: Vec<_> = (0..5).map(|i| loops.loop_depth(bb(i))).collect();
+    assert_eq!(depths, [0, 1, 1, 1, 0]);
+}
+
+#[test]
+fn irreducible() {
+    // The cycle between `bb1` and `bb2` can be entered from both blocks, so neither of them
+    // dominates the other, and it isn't a loop.
+    let body = mock_body(&[&[1, 2], &[2], &[1, 3], &[]]);
+    let loops = Loops::new(&body);
+    assert!(loops.is_empty());
+    assert!((0..4).all(|i| loops.innermost_loop(bb(i)).is_none()));
+
+    // The same cycle, between `bb2` and `bb3`, in the body of a loop. Only the loop is found, and
+    // it contains the cycle.
+    let body = mock_body(&[&[1], &[2, 3, 5], &[3], &[2, 4], &[1], &[]]);
+    let loops = Loops::new(&body);
+
+    let all: Vec<_> = loops.iter_enumerated().map(|(_, loop_)| loop_).collect();
+    let [loop_] = all[..] else {
+        panic!("expected one loop, found {all:?}");
+    };
+    assert_eq!(loop_.header, bb(1));
+    assert_eq!(loop_.latches, [bb(4)]);
+    assert_eq!(blocks(loop_), [1, 2, 3, 4]);
+    let depths: Vec<_> = (0..6).map(|i| loops.loop_depth(bb(i))).collect();
+    assert_eq!(depths, [0, 1, 1, 1, 1, 0]);
+}
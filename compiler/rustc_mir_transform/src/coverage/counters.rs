@@ -38,15 +38,16 @@ pub fn enable_debug(&mut self) {
     }
 
     /// Makes `CoverageKind` `Counter`s and `Expressions` for the `BasicCoverageBlock`s directly or
-    /// indirectly associated with `CoverageSpans`, and returns additional `Expression`s
-    /// representing intermediate values.
+    /// indirectly associated with `CoverageSpans`, and for the `bcbs_with_branches` that branch
+    /// regions count, and returns additional `Expression`s representing intermediate values.
     pub fn make_bcb_counters(
         &mut self,
         basic_coverage_blocks: &mut CoverageGraph,
         coverage_spans: &[CoverageSpan],
+        bcbs_with_branches: &[BasicCoverageBlock],
     ) -> Result<Vec<CoverageKind>, Error> {
         let mut bcb_counters = BcbCounters::new(self, basic_coverage_blocks);
-        bcb_counters.make_bcb_counters(coverage_spans)
+        bcb_counters.make_bcb_counters(coverage_spans, bcbs_with_branches)
     }
 
     fn make_counter<F>(&mut self, debug_block_label_fn: F) -> CoverageKind
@@ -92,6 +93,16 @@ pub fn make_identity_counter(&mut self, counter_operand: ExpressionOperandId) ->
         })
     }
 
+    /// Makes an `Expression` subtracting `rhs` from `lhs`, such as to count the executions of a
+    /// branching block that did not take a given branch.
+    pub fn make_difference(
+        &mut self,
+        lhs: ExpressionOperandId,
+        rhs: ExpressionOperandId,
+    ) -> CoverageKind {
+        self.make_expression(lhs, Op::Subtract, rhs, || None)
+    }
+
     /// Counter IDs start from one and go up.
     fn next_counter(&mut self) -> CounterValueReference {
         assert!(self.next_counter_id < u32::MAX - self.num_expressions);
@@ -141,6 +152,7 @@ fn new(
     fn make_bcb_counters(
         &mut self,
         coverage_spans: &[CoverageSpan],
+        bcbs_with_branches: &[BasicCoverageBlock],
     ) -> Result<Vec<CoverageKind>, Error> {
         debug!("make_bcb_counters(): adding a counter or expression to each BasicCoverageBlock");
         let num_bcbs = self.basic_coverage_blocks.num_nodes();
@@ -185,6 +197,13 @@ fn make_bcb_counters(
             }
         }
 
+        // Branch regions count the `BasicCoverageBlock`s their branches jump to, which might not
+        // have any `CoverageSpan`, nor be needed by another counter's expression.
+        for &bcb in bcbs_with_branches {
+            debug!("{:?} is the target of a branch. Get or make its counter", bcb);
+            self.get_or_make_counter_operand(bcb, &mut collect_intermediate_expressions)?;
+        }
+
         if traversal.is_complete() {
             Ok(collect_intermediate_expressions)
         } else {
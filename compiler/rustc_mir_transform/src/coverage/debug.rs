@@ -308,6 +308,9 @@ pub fn format_counter(&self, counter_kind: &CoverageKind) -> String {
                 format!("Expression({})", self.format_counter_kind(counter_kind))
             }
             CoverageKind::Unreachable { .. } => "Unreachable".to_owned(),
+            CoverageKind::BlockMarker { .. } | CoverageKind::Branch { .. } => {
+                format!("{:?}", counter_kind)
+            }
         }
     }
 
@@ -523,9 +526,17 @@ pub fn is_enabled(&self) -> bool {
 
     pub fn add_expression_operands(&mut self, expression: &CoverageKind) {
         if let Some(used_expression_operands) = self.some_used_expression_operands.as_mut() {
-            if let CoverageKind::Expression { id, lhs, rhs, .. } = *expression {
-                used_expression_operands.entry(lhs).or_insert_with(Vec::new).push(id);
-                used_expression_operands.entry(rhs).or_insert_with(Vec::new).push(id);
+            match *expression {
+                CoverageKind::Expression { id, lhs, rhs, .. } => {
+                    used_expression_operands.entry(lhs).or_insert_with(Vec::new).push(id);
+                    used_expression_operands.entry(rhs).or_insert_with(Vec::new).push(id);
+                }
+                // Branches have no id of their own, but are still users of their counters.
+                CoverageKind::Branch { true_counter, false_counter } => {
+                    used_expression_operands.entry(true_counter).or_insert_with(Vec::new);
+                    used_expression_operands.entry(false_counter).or_insert_with(Vec::new);
+                }
+                _ => {}
             }
         }
     }
@@ -26,6 +26,7 @@
     TerminatorKind,
 };
 use rustc_middle::ty::TyCtxt;
+use rustc_session::config::CoverageMacroSite;
 use rustc_span::def_id::DefId;
 use rustc_span::source_map::SourceMap;
 use rustc_span::{CharPos, ExpnKind, Pos, SourceFile, Span, Symbol};
@@ -42,6 +43,21 @@ pub fn from_string<T>(message: String) -> Result<T, Error> {
     }
 }
 
+/// A branch recorded while building the MIR (with `-Z coverage-options=branch`), mapped to the
+/// `BasicCoverageBlock`s counting its outcomes. A block is `None` if MIR building marked it, but
+/// it has since been removed as unreachable.
+enum CoverageBranch {
+    /// A boolean condition, which jumps to `true_bcb` when true, and to `false_bcb` when false.
+    Condition {
+        span: Span,
+        true_bcb: Option<BasicCoverageBlock>,
+        false_bcb: Option<BasicCoverageBlock>,
+    },
+    /// A `match` arm, taken each time `arm_bcb` executes, and not taken in the other executions
+    /// of `match_bcb`, where its `match` starts testing the scrutinee.
+    MatchArm { span: Span, arm_bcb: Option<BasicCoverageBlock>, match_bcb: BasicCoverageBlock },
+}
+
 /// Inserts `StatementKind::Coverage` statements that either instrument the binary with injected
 /// counters, via intrinsic `llvm.instrprof.increment`, and/or inject metadata used during codegen
 /// to construct the coverage map.
@@ -174,7 +190,7 @@ fn inject_counters(&'a mut self) {
 
         ////////////////////////////////////////////////////
         // Compute `CoverageSpan`s from the `CoverageGraph`.
-        let coverage_spans = CoverageSpans::generate_coverage_spans(
+        let mut coverage_spans = CoverageSpans::generate_coverage_spans(
             &self.mir_body,
             fn_sig_span,
             body_span,
@@ -192,6 +208,31 @@ fn inject_counters(&'a mut self) {
             );
         }
 
+        ////////////////////////////////////////////////////
+        // With `-Z coverage-options=macros=def-site`, also report the code expanded from macros
+        // at their definitions, in addition to their invocations in the function body.
+        if tcx.sess.opts.unstable_opts.coverage_options.macros == Some(CoverageMacroSite::DefSite) {
+            coverage_spans.extend(spans::macro_def_site_coverage_spans(
+                &self.mir_body,
+                tcx.sess.source_map(),
+                body_span,
+                &self.basic_coverage_blocks,
+            ));
+        }
+
+        ////////////////////////////////////////////////////
+        // Find the `BasicCoverageBlock`s marked by the branches recorded while building the MIR,
+        // if any, so their counts can be reported in branch regions.
+        let coverage_branches = self.extract_coverage_branches();
+        let bcbs_with_branches = coverage_branches
+            .iter()
+            .flat_map(|branch| match *branch {
+                CoverageBranch::Condition { true_bcb, false_bcb, .. } => [true_bcb, false_bcb],
+                CoverageBranch::MatchArm { arm_bcb, match_bcb, .. } => [arm_bcb, Some(match_bcb)],
+            })
+            .flatten()
+            .collect::<Vec<_>>();
+
         ////////////////////////////////////////////////////
         // Create an optimized mix of `Counter`s and `Expression`s for the `CoverageGraph`. Ensure
         // every `CoverageSpan` has a `Counter` or `Expression` assigned to its `BasicCoverageBlock`
@@ -200,12 +241,14 @@ fn inject_counters(&'a mut self) {
         //
         // Intermediate expressions (used to compute other `Expression` values), which have no
         // direct associate to any `BasicCoverageBlock`, are returned in the method `Result`.
-        let intermediate_expressions_or_error = self
-            .coverage_counters
-            .make_bcb_counters(&mut self.basic_coverage_blocks, &coverage_spans);
+        let intermediate_expressions_or_error = self.coverage_counters.make_bcb_counters(
+            &mut self.basic_coverage_blocks,
+            &coverage_spans,
+            &bcbs_with_branches,
+        );
 
         let (result, intermediate_expressions) = match intermediate_expressions_or_error {
-            Ok(intermediate_expressions) => {
+            Ok(mut intermediate_expressions) => {
                 // If debugging, add any intermediate expressions (which are not associated with any
                 // BCB) to the `debug_used_expressions` map.
                 if debug_used_expressions.is_enabled() {
@@ -214,6 +257,16 @@ fn inject_counters(&'a mut self) {
                     }
                 }
 
+                ////////////////////////////////////////////////////
+                // Inject a `Branch` statement for each branch, with the code region of its
+                // condition or arm. This reads the counters of the branch `BasicCoverageBlock`s, so
+                // it must be done before they are taken by the injection of counters below.
+                self.inject_branch_regions(
+                    coverage_branches,
+                    &mut intermediate_expressions,
+                    &mut debug_used_expressions,
+                );
+
                 ////////////////////////////////////////////////////
                 // Remove the counter or edge counter from of each `CoverageSpan`s associated
                 // `BasicCoverageBlock`, and inject a `Coverage` statement into the MIR.
@@ -278,6 +331,98 @@ fn inject_counters(&'a mut self) {
         }
     }
 
+    /// Maps the branches in the MIR body's `coverage_branch_info` to the `BasicCoverageBlock`s that
+    /// contain their block markers. Branches outside of the function body span are ignored.
+    fn extract_coverage_branches(&self) -> Vec<CoverageBranch> {
+        let Some(branch_info) = self.mir_body.coverage_branch_info.as_deref() else {
+            return vec![];
+        };
+
+        let mut block_markers = IndexVec::<BlockMarkerId, Option<BasicBlock>>::from_elem_n(
+            None,
+            branch_info.num_block_markers,
+        );
+        for (bb, data) in self.mir_body.basic_blocks.iter_enumerated() {
+            for statement in &data.statements {
+                if let StatementKind::Coverage(box Coverage {
+                    kind: CoverageKind::BlockMarker { id },
+                    ..
+                }) = statement.kind
+                {
+                    block_markers[id] = Some(bb);
+                }
+            }
+        }
+        let bcb_from_marker = |marker: BlockMarkerId| {
+            block_markers[marker].and_then(|bb| self.basic_coverage_blocks.bcb_from_bb(bb))
+        };
+
+        let body_span = self.body_span;
+        let conditions = branch_info
+            .branch_spans
+            .iter()
+            .filter(|branch_span| body_span.contains(branch_span.span))
+            .map(|&BranchSpan { span, true_marker, false_marker }| CoverageBranch::Condition {
+                span,
+                true_bcb: bcb_from_marker(true_marker),
+                false_bcb: bcb_from_marker(false_marker),
+            });
+        let match_arms = branch_info
+            .match_arm_spans
+            .iter()
+            .filter(|arm_span| body_span.contains(arm_span.span))
+            .filter_map(|&MatchArmSpan { span, arm_marker, match_marker }| {
+                // Without the block where its `match` starts, an arm's count can't be compared
+                // to anything.
+                let match_bcb = bcb_from_marker(match_marker)?;
+                let arm_bcb = bcb_from_marker(arm_marker);
+                Some(CoverageBranch::MatchArm { span, arm_bcb, match_bcb })
+            });
+        conditions.chain(match_arms).collect()
+    }
+
+    /// Inject a `Branch` statement, with the code region of its span, for each of the
+    /// `coverage_branches`. The number of times a branch was taken is the count of the
+    /// `BasicCoverageBlock` it jumps to, or zero if that block was removed. The number of times a
+    /// `match` arm was not taken is computed by a new intermediate expression.
+    fn inject_branch_regions(
+        &mut self,
+        coverage_branches: Vec<CoverageBranch>,
+        intermediate_expressions: &mut Vec<CoverageKind>,
+        debug_used_expressions: &mut debug::UsedExpressions,
+    ) {
+        let source_map = self.tcx.sess.source_map();
+        let body_span = self.body_span;
+        let file_name = Symbol::intern(&self.source_file.name.prefer_remapped().to_string_lossy());
+
+        for coverage_branch in coverage_branches {
+            let (span, true_counter, false_counter) = match coverage_branch {
+                CoverageBranch::Condition { span, true_bcb, false_bcb } => {
+                    (span, self.bcb_counter_operand(true_bcb), self.bcb_counter_operand(false_bcb))
+                }
+                CoverageBranch::MatchArm { span, arm_bcb, match_bcb } => {
+                    let arm_counter = self.bcb_counter_operand(arm_bcb);
+                    let match_counter = self.bcb_counter_operand(Some(match_bcb));
+                    let not_taken =
+                        self.coverage_counters.make_difference(match_counter, arm_counter);
+                    debug_used_expressions.add_expression_operands(&not_taken);
+                    let not_taken_counter = not_taken.as_operand_id();
+                    intermediate_expressions.push(not_taken);
+                    (span, arm_counter, not_taken_counter)
+                }
+            };
+
+            let branch = CoverageKind::Branch { true_counter, false_counter };
+            debug_used_expressions.add_expression_operands(&branch);
+            inject_statement(
+                self.mir_body,
+                branch,
+                mir::START_BLOCK,
+                Some(make_code_region(source_map, file_name, &self.source_file, span, body_span)),
+            );
+        }
+    }
+
     /// Inject a counter for each `CoverageSpan`. There can be multiple `CoverageSpan`s for a given
     /// BCB, but only one actual counter needs to be incremented per BCB. `bb_counters` maps each
     /// `bcb` to its `Counter`, when injected. Subsequent `CoverageSpan`s for a BCB that already has
@@ -320,11 +465,21 @@ fn inject_coverage_span_counters(
                 source_map.span_to_diagnostic_string(body_span)
             );
 
+            let code_region = if self.source_file.contains(span.lo()) {
+                make_code_region(source_map, file_name, &self.source_file, span, body_span)
+            } else {
+                // The definition of a macro, for `-Z coverage-options=macros=def-site`, can be in
+                // another source file than the function body.
+                let source_file = source_map.lookup_source_file(span.lo());
+                let file_name =
+                    Symbol::intern(&source_file.name.prefer_remapped().to_string_lossy());
+                make_code_region(source_map, file_name, &source_file, span, body_span)
+            };
             inject_statement(
                 self.mir_body,
                 counter_kind,
                 self.bcb_leader_bb(bcb),
-                Some(make_code_region(source_map, file_name, &self.source_file, span, body_span)),
+                Some(code_region),
             );
         }
     }
@@ -415,6 +570,12 @@ fn inject_indirect_counters(
         }
     }
 
+    #[inline]
+    fn bcb_counter_operand(&self, bcb: Option<BasicCoverageBlock>) -> ExpressionOperandId {
+        bcb.and_then(|bcb| self.bcb_data(bcb).counter())
+            .map_or(ExpressionOperandId::ZERO, |counter_kind| counter_kind.as_operand_id())
+    }
+
     #[inline]
     fn bcb_leader_bb(&self, bcb: BasicCoverageBlock) -> BasicBlock {
         self.bcb_data(bcb).leader_bb()
@@ -569,6 +730,22 @@ fn get_body_span<'tcx>(
                 break;
             }
         }
+    } else if let Some(macro_site) = tcx.sess.opts.unstable_opts.coverage_options.macros {
+        // A function generated by a macro, such as by a `#[derive]`, may have no source of its own
+        // in the current crate (or be spanned at the definition of a proc macro, in another
+        // crate). With `-Z coverage-options=macros=...`, report it at the macro invocation
+        // instead, unless its spans can be reported at the macro definition.
+        let source_map = tcx.sess.source_map();
+        loop {
+            let expn_data = body_span.ctxt().outer_expn_data();
+            let ExpnKind::Macro { .. } = expn_data.kind else { break };
+            if macro_site == CoverageMacroSite::DefSite
+                && spans::is_local_source_span(source_map, body_span)
+            {
+                break;
+            }
+            body_span = expn_data.call_site;
+        }
     }
 
     body_span
@@ -103,6 +103,10 @@ fn visit_coverage(&mut self, coverage: &Coverage) {
                     self.update_from_expression_operand(u32::from(lhs));
                     self.update_from_expression_operand(u32::from(rhs));
                 }
+                CoverageKind::Branch { true_counter, false_counter } => {
+                    self.update_from_expression_operand(u32::from(true_counter));
+                    self.update_from_expression_operand(u32::from(false_counter));
+                }
                 _ => {}
             }
         } else {
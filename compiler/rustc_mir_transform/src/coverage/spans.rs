@@ -9,11 +9,12 @@
     TerminatorKind,
 };
 use rustc_middle::ty::TyCtxt;
-use rustc_span::source_map::original_sp;
+use rustc_span::def_id::LOCAL_CRATE;
+use rustc_span::source_map::{original_sp, SourceMap};
 use rustc_span::{BytePos, ExpnKind, MacroKind, Span, Symbol};
 
 use std::cell::RefCell;
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 
 #[derive(Debug, Copy, Clone)]
 pub(super) enum CoverageStatement {
@@ -892,3 +893,70 @@ pub(super) fn function_source_span(span: Span, body_span: Span) -> Span {
     let original_span = original_sp(span, body_span).with_ctxt(body_span.ctxt());
     if body_span.contains(original_span) { original_span } else { body_span }
 }
+
+/// Returns a `CoverageSpan` for each distinct location of the code that macros expanded into the
+/// function body, where its spans point (such as into the body of a `macro_rules!` definition),
+/// for `-Z coverage-options=macros=def-site`. The `CoverageSpan`s of `generate_coverage_spans()`
+/// report the same code at its macro invocation, within the `body_span`.
+///
+/// Only locations outside of the `body_span`, in a source file of the current crate, are
+/// returned. Each location is counted by the first `BasicCoverageBlock` that contains it.
+pub(super) fn macro_def_site_coverage_spans(
+    mir_body: &mir::Body<'_>,
+    source_map: &SourceMap,
+    body_span: Span,
+    basic_coverage_blocks: &CoverageGraph,
+) -> Vec<CoverageSpan> {
+    let def_site_span = |span| macro_def_site_span(source_map, span, body_span);
+
+    let mut def_site_spans = Vec::new();
+    for (bcb, bcb_data) in basic_coverage_blocks.iter_enumerated() {
+        for &bb in &bcb_data.basic_blocks {
+            let data = &mir_body[bb];
+            for (index, statement) in data.statements.iter().enumerate() {
+                if let Some(span) = filtered_statement_span(statement)
+                    && let Some(def_span) = def_site_span(span)
+                {
+                    def_site_spans.push(CoverageSpan::for_statement(
+                        statement, def_span, span, bcb, bb, index,
+                    ));
+                }
+            }
+            if let Some(span) = filtered_terminator_span(data.terminator())
+                && let Some(def_span) = def_site_span(span)
+            {
+                def_site_spans.push(CoverageSpan::for_terminator(def_span, span, bcb, bb));
+            }
+        }
+    }
+
+    // Closures are counted by their own MIR.
+    def_site_spans.retain(|covspan| !covspan.is_closure);
+    def_site_spans
+        .sort_by_key(|covspan| (covspan.span.lo(), Reverse(covspan.span.hi()), covspan.bcb));
+    def_site_spans.dedup_by_key(|covspan| (covspan.span.lo(), covspan.span.hi()));
+    def_site_spans
+}
+
+/// Walks up the macro expansions of `span`, and returns the first of their spans outside of the
+/// `body_span` that is in a source file of the current crate, if any.
+fn macro_def_site_span(source_map: &SourceMap, mut span: Span, body_span: Span) -> Option<Span> {
+    while span.from_expansion() {
+        if !body_span.contains(span) && is_local_source_span(source_map, span) {
+            return Some(span);
+        }
+        span = span.ctxt().outer_expn_data().call_site;
+    }
+    None
+}
+
+/// Returns true if `span` is a range of one of the current crate's source files, which coverage
+/// reports can show. Code from other crates, such as the expansion of a macro from the standard
+/// library, or tokens spanned at the definition of a proc macro, is not.
+pub(super) fn is_local_source_span(source_map: &SourceMap, span: Span) -> bool {
+    if span.is_dummy() {
+        return false;
+    }
+    let source_file = source_map.lookup_source_file(span.lo());
+    source_file.cnum == LOCAL_CRATE && !source_file.is_imported() && source_file.contains(span.hi())
+}
@@ -680,7 +680,7 @@ fn test_make_bcb_counters() {
         }
         let mut coverage_counters = counters::CoverageCounters::new(0);
         let intermediate_expressions = coverage_counters
-            .make_bcb_counters(&mut basic_coverage_blocks, &coverage_spans)
+            .make_bcb_counters(&mut basic_coverage_blocks, &coverage_spans, &[])
             .expect("should be Ok");
         assert_eq!(intermediate_expressions.len(), 0);
 
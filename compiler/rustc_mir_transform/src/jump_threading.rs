@@ -0,0 +1,345 @@
+//! A jump threading optimization.
+//!
+//! This pass looks for `SwitchInt` terminators whose discriminant is known along some of the
+//! paths leading to them, e.g. because a block on the path assigns a constant or constructs an
+//! enum variant, and redirects those paths directly to the corresponding target:
+//! ```text
+//! bb0: { _1 = Option::<u32>::Some(_2); goto -> bb2; }
+//! bb1: { _1 = Option::<u32>::None; goto -> bb2; }
+//! bb2: { _3 = discriminant(_1); switchInt(move _3) -> [0: bb3, 1: bb4, otherwise: bb5]; }
+//! ```
+//! becomes
+//! ```text
+//! bb0: { _1 = Option::<u32>::Some(_2); goto -> bb6; }
+//! bb1: { _1 = Option::<u32>::None; goto -> bb7; }
+//! bb6: { _3 = discriminant(_1); goto -> bb4; }
+//! bb7: { _3 = discriminant(_1); goto -> bb3; }
+//! ```
+//! The blocks between the block which determines the discriminant and the switch are duplicated,
+//! so that the other paths going through them are unaffected. `?` desugaring and `match` on
+//! freshly constructed `Option`s and `Result`s produce this shape once inlined.
+//!
+//! The discriminant is followed backwards through copies and discriminant reads of locals whose
+//! address is never taken, so that no statement can modify them indirectly. A switch on the same
+//! value as a previous switch is also threaded along the edges of the previous switch.
+
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{ParamEnv, TyCtxt};
+use rustc_mir_dataflow::value_analysis::excluded_locals;
+use rustc_target::abi::VariantIdx;
+
+use super::simplify::simplify_cfg;
+use crate::MirPass;
+
+/// The maximum number of blocks to walk back from a switch, which is also the maximum number of
+/// blocks duplicated for each threaded path.
+const MAX_BACKTRACK: usize = 5;
+/// The maximum number of statements duplicated for each threaded path.
+const MAX_COST: usize = 100;
+
+pub struct JumpThreading;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(skip_all level = "debug")]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let mut finder = ThreadingFinder {
+            tcx,
+            param_env,
+            body,
+            excluded_locals: excluded_locals(body),
+            opportunities: Vec::new(),
+        };
+        for (bb, data) in body.basic_blocks.iter_enumerated() {
+            finder.find_opportunities(bb, data);
+        }
+
+        let opportunities = finder.opportunities;
+        if opportunities.is_empty() {
+            return;
+        }
+        debug!(?opportunities);
+        for opportunity in opportunities {
+            opportunity.apply(body);
+        }
+
+        // The original blocks may now be unreachable.
+        simplify_cfg(tcx, body);
+    }
+}
+
+/// The value a switch is made on, as seen at some point of a path leading to the switch.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Tracked<'tcx> {
+    /// The value of the place.
+    Value(Place<'tcx>),
+    /// The discriminant of the place.
+    Discriminant(Place<'tcx>),
+}
+
+impl<'tcx> Tracked<'tcx> {
+    fn place(self) -> Place<'tcx> {
+        match self {
+            Tracked::Value(place) | Tracked::Discriminant(place) => place,
+        }
+    }
+}
+
+/// The outcome of walking back through a statement.
+enum Walk {
+    /// The tracked value is not determined yet.
+    Continue,
+    /// The tracked value is known to have the given bits.
+    Known(u128),
+    /// The tracked value can't be followed further back.
+    Stop,
+}
+
+#[derive(Debug)]
+struct ThreadingOpportunity {
+    /// The block whose edge to `path[0]` is redirected.
+    origin: BasicBlock,
+    /// The blocks from the successor of `origin` to the switch, which are duplicated.
+    path: Vec<BasicBlock>,
+    /// The target of the switch for the value known on the edge from `origin`.
+    target: BasicBlock,
+}
+
+impl ThreadingOpportunity {
+    fn apply(self, body: &mut Body<'_>) {
+        let blocks = body.basic_blocks_mut();
+        let mut next = self.target;
+        for &bb in self.path.iter().rev() {
+            let mut data = blocks[bb].clone();
+            data.terminator_mut().kind = TerminatorKind::Goto { target: next };
+            next = blocks.push(data);
+        }
+
+        let entry = self.path[0];
+        match blocks[self.origin].terminator_mut().kind {
+            TerminatorKind::Goto { ref mut target } => *target = next,
+            TerminatorKind::SwitchInt { ref mut targets, .. } => {
+                for target in targets.all_targets_mut() {
+                    if *target == entry {
+                        *target = next;
+                    }
+                }
+            }
+            ref kind => bug!("unexpected terminator for a threaded edge: {kind:?}"),
+        }
+    }
+}
+
+struct ThreadingFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    /// The locals that may be modified through a pointer, which can't be tracked.
+    excluded_locals: IndexVec<Local, bool>,
+    opportunities: Vec<ThreadingOpportunity>,
+}
+
+impl<'a, 'tcx> ThreadingFinder<'a, 'tcx> {
+    fn find_opportunities(&mut self, bb: BasicBlock, data: &'a BasicBlockData<'tcx>) {
+        // Cleanup blocks rely on having a unique parent, do not duplicate them.
+        if data.is_cleanup || data.statements.len() > MAX_COST {
+            return;
+        }
+        let Some((discr, targets)) = data.terminator().kind.as_switch() else { return };
+        let Some(place) = discr.place() else { return };
+
+        let mut tracked = Tracked::Value(place);
+        match self.walk_statements(&mut tracked, data) {
+            Walk::Continue => {}
+            // A value known within the block is handled by `SimplifyConstCondition`.
+            Walk::Known(_) | Walk::Stop => return,
+        }
+        self.find_origins(&mut vec![bb], tracked, targets, data.statements.len());
+    }
+
+    /// Finds the predecessors of `path[0]` where `tracked` is known, walking back through the
+    /// blocks which don't determine it.
+    fn find_origins(
+        &mut self,
+        path: &mut Vec<BasicBlock>,
+        tracked: Tracked<'tcx>,
+        targets: &'a SwitchTargets,
+        cost: usize,
+    ) {
+        let body = self.body;
+        let entry = path[0];
+        let predecessors = &body.basic_blocks.predecessors()[entry];
+        for (i, &pred) in predecessors.iter().enumerate() {
+            if predecessors[..i].contains(&pred) || path.contains(&pred) {
+                continue;
+            }
+            let pred_data = &body.basic_blocks[pred];
+            if pred_data.is_cleanup {
+                continue;
+            }
+
+            let mut pred_tracked = tracked;
+            let walk = match &pred_data.terminator().kind {
+                TerminatorKind::Goto { .. } => self.walk_statements(&mut pred_tracked, pred_data),
+                TerminatorKind::SwitchInt { discr, targets: pred_targets } => {
+                    match self.walk_statements(&mut pred_tracked, pred_data) {
+                        Walk::Continue => {
+                            self.walk_switch(pred_tracked, pred_data, discr, pred_targets, entry)
+                        }
+                        walk => walk,
+                    }
+                }
+                // Other terminators may write to the tracked place, or have an unwind edge.
+                _ => continue,
+            };
+
+            match walk {
+                Walk::Known(value) => self.opportunities.push(ThreadingOpportunity {
+                    origin: pred,
+                    path: path.clone(),
+                    target: targets.target_for_value(value),
+                }),
+                Walk::Stop => {}
+                Walk::Continue => {
+                    let cost = cost + pred_data.statements.len();
+                    if let TerminatorKind::Goto { .. } = pred_data.terminator().kind
+                        && path.len() < MAX_BACKTRACK
+                        && cost <= MAX_COST
+                    {
+                        path.insert(0, pred);
+                        self.find_origins(path, pred_tracked, targets, cost);
+                        path.remove(0);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Checks whether the switch of `data`, which jumps to `target`, is on the value tracked at
+    /// the start of `data`. If so, the value is known on the edge to `target`.
+    fn walk_switch(
+        &self,
+        tracked: Tracked<'tcx>,
+        data: &BasicBlockData<'tcx>,
+        discr: &Operand<'tcx>,
+        targets: &SwitchTargets,
+        target: BasicBlock,
+    ) -> Walk {
+        let Some(place) = discr.place() else { return Walk::Continue };
+        let mut switch_tracked = Tracked::Value(place);
+        if let Walk::Continue = self.walk_statements(&mut switch_tracked, data)
+            && switch_tracked == tracked
+            && let Some(value) = value_for_target(targets, target)
+        {
+            Walk::Known(value)
+        } else {
+            Walk::Continue
+        }
+    }
+
+    /// Walks the statements of `data` backwards, updating what `tracked` refers to.
+    fn walk_statements(&self, tracked: &mut Tracked<'tcx>, data: &BasicBlockData<'tcx>) -> Walk {
+        for stmt in data.statements.iter().rev() {
+            match self.walk_statement(tracked, stmt) {
+                Walk::Continue => {}
+                walk => return walk,
+            }
+        }
+        Walk::Continue
+    }
+
+    fn walk_statement(&self, tracked: &mut Tracked<'tcx>, stmt: &Statement<'tcx>) -> Walk {
+        let place = tracked.place();
+        if self.excluded_locals[place.local]
+            || place.projection.iter().any(|elem| !matches!(elem, ProjectionElem::Field(..)))
+        {
+            return Walk::Stop;
+        }
+
+        match &stmt.kind {
+            StatementKind::Assign(box (lhs, rvalue)) if *lhs == place => match (*tracked, rvalue) {
+                (Tracked::Value(_), Rvalue::Use(Operand::Constant(constant))) => {
+                    let ty = place.ty(self.body, self.tcx).ty;
+                    match constant.literal.try_eval_bits(self.tcx, self.param_env, ty) {
+                        Some(bits) => Walk::Known(bits),
+                        None => Walk::Stop,
+                    }
+                }
+                (
+                    Tracked::Value(_),
+                    Rvalue::Use(Operand::Copy(from) | Operand::Move(from))
+                    | Rvalue::CopyForDeref(from),
+                ) => {
+                    *tracked = Tracked::Value(*from);
+                    Walk::Continue
+                }
+                (Tracked::Value(_), Rvalue::Discriminant(from)) => {
+                    *tracked = Tracked::Discriminant(*from);
+                    Walk::Continue
+                }
+                (
+                    Tracked::Discriminant(_),
+                    Rvalue::Use(Operand::Copy(from) | Operand::Move(from))
+                    | Rvalue::CopyForDeref(from),
+                ) => {
+                    *tracked = Tracked::Discriminant(*from);
+                    Walk::Continue
+                }
+                (
+                    Tracked::Discriminant(_),
+                    Rvalue::Aggregate(box AggregateKind::Adt(_, variant_index, _, _, None), _),
+                ) => self.discriminant_for_variant(place, *variant_index),
+                _ => Walk::Stop,
+            },
+            StatementKind::SetDiscriminant { place: target, variant_index }
+                if **target == place =>
+            {
+                match *tracked {
+                    Tracked::Discriminant(_) => {
+                        self.discriminant_for_variant(place, *variant_index)
+                    }
+                    Tracked::Value(_) => Walk::Stop,
+                }
+            }
+            // Any other write to the local of the tracked place may modify it.
+            StatementKind::Assign(box (target, _))
+            | StatementKind::SetDiscriminant { place: box target, .. }
+            | StatementKind::Deinit(box target)
+            | StatementKind::Retag(_, box target)
+                if target.local == place.local =>
+            {
+                Walk::Stop
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local)
+                if *local == place.local =>
+            {
+                Walk::Stop
+            }
+            _ => Walk::Continue,
+        }
+    }
+
+    fn discriminant_for_variant(&self, place: Place<'tcx>, variant_index: VariantIdx) -> Walk {
+        let ty = place.ty(self.body, self.tcx).ty;
+        match ty.discriminant_for_variant(self.tcx, variant_index) {
+            Some(discr) => Walk::Known(discr.val),
+            None => Walk::Stop,
+        }
+    }
+}
+
+/// Returns the value for which `targets` jumps to `target`, if there is exactly one.
+fn value_for_target(targets: &SwitchTargets, target: BasicBlock) -> Option<u128> {
+    if targets.otherwise() == target {
+        return None;
+    }
+    let mut values = targets.iter().filter(|&(_, bb)| bb == target);
+    let (value, _) = values.next()?;
+    values.next().is_none().then_some(value)
+}
@@ -35,6 +35,7 @@
 };
 use rustc_middle::ty::query::Providers;
 use rustc_middle::ty::{self, TyCtxt, TypeVisitable};
+use rustc_session::ItemTimingKind;
 use rustc_span::sym;
 
 #[macro_use]
@@ -71,8 +72,11 @@
 mod ffi_unwind_calls;
 mod function_item_references;
 mod generator;
+mod gvn;
 mod inline;
 mod instcombine;
+mod jump_threading;
+mod licm;
 mod lower_intrinsics;
 mod lower_slice_len;
 mod match_branches;
@@ -562,8 +566,11 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
             &multiple_return_terminators::MultipleReturnTerminators,
             &instcombine::InstCombine,
             &separate_const_switch::SeparateConstSwitch,
+            &jump_threading::JumpThreading,
             &simplify::SimplifyLocals::new("before-const-prop"),
             &copy_prop::CopyProp,
+            &gvn::GVN,
+            &licm::LoopInvariantCodeMotion,
             //
             // FIXME(#70073): This pass is responsible for both optimization as well as some lints.
             &const_prop::ConstProp,
@@ -596,7 +603,9 @@ fn o1<T>(x: T) -> WithMinOptLevel<T> {
 fn optimized_mir(tcx: TyCtxt<'_>, did: DefId) -> &Body<'_> {
     let did = did.expect_local();
     assert_eq!(ty::WithOptConstParam::try_lookup(did, tcx), None);
-    tcx.arena.alloc(inner_optimized_mir(tcx, did))
+    let body =
+        tcx.sess.time_item(ItemTimingKind::OptimizedMir, did, || inner_optimized_mir(tcx, did));
+    tcx.arena.alloc(body)
 }
 
 fn inner_optimized_mir(tcx: TyCtxt<'_>, did: LocalDefId) -> Body<'_> {
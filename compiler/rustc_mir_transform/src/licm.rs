@@ -0,0 +1,225 @@
+//! Loop-invariant code motion.
+//!
+//! This pass moves the assignments to SSA locals which compute the same value in each iteration of
+//! a loop out of the loop, to its preheader: a block which only jumps to the loop header, and
+//! through which the loop is always entered. An assignment computes the same value in each
+//! iteration when all its operands are constants, or SSA locals assigned outside of the loop or by
+//! assignments moved out of the loop.
+//!
+//! The moved assignments are executed even if the loop is entered but they aren't reached, so we
+//! only move the computations which can't panic nor have undefined behavior, and are cheap: the
+//! unchecked arithmetic, bitwise and comparison operators except divisions and shifts, numeric
+//! casts, and copies, between scalars.
+//!
+//! The loops are visited from the outermost, so assignments are moved out of as many loops as
+//! possible:
+//! ```ignore (MIR)
+//! bb1: {                     // preheader of the outer loop
+//!     _9 = _1 * const 2_u32; // moved here, as `_1` is assigned before the outer loop
+//!     goto -> bb2;
+//! }
+//! bb2: {                     // header of the outer loop
+//!     ...
+//! }
+//! ```
+
+use rustc_data_structures::graph::dominators::Dominators;
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::visit::MutVisitor;
+use rustc_middle::mir::*;
+use rustc_middle::ty::TyCtxt;
+use rustc_mir_dataflow::impls::borrowed_locals;
+use rustc_mir_dataflow::loops::{Loop, Loops};
+
+use crate::ssa::{SsaLocals, StorageRemover};
+use crate::MirPass;
+
+pub struct LoopInvariantCodeMotion;
+
+impl<'tcx> MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+
+        let dominators = body.basic_blocks.dominators();
+        let loops = Loops::with_dominators(body, &dominators);
+        if loops.is_empty() {
+            return;
+        }
+
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let borrowed_locals = borrowed_locals(body);
+        let ssa = SsaLocals::new(tcx, param_env, body, &borrowed_locals);
+        let reverse_postorder: Vec<_> =
+            traversal::reverse_postorder(body).map(|(bb, _)| bb).collect();
+
+        // Find the assignments to move out of each loop before modifying the body.
+        let mut finder = InvariantFinder {
+            body,
+            ssa: &ssa,
+            dominators: &dominators,
+            moved_locals: BitSet::new_empty(body.local_decls.len()),
+        };
+        let mut moves = Vec::new();
+        for (_, lp) in loops.iter_enumerated() {
+            let locations = finder.invariant_assignments(lp, &reverse_postorder);
+            if !locations.is_empty() {
+                moves.push((lp, locations));
+            }
+        }
+        if moves.is_empty() {
+            return;
+        }
+        let moved_locals = finder.moved_locals;
+
+        for (lp, locations) in moves {
+            let statements: Vec<_> = locations
+                .into_iter()
+                .map(|location| {
+                    let block = &mut body.basic_blocks.as_mut_preserves_cfg()[location.block];
+                    let statement = &mut block.statements[location.statement_index];
+                    let moved = statement.clone();
+                    statement.make_nop();
+                    moved
+                })
+                .collect();
+            let preheader = preheader(body, lp);
+            debug!(?lp.header, ?preheader, ?statements);
+            body.basic_blocks_mut()[preheader].statements.extend(statements);
+        }
+
+        // The moved assignments may now be executed while the storage of their locals is dead, so
+        // remove their storage statements.
+        StorageRemover { tcx, reused_locals: moved_locals }.visit_body_preserves_cfg(body);
+    }
+}
+
+struct InvariantFinder<'a, 'tcx> {
+    body: &'a Body<'tcx>,
+    ssa: &'a SsaLocals,
+    dominators: &'a Dominators<BasicBlock>,
+    /// The locals whose assignment is moved out of a loop.
+    moved_locals: BitSet<Local>,
+}
+
+impl<'a, 'tcx> InvariantFinder<'a, 'tcx> {
+    /// Returns the locations of the assignments to move out of `lp`, in an order where each local
+    /// is assigned before it is used.
+    fn invariant_assignments(
+        &mut self,
+        lp: &Loop,
+        reverse_postorder: &[BasicBlock],
+    ) -> Vec<Location> {
+        let header = &self.body.basic_blocks[lp.header];
+        // The start block can't be preceded by a preheader, and the cleanup blocks are entered by
+        // unwinding edges which can't be redirected to one.
+        if lp.header == START_BLOCK || header.is_cleanup {
+            return Vec::new();
+        }
+
+        let mut locations = Vec::new();
+        for &bb in reverse_postorder {
+            if !lp.contains(bb) {
+                continue;
+            }
+            for (statement_index, statement) in
+                self.body.basic_blocks[bb].statements.iter().enumerate()
+            {
+                let StatementKind::Assign(box (place, ref rvalue)) = statement.kind else {
+                    continue;
+                };
+                let Some(local) = place.as_local() else { continue };
+                if self.moved_locals.contains(local)
+                    || !self.is_movable_local(local)
+                    || !self.is_invariant_rvalue(lp, rvalue)
+                {
+                    continue;
+                }
+                self.moved_locals.insert(local);
+                locations.push(Location { block: bb, statement_index });
+            }
+        }
+        locations
+    }
+
+    fn is_movable_local(&self, local: Local) -> bool {
+        let decl = &self.body.local_decls[local];
+        self.ssa.is_ssa(local)
+            && matches!(self.body.local_kind(local), LocalKind::Var | LocalKind::Temp)
+            && decl.ty.is_scalar()
+    }
+
+    /// Whether `rvalue` computes the same value in each iteration of `lp` without side effects.
+    fn is_invariant_rvalue(&self, lp: &Loop, rvalue: &Rvalue<'tcx>) -> bool {
+        match *rvalue {
+            Rvalue::Use(ref operand)
+            | Rvalue::UnaryOp(UnOp::Not | UnOp::Neg, ref operand)
+            | Rvalue::Cast(
+                CastKind::IntToInt
+                | CastKind::IntToFloat
+                | CastKind::FloatToInt
+                | CastKind::FloatToFloat,
+                ref operand,
+                _,
+            ) => self.is_invariant_operand(lp, operand),
+            Rvalue::BinaryOp(op, box (ref lhs, ref rhs)) => {
+                // Divisions and shifts may be guarded by assertions in the loop.
+                !matches!(op, BinOp::Div | BinOp::Rem | BinOp::Shl | BinOp::Shr | BinOp::Offset)
+                    && self.is_invariant_operand(lp, lhs)
+                    && self.is_invariant_operand(lp, rhs)
+            }
+            Rvalue::NullaryOp(NullOp::SizeOf | NullOp::AlignOf, _) => true,
+            _ => false,
+        }
+    }
+
+    fn is_invariant_operand(&self, lp: &Loop, operand: &Operand<'tcx>) -> bool {
+        match *operand {
+            Operand::Constant(_) => true,
+            Operand::Copy(place) | Operand::Move(place) => {
+                let Some(local) = place.as_local() else { return false };
+                if !self.ssa.is_ssa(local) || !self.body.local_decls[local].ty.is_scalar() {
+                    return false;
+                }
+                // Either the local is assigned before entering the loop, or its assignment is
+                // moved out of the loop before this one.
+                self.moved_locals.contains(local)
+                    || self.ssa.assignment_dominates(
+                        self.dominators,
+                        local,
+                        Location { block: lp.header, statement_index: 0 },
+                    )
+            }
+        }
+    }
+}
+
+/// Returns the preheader of `lp`, creating it if the loop is entered from several blocks or from a
+/// block which doesn't only jump to the header.
+fn preheader(body: &mut Body<'_>, lp: &Loop) -> BasicBlock {
+    let entries: Vec<_> = lp.entries(body).collect();
+
+    if let [entry] = entries[..]
+        && let TerminatorKind::Goto { .. } = body.basic_blocks[entry].terminator().kind
+    {
+        return entry;
+    }
+
+    let source_info = body.basic_blocks[lp.header].terminator().source_info;
+    let preheader = body.basic_blocks_mut().push(BasicBlockData::new(Some(Terminator {
+        source_info,
+        kind: TerminatorKind::Goto { target: lp.header },
+    })));
+    for entry in entries {
+        for target in body.basic_blocks_mut()[entry].terminator_mut().successors_mut() {
+            if *target == lp.header {
+                *target = preheader;
+            }
+        }
+    }
+    preheader
+}
@@ -36,6 +36,8 @@ pub struct UnusedGenericParamsHint {
     pub span: Span,
     pub param_spans: Vec<Span>,
     pub param_names: Vec<String>,
+    pub layout_only_param_spans: Vec<Span>,
+    pub layout_only_param_names: Vec<String>,
 }
 
 impl IntoDiagnostic<'_> for UnusedGenericParamsHint {
@@ -52,6 +54,14 @@ fn into_diagnostic(
             // how to combine the two. 😢
             diag.span_label(span, format!("generic parameter `{name}` is unused"));
         }
+        for (span, name) in
+            self.layout_only_param_spans.into_iter().zip(self.layout_only_param_names)
+        {
+            diag.span_label(
+                span,
+                format!("generic parameter `{name}` is only used for its size or alignment"),
+            );
+        }
         diag
     }
 }
@@ -1,4 +1,5 @@
 #![feature(array_windows)]
+#![feature(let_chains)]
 #![recursion_limit = "256"]
 #![allow(rustc::potential_query_instability)]
 #![deny(rustc::untranslatable_diagnostic)]
@@ -21,6 +22,8 @@
 mod polymorphize;
 mod util;
 
+pub use partitioning::dump_mono_bloat;
+
 fn custom_coerce_unsize_info<'tcx>(
     tcx: TyCtxtAt<'tcx>,
     source_ty: Ty<'tcx>,
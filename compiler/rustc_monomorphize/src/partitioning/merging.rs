@@ -55,6 +55,16 @@ pub fn merge_codegen_units<'tcx>(
         );
     }
 
+    name_merged_codegen_units(cx, codegen_units, cgu_contents);
+}
+
+/// Names the codegen units once merging is done. `cgu_contents` maps the name of each codegen
+/// unit to the names of the codegen units that were merged into it, including itself.
+pub fn name_merged_codegen_units<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    codegen_units: &mut [CodegenUnit<'tcx>],
+    cgu_contents: FxHashMap<Symbol, Vec<Symbol>>,
+) {
     let cgu_name_builder = &mut CodegenUnitNameBuilder::new(cx.tcx);
 
     if cx.tcx.sess.opts.incremental.is_some() {
@@ -94,6 +94,7 @@
 
 mod default;
 mod merging;
+mod size_aware;
 
 use std::cmp;
 use std::fs::{self, File};
@@ -108,6 +109,7 @@
 use rustc_middle::mir::mono::{CodegenUnit, Linkage};
 use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::query::Providers;
+use rustc_middle::ty::subst::GenericArgKind;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::{DumpMonoStatsFormat, SwitchWithOptPath};
 use rustc_span::symbol::Symbol;
@@ -158,6 +160,7 @@ fn get_partitioner<'tcx>(tcx: TyCtxt<'tcx>) -> Box<dyn Partitioner<'tcx>> {
 
     match strategy {
         "default" => Box::new(default::DefaultPartitioning),
+        "size" => Box::new(size_aware::SizeAwarePartitioning),
         _ => {
             tcx.sess.emit_fatal(UnknownPartitionStrategy);
         }
@@ -551,6 +554,160 @@ struct MonoItem {
     Ok(())
 }
 
+/// Outputs the size of the code generated for each instantiation of the user-defined generic
+/// functions, grouped by generic function, to a file in the directory given to
+/// `-Z dump-mono-bloat`. Must be called after codegen, so that the instruction counts recorded
+/// by the codegen backend are available.
+pub fn dump_mono_bloat(tcx: TyCtxt<'_>) {
+    if let SwitchWithOptPath::Enabled(ref path) = tcx.sess.opts.unstable_opts.dump_mono_bloat {
+        if let Err(err) = dump_mono_bloat_report(tcx, path, tcx.crate_name(LOCAL_CRATE)) {
+            tcx.sess.emit_fatal(CouldntDumpMonoStats { error: err.to_string() });
+        }
+    }
+}
+
+fn dump_mono_bloat_report(
+    tcx: TyCtxt<'_>,
+    output_directory: &Option<PathBuf>,
+    crate_name: Symbol,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let output_directory = if let Some(ref directory) = output_directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    let format = tcx.sess.opts.unstable_opts.dump_mono_stats_format;
+    let ext = format.extension();
+    let filename = format!("{crate_name}.mono_bloat.{ext}");
+    let output_path = output_directory.join(&filename);
+    let file = File::create(&output_path)?;
+    let mut file = BufWriter::new(file);
+
+    // Count the codegen units each instantiation of a generic function is codegenned in: the
+    // instantiations which are inlined are codegenned again in each codegen unit using them.
+    let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+    let mut copies: FxHashMap<_, usize> = Default::default();
+    for cgu in codegen_units {
+        for (&mono_item, _) in cgu.items() {
+            if let MonoItem::Fn(instance) = mono_item
+                && mono_item.is_user_defined()
+                && instance.substs.non_erasable_generics().next().is_some()
+            {
+                *copies.entry(instance).or_default() += 1;
+            }
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct Instantiation {
+        args: String,
+        copies: usize,
+        mir_size_estimate: usize,
+        /// The number of LLVM instructions, summed over all the copies, if the backend
+        /// recorded them: it doesn't for the codegen units reused from the incremental cache.
+        llvm_instructions: Option<usize>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct GenericItem {
+        name: String,
+        total_mir_size_estimate: usize,
+        total_llvm_instructions: usize,
+        instantiations: Vec<Instantiation>,
+    }
+
+    let instruction_counts = tcx.sess.codegen_instruction_counts.lock();
+    let mut instantiations_per_def_id: FxHashMap<_, Vec<_>> = Default::default();
+    for (instance, copies) in copies {
+        let args = with_no_trimmed_paths!(
+            instance
+                .substs
+                .iter()
+                .filter(|arg| !matches!(arg.unpack(), GenericArgKind::Lifetime(_)))
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        let mono_item = MonoItem::Fn(instance);
+        let symbol_name = mono_item.symbol_name(tcx).name;
+        instantiations_per_def_id.entry(instance.def_id()).or_default().push(Instantiation {
+            args: format!("<{args}>"),
+            copies,
+            mir_size_estimate: copies * mono_item.size_estimate(tcx),
+            llvm_instructions: instruction_counts.get(symbol_name).copied(),
+        });
+    }
+
+    // Output stats sorted by generated size, from heaviest to lightest
+    let size_key = |llvm_instructions: usize, mir_size_estimate: usize| {
+        cmp::Reverse((llvm_instructions, mir_size_estimate))
+    };
+    let mut stats: Vec<_> = instantiations_per_def_id
+        .into_iter()
+        .map(|(def_id, mut instantiations)| {
+            instantiations.sort_unstable_by(|a, b| {
+                size_key(a.llvm_instructions.unwrap_or(0), a.mir_size_estimate)
+                    .cmp(&size_key(b.llvm_instructions.unwrap_or(0), b.mir_size_estimate))
+                    .then_with(|| a.args.cmp(&b.args))
+            });
+            GenericItem {
+                name: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+                total_mir_size_estimate: instantiations.iter().map(|i| i.mir_size_estimate).sum(),
+                total_llvm_instructions: instantiations
+                    .iter()
+                    .filter_map(|i| i.llvm_instructions)
+                    .sum(),
+                instantiations,
+            }
+        })
+        .collect();
+    stats.sort_unstable_by(|a, b| {
+        size_key(a.total_llvm_instructions, a.total_mir_size_estimate)
+            .cmp(&size_key(b.total_llvm_instructions, b.total_mir_size_estimate))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    if !stats.is_empty() {
+        match format {
+            DumpMonoStatsFormat::Json => serde_json::to_writer(file, &stats)?,
+            DumpMonoStatsFormat::Markdown => {
+                writeln!(
+                    file,
+                    "| Item | Instantiation | Copies | Estimated MIR Size | LLVM Instructions |"
+                )?;
+                writeln!(file, "| --- | --- | ---: | ---: | ---: |")?;
+
+                for item in stats {
+                    writeln!(
+                        file,
+                        "| `{}` | {} instantiations | | {} | {} |",
+                        item.name,
+                        item.instantiations.len(),
+                        item.total_mir_size_estimate,
+                        item.total_llvm_instructions
+                    )?;
+                    for Instantiation { args, copies, mir_size_estimate, llvm_instructions } in
+                        item.instantiations
+                    {
+                        let llvm_instructions = match llvm_instructions {
+                            Some(count) => count.to_string(),
+                            None => "-".to_string(),
+                        };
+                        writeln!(
+                            file,
+                            "| | `{args}` | {copies} | {mir_size_estimate} | {llvm_instructions} |"
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 fn codegened_and_inlined_items(tcx: TyCtxt<'_>, (): ()) -> &DefIdSet {
     let (items, cgus) = tcx.collect_and_partition_mono_items(());
     let mut visited = DefIdSet::default();
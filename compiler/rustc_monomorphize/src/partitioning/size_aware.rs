@@ -0,0 +1,252 @@
+//! A partitioning strategy balancing the sizes of codegen units, selected with
+//! `-Z cgu-partitioning-strategy=size`.
+//!
+//! The default strategy places the items of each source-level module into the same codegen unit
+//! and then merges the smallest codegen units together, so a large module ends up as a single
+//! large codegen unit, which is still being optimized long after the others are done. This
+//! strategy places the root items the same way, and then:
+//!
+//! - splits the codegen units which are larger than the average size, keeping the roots which
+//!   inline the same items together, and
+//! - merges each of the smallest codegen units into the one which shares the most inlined items
+//!   with it, as long as the result doesn't exceed the average size.
+//!
+//! Sizes are estimated from the number of MIR statements of the items, including the items which
+//! will be inlined into the codegen unit, since those are codegenned again in each codegen unit
+//! using them. Inlining and internalization are then done as in the default strategy.
+
+use std::cmp;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_middle::mir::mono::{CodegenUnit, Linkage, MonoItem, Visibility};
+use rustc_span::symbol::Symbol;
+
+use super::default::DefaultPartitioning;
+use super::merging;
+use super::{Partitioner, PartitioningCx, PostInliningPartitioning, PreInliningPartitioning};
+
+pub struct SizeAwarePartitioning;
+
+impl<'tcx> Partitioner<'tcx> for SizeAwarePartitioning {
+    fn place_root_mono_items(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        mono_items: &mut dyn Iterator<Item = MonoItem<'tcx>>,
+    ) -> PreInliningPartitioning<'tcx> {
+        DefaultPartitioning.place_root_mono_items(cx, mono_items)
+    }
+
+    fn merge_codegen_units(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        initial_partitioning: &mut PreInliningPartitioning<'tcx>,
+    ) {
+        assert!(cx.target_cgu_count >= 1);
+        let codegen_units = &mut initial_partitioning.codegen_units;
+
+        // Start from a deterministic order, see `merging::merge_codegen_units`.
+        codegen_units.sort_by(|a, b| a.name().as_str().partial_cmp(b.name().as_str()).unwrap());
+
+        let sized: Vec<_> = codegen_units.drain(..).map(|cgu| SizedCgu::new(cx, cgu)).collect();
+        let total_size: usize = sized.iter().map(|cgu| cgu.size).sum();
+        let target_size = (total_size + cx.target_cgu_count - 1) / cx.target_cgu_count;
+
+        // Merging can't make a codegen unit smaller, so split the large ones first.
+        let mut cgus = Vec::new();
+        for cgu in sized {
+            if cgu.size > target_size {
+                cgus.extend(split(cx, cgu, target_size));
+            } else {
+                cgus.push(cgu);
+            }
+        }
+
+        // This map keeps track of what got merged into what.
+        let mut cgu_contents: FxHashMap<Symbol, Vec<Symbol>> =
+            cgus.iter().map(|cgu| (cgu.cgu.name(), vec![cgu.cgu.name()])).collect();
+
+        while cgus.len() > cx.target_cgu_count {
+            // Sort small cgus to the back.
+            cgus.sort_by_key(|cgu| cmp::Reverse(cgu.size));
+            let smallest = cgus.pop().unwrap();
+
+            // Prefer the codegen unit sharing the most inlined items if the result is not too
+            // large, and the smallest one otherwise.
+            let (index, shared) = cgus
+                .iter()
+                .map(|cgu| smallest.shared_size(cx, cgu))
+                .enumerate()
+                .max_by_key(|&(index, shared)| {
+                    let fits = smallest.size + cgus[index].size - shared <= target_size;
+                    (fits, if fits { shared } else { 0 }, cmp::Reverse(cgus[index].size))
+                })
+                .unwrap();
+            let partner = &mut cgus[index];
+
+            debug!(
+                "CodegenUnit {} merged into CodegenUnit {}, sharing {} of inlined items",
+                smallest.cgu.name(),
+                partner.cgu.name(),
+                shared
+            );
+
+            let mut consumed_cgu_names = cgu_contents.remove(&smallest.cgu.name()).unwrap();
+            cgu_contents.get_mut(&partner.cgu.name()).unwrap().append(&mut consumed_cgu_names);
+            partner.merge(smallest, shared);
+        }
+
+        codegen_units.extend(cgus.into_iter().map(|cgu| cgu.cgu));
+        codegen_units.iter_mut().for_each(|cgu| cgu.create_size_estimate(cx.tcx));
+        merging::name_merged_codegen_units(cx, codegen_units, cgu_contents);
+    }
+
+    fn place_inlined_mono_items(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        initial_partitioning: PreInliningPartitioning<'tcx>,
+    ) -> PostInliningPartitioning<'tcx> {
+        DefaultPartitioning.place_inlined_mono_items(cx, initial_partitioning)
+    }
+
+    fn internalize_symbols(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        partitioning: &mut PostInliningPartitioning<'tcx>,
+    ) {
+        DefaultPartitioning.internalize_symbols(cx, partitioning)
+    }
+}
+
+/// A codegen unit, with the items that will be inlined into it.
+struct SizedCgu<'tcx> {
+    cgu: CodegenUnit<'tcx>,
+    inlined: FxHashSet<MonoItem<'tcx>>,
+    /// The estimated size of the roots and of the inlined items.
+    size: usize,
+}
+
+impl<'tcx> SizedCgu<'tcx> {
+    fn new(cx: &PartitioningCx<'_, 'tcx>, cgu: CodegenUnit<'tcx>) -> Self {
+        let mut sized =
+            SizedCgu { cgu: CodegenUnit::new(cgu.name()), inlined: Default::default(), size: 0 };
+        for (root, linkage) in cgu.items_in_deterministic_order(cx.tcx) {
+            sized.add_root(cx, root, linkage, inlined_items(cx, root));
+        }
+        sized
+    }
+
+    fn add_root(
+        &mut self,
+        cx: &PartitioningCx<'_, 'tcx>,
+        root: MonoItem<'tcx>,
+        linkage: (Linkage, Visibility),
+        inlined: FxHashSet<MonoItem<'tcx>>,
+    ) {
+        self.cgu.items_mut().insert(root, linkage);
+        self.size += root.size_estimate(cx.tcx);
+        for item in inlined {
+            if self.inlined.insert(item) {
+                self.size += item.size_estimate(cx.tcx);
+            }
+        }
+    }
+
+    /// The size of the items inlined into both `self` and `other`.
+    fn shared_size(&self, cx: &PartitioningCx<'_, 'tcx>, other: &SizedCgu<'tcx>) -> usize {
+        let (small, large) = if self.inlined.len() <= other.inlined.len() {
+            (&self.inlined, &other.inlined)
+        } else {
+            (&other.inlined, &self.inlined)
+        };
+        small
+            .iter()
+            .filter(|item| large.contains(item))
+            .map(|item| item.size_estimate(cx.tcx))
+            .sum()
+    }
+
+    fn merge(&mut self, other: SizedCgu<'tcx>, shared: usize) {
+        self.size = self.size + other.size - shared;
+        self.cgu.items_mut().extend(other.cgu.items().iter().map(|(&k, &v)| (k, v)));
+        self.inlined.extend(other.inlined);
+    }
+}
+
+/// Returns the items which will be inlined into the codegen unit of `root`.
+fn inlined_items<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    root: MonoItem<'tcx>,
+) -> FxHashSet<MonoItem<'tcx>> {
+    let mut inlined = FxHashSet::default();
+    let mut stack = vec![root];
+    while let Some(item) = stack.pop() {
+        cx.inlining_map.with_inlining_candidates(item, |target| {
+            if inlined.insert(target) {
+                stack.push(target);
+            }
+        });
+    }
+    inlined
+}
+
+/// Splits `cgu` into codegen units of at most `target_size`, unless a single root is larger.
+fn split<'tcx>(
+    cx: &PartitioningCx<'_, 'tcx>,
+    cgu: SizedCgu<'tcx>,
+    target_size: usize,
+) -> Vec<SizedCgu<'tcx>> {
+    let mut roots: Vec<_> = cgu
+        .cgu
+        .items_in_deterministic_order(cx.tcx)
+        .into_iter()
+        .map(|(root, linkage)| {
+            let inlined = inlined_items(cx, root);
+            let size = root.size_estimate(cx.tcx)
+                + inlined.iter().map(|item| item.size_estimate(cx.tcx)).sum::<usize>();
+            (root, linkage, inlined, size)
+        })
+        .collect();
+    // Place the largest roots first, so the smaller ones can fill the gaps.
+    roots.sort_by_key(|&(.., size)| cmp::Reverse(size));
+
+    let name = cgu.cgu.name();
+    let mut parts: Vec<SizedCgu<'tcx>> = Vec::new();
+    for (root, linkage, inlined, size) in roots {
+        // Place the root into the part sharing the most inlined items with it, among those with
+        // enough room left.
+        let part = parts
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                let shared: usize = inlined
+                    .iter()
+                    .filter(|item| part.inlined.contains(item))
+                    .map(|item| item.size_estimate(cx.tcx))
+                    .sum();
+                (index, shared)
+            })
+            .filter(|&(index, shared)| parts[index].size + size - shared <= target_size)
+            .max_by_key(|&(index, shared)| (shared, cmp::Reverse(index)))
+            .map(|(index, _)| index);
+        let part = match part {
+            Some(index) => &mut parts[index],
+            None => {
+                let part_name = if parts.is_empty() {
+                    name
+                } else {
+                    Symbol::intern(&format!("{}-{}", name, parts.len()))
+                };
+                parts.push(SizedCgu {
+                    cgu: CodegenUnit::new(part_name),
+                    inlined: Default::default(),
+                    size: 0,
+                });
+                parts.last_mut().unwrap()
+            }
+        };
+        part.add_root(cx, root, linkage, inlined);
+    }
+
+    debug!("CodegenUnit {} split into {} parts", name, parts.len());
+    parts
+}
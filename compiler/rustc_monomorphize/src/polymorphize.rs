@@ -2,21 +2,23 @@
 //! =========================
 //!
 //! This module implements an analysis of functions, methods and closures to determine which
-//! generic parameters are unused (and eventually, in what ways generic parameters are used - only
-//! for their size, offset of a field, etc.).
+//! generic parameters are unused, and which type parameters are only used for their size and
+//! alignment (eventually, other ways generic parameters are used could be considered - the offset
+//! of a field, etc.).
 
 use rustc_hir::{def::DefKind, def_id::DefId, ConstContext};
 use rustc_middle::mir::{
     self,
     visit::{TyContext, Visitor},
-    Constant, ConstantKind, Local, LocalDecl, Location,
+    Constant, ConstantKind, Local, LocalDecl, Location, NullOp, Operand, Rvalue, Terminator,
+    TerminatorKind,
 };
 use rustc_middle::ty::{
     self,
     query::Providers,
     subst::SubstsRef,
     visit::{TypeSuperVisitable, TypeVisitable, TypeVisitor},
-    Const, Ty, TyCtxt, UnusedGenericParams,
+    Const, ParamTy, Ty, TyCtxt, UnusedGenericParams,
 };
 use rustc_span::symbol::sym;
 use std::ops::ControlFlow;
@@ -188,7 +190,7 @@ fn mark_used_by_default_parameters<'tcx>(
 }
 
 /// Emit errors for the function annotated by `#[rustc_polymorphize_error]`, labelling each generic
-/// parameter which was unused.
+/// parameter which was unused or only used for its size or alignment.
 #[instrument(level = "debug", skip(tcx, generics))]
 fn emit_unused_generic_params_error<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -208,6 +210,8 @@ fn emit_unused_generic_params_error<'tcx>(
 
     let mut param_spans = Vec::new();
     let mut param_names = Vec::new();
+    let mut layout_only_param_spans = Vec::new();
+    let mut layout_only_param_names = Vec::new();
     let mut next_generics = Some(generics);
     while let Some(generics) = next_generics {
         for param in &generics.params {
@@ -216,13 +220,23 @@ fn emit_unused_generic_params_error<'tcx>(
                 let def_span = tcx.def_span(param.def_id);
                 param_spans.push(def_span);
                 param_names.push(param.name.to_string());
+            } else if unused_parameters.is_used_only_for_layout(param.index) {
+                debug!(?param, "(layout)");
+                layout_only_param_spans.push(tcx.def_span(param.def_id));
+                layout_only_param_names.push(param.name.to_string());
             }
         }
 
         next_generics = generics.parent.map(|did| tcx.generics_of(did));
     }
 
-    tcx.sess.emit_err(UnusedGenericParamsHint { span: fn_span, param_spans, param_names });
+    tcx.sess.emit_err(UnusedGenericParamsHint {
+        span: fn_span,
+        param_spans,
+        param_names,
+        layout_only_param_spans,
+        layout_only_param_names,
+    });
 }
 
 /// Visitor used to aggregate generic parameter uses.
@@ -233,6 +247,23 @@ struct MarkUsedGenericParams<'a, 'tcx> {
 }
 
 impl<'a, 'tcx> MarkUsedGenericParams<'a, 'tcx> {
+    /// Returns the type parameter of `func` if it is one of the functions returning the size or
+    /// alignment of a type parameter, which are not lowered to `NullOp`s in unoptimized MIR.
+    fn layout_call_param(&self, func: &Operand<'tcx>) -> Option<ParamTy> {
+        let ty::FnDef(def_id, substs) = *func.constant()?.ty().kind() else { return None };
+        let is_layout_fn = match self.tcx.get_diagnostic_name(def_id) {
+            Some(sym::mem_size_of | sym::mem_align_of) => true,
+            _ => {
+                ty::util::is_intrinsic(self.tcx, def_id)
+                    && matches!(self.tcx.item_name(def_id), sym::size_of | sym::min_align_of)
+            }
+        };
+        match *substs.type_at(0).kind() {
+            ty::Param(param) if is_layout_fn => Some(param),
+            _ => None,
+        }
+    }
+
     /// Invoke `unused_generic_params` on a body contained within the current item (e.g.
     /// a closure, generator or constant).
     #[instrument(level = "debug", skip(self, def_id, substs))]
@@ -273,15 +304,30 @@ fn visit_constant(&mut self, ct: &Constant<'tcx>, location: Location) {
             ConstantKind::Ty(c) => {
                 c.visit_with(self);
             }
-            ConstantKind::Unevaluated(mir::UnevaluatedConst { def, substs: _, promoted }, ty) => {
-                // Avoid considering `T` unused when constants are of the form:
-                //   `<Self as Foo<T>>::foo::promoted[p]`
-                if let Some(p) = promoted {
-                    if self.def_id == def.did && !self.tcx.generics_of(def.did).has_self {
-                        // If there is a promoted, don't look at the substs - since it will always contain
-                        // the generic parameters, instead, traverse the promoted MIR.
-                        let promoted = self.tcx.promoted_mir(def.did);
-                        self.visit_body(&promoted[p]);
+            ConstantKind::Unevaluated(mir::UnevaluatedConst { def, substs, promoted }, ty) => {
+                match promoted {
+                    // Avoid considering `T` unused when constants are of the form:
+                    //   `<Self as Foo<T>>::foo::promoted[p]`
+                    Some(p) => {
+                        if self.def_id == def.did && !self.tcx.generics_of(def.did).has_self {
+                            // If there is a promoted, don't look at the substs - since it will
+                            // always contain the generic parameters, instead, traverse the
+                            // promoted MIR.
+                            let promoted = self.tcx.promoted_mir(def.did);
+                            self.visit_body(&promoted[p]);
+                        }
+                    }
+                    // Other constants are evaluated during codegen with the substitutions of the
+                    // instance, which can't be polymorphized away.
+                    None if matches!(
+                        self.tcx.def_kind(def.did),
+                        DefKind::AnonConst | DefKind::InlineConst
+                    ) =>
+                    {
+                        self.visit_child_body(def.did, substs);
+                    }
+                    None => {
+                        substs.visit_with(self);
                     }
                 }
 
@@ -294,6 +340,31 @@ fn visit_constant(&mut self, ct: &Constant<'tcx>, location: Location) {
     fn visit_ty(&mut self, ty: Ty<'tcx>, _: TyContext) {
         ty.visit_with(self);
     }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::NullaryOp(NullOp::SizeOf | NullOp::AlignOf, ty) = *rvalue
+            && let ty::Param(param) = *ty.kind()
+        {
+            debug!(?param, "(layout)");
+            self.unused_parameters.mark_used_for_layout(param.index);
+            return;
+        }
+
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if let TerminatorKind::Call { func, .. } = &terminator.kind
+            && let Some(param) = self.layout_call_param(func)
+        {
+            // These calls don't have arguments, and return a `usize`.
+            debug!(?param, "(layout)");
+            self.unused_parameters.mark_used_for_layout(param.index);
+            return;
+        }
+
+        self.super_terminator(terminator, location);
+    }
 }
 
 impl<'a, 'tcx> TypeVisitor<'tcx> for MarkUsedGenericParams<'a, 'tcx> {
@@ -348,8 +348,24 @@ pub(crate) fn create_query_frame<
         })
     };
     let ty_adt_id = key.ty_adt_id();
+    // Trait selection only looks at the hidden type of an opaque type for auto traits.
+    let auto_trait_leakage = matches!(
+        kind,
+        dep_graph::DepKind::evaluate_obligation
+            | dep_graph::DepKind::is_freeze_raw
+            | dep_graph::DepKind::is_unpin_raw
+    );
 
-    QueryStackFrame::new(description, span, def_id, def_kind, kind, ty_adt_id, hash)
+    QueryStackFrame::new(
+        description,
+        span,
+        def_id,
+        def_kind,
+        kind,
+        ty_adt_id,
+        auto_trait_leakage,
+        hash,
+    )
 }
 
 fn try_load_from_on_disk_cache<'tcx, Q>(tcx: TyCtxt<'tcx>, dep_node: DepNode)
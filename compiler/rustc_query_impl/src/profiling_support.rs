@@ -218,6 +218,14 @@ pub(crate) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
     tcx.prof.with_profiler(|profiler| {
         let event_id_builder = profiler.event_id_builder();
 
+        // `-Z self-profile-summary` aggregates query events by query name, so it
+        // needs to know which query each invocation belongs to.
+        if profiler.summary_enabled() {
+            let mut query_invocation_ids = Vec::new();
+            query_cache.iter(&mut |_, _, i| query_invocation_ids.push(i.into()));
+            profiler.name_query_invocations(query_name, query_invocation_ids.into_iter());
+        }
+
         // Walk the entire query cache and allocate the appropriate
         // string representations. Each cache entry is uniquely
         // identified by its dep_node_index.
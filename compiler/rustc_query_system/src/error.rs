@@ -1,6 +1,8 @@
 use rustc_macros::{Diagnostic, Subdiagnostic};
 use rustc_session::Limit;
 use rustc_span::{Span, Symbol};
+use std::io;
+use std::path::PathBuf;
 
 #[derive(Subdiagnostic)]
 #[note(query_system_cycle_stack_middle)]
@@ -57,6 +59,22 @@ pub struct Cycle {
     pub alias: Option<Alias>,
     #[subdiagnostic]
     pub cycle_usage: Option<CycleUsage>,
+    #[subdiagnostic]
+    pub opaque_types: Vec<CycleOpaqueType>,
+}
+
+#[derive(Subdiagnostic)]
+#[help(query_system_cycle_opaque_type)]
+pub struct CycleOpaqueType {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(query_system_cycle_graph_write_failed)]
+pub struct CycleGraphWriteFailed {
+    pub path: PathBuf,
+    pub error: io::Error,
 }
 
 #[derive(Diagnostic)]
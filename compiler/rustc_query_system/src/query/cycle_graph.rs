@@ -0,0 +1,196 @@
+//! Writes query cycles to files for `-Z query-cycle-graph`: as a Graphviz graph to look at, and as
+//! JSON for tools which want to find the items involved in the cycle.
+
+use crate::dep_graph::DepKind;
+use crate::error::CycleGraphWriteFailed;
+use crate::query::plumbing::CycleError;
+use crate::query::QueryStackFrame;
+use rustc_graphviz as dot;
+use rustc_session::Session;
+use rustc_span::Span;
+use serde::Serialize;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+#[derive(Serialize)]
+struct CycleGraph {
+    /// The queries of the cycle, each of which requires the next one, and the last one requires
+    /// the first one again.
+    frames: Vec<Frame>,
+    /// The query which required the first query of the cycle, if it isn't part of the cycle.
+    usage: Option<Frame>,
+}
+
+#[derive(Serialize)]
+struct Frame {
+    /// The name of the query, like `type_of`.
+    query: String,
+    description: String,
+    /// Where this query requires the next query.
+    span: Option<SpanLocation>,
+    /// The span of the key of this query, like the definition of the item it is about.
+    key_span: Option<SpanLocation>,
+}
+
+/// A span with 1-based lines and columns, like in the JSON diagnostics.
+#[derive(Serialize)]
+struct SpanLocation {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    line_end: usize,
+    column_end: usize,
+}
+
+impl SpanLocation {
+    fn new(sess: &Session, span: Span) -> Option<SpanLocation> {
+        if span.is_dummy() {
+            return None;
+        }
+        let lo = sess.source_map().lookup_char_pos(span.lo());
+        let hi = sess.source_map().lookup_char_pos(span.hi());
+        Some(SpanLocation {
+            file_name: lo.file.name.prefer_remapped().to_string(),
+            line_start: lo.line,
+            column_start: lo.col.0 + 1,
+            line_end: hi.line,
+            column_end: hi.col.0 + 1,
+        })
+    }
+}
+
+impl fmt::Display for SpanLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file_name, self.line_start, self.column_start)
+    }
+}
+
+impl Frame {
+    fn new<D: DepKind>(sess: &Session, query: &QueryStackFrame<D>, span: Span) -> Frame {
+        Frame {
+            query: format!("{:?}", query.dep_kind),
+            description: query.description.clone(),
+            span: SpanLocation::new(sess, span),
+            key_span: query.span.and_then(|span| SpanLocation::new(sess, span)),
+        }
+    }
+
+    fn label(&self) -> String {
+        let mut label = format!("[{}] {}", self.query, self.description);
+        if let Some(key_span) = &self.key_span {
+            label.push('\n');
+            label.push_str(&key_span.to_string());
+        }
+        label
+    }
+}
+
+/// If `-Z query-cycle-graph` is specified, writes `cycle` to `query-cycle-N.dot` and
+/// `query-cycle-N.json` in the directory given to it, where `N` counts the cycles reported by this
+/// session.
+pub(crate) fn dump_cycle_graph<D: DepKind>(sess: &Session, cycle: &CycleError<D>) {
+    let Some(dir) = &sess.opts.unstable_opts.query_cycle_graph else { return };
+    let stack = &cycle.cycle;
+    let frames = stack
+        .iter()
+        .enumerate()
+        .map(|(i, info)| Frame::new(sess, &info.query, stack[(i + 1) % stack.len()].span))
+        .collect();
+    let usage = cycle.usage.as_ref().map(|(span, query)| Frame::new(sess, query, *span));
+    let graph = CycleGraph { frames, usage };
+
+    let index = sess.query_cycle_graph_count.fetch_add(1, Ordering::Relaxed);
+    let dot_path = dir.join(format!("query-cycle-{index}.dot"));
+    let json_path = dir.join(format!("query-cycle-{index}.json"));
+    let results = [
+        (&dot_path, write_file(&dot_path, |w| dot::render(&graph, w))),
+        (&json_path, write_file(&json_path, |w| Ok(serde_json::to_writer_pretty(w, &graph)?))),
+    ];
+    for (path, result) in results {
+        if let Err(error) = result {
+            sess.emit_warning(CycleGraphWriteFailed { path: path.clone(), error });
+        }
+    }
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write(&mut file)?;
+    file.flush()
+}
+
+/// Nodes are the indices of the frames of the cycle, followed by the usage if there is one.
+/// Edges point from a query to the query it requires.
+type Edge = (usize, usize);
+
+impl CycleGraph {
+    fn node_count(&self) -> usize {
+        self.frames.len() + self.usage.is_some() as usize
+    }
+
+    fn frame(&self, node: usize) -> &Frame {
+        self.frames.get(node).or(self.usage.as_ref()).unwrap()
+    }
+}
+
+impl<'a> dot::Labeller<'a> for CycleGraph {
+    type Node = usize;
+    type Edge = Edge;
+
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new("query_cycle").unwrap()
+    }
+
+    fn node_id(&'a self, n: &usize) -> dot::Id<'a> {
+        dot::Id::new(format!("frame_{n}")).unwrap()
+    }
+
+    fn node_shape(&'a self, n: &usize) -> Option<dot::LabelText<'a>> {
+        let shape = if *n < self.frames.len() { "box" } else { "ellipse" };
+        Some(dot::LabelText::LabelStr(shape.into()))
+    }
+
+    fn node_label(&'a self, n: &usize) -> dot::LabelText<'a> {
+        dot::LabelText::LabelStr(self.frame(*n).label().into())
+    }
+
+    fn edge_label(&'a self, &(source, _): &Edge) -> dot::LabelText<'a> {
+        let label = match &self.frame(source).span {
+            Some(span) => span.to_string(),
+            None => String::new(),
+        };
+        dot::LabelText::LabelStr(label.into())
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for CycleGraph {
+    type Node = usize;
+    type Edge = Edge;
+
+    fn nodes(&'a self) -> dot::Nodes<'a, usize> {
+        (0..self.node_count()).collect()
+    }
+
+    fn edges(&'a self) -> dot::Edges<'a, Edge> {
+        let len = self.frames.len();
+        let mut edges: Vec<_> = (0..len).map(|i| (i, (i + 1) % len)).collect();
+        if self.usage.is_some() {
+            edges.push((len, 0));
+        }
+        edges.into()
+    }
+
+    fn source(&'a self, e: &Edge) -> usize {
+        e.0
+    }
+
+    fn target(&'a self, e: &Edge) -> usize {
+        e.1
+    }
+}
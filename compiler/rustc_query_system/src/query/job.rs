@@ -1,16 +1,16 @@
 use crate::dep_graph::DepKind;
-use crate::error::CycleStack;
+use crate::error::{CycleOpaqueType, CycleStack};
 use crate::query::plumbing::CycleError;
 use crate::query::{QueryContext, QueryStackFrame};
 use core::marker::PhantomData;
 
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
 use rustc_errors::{
     Diagnostic, DiagnosticBuilder, ErrorGuaranteed, Handler, IntoDiagnostic, Level,
 };
 use rustc_hir::def::DefKind;
 use rustc_session::Session;
-use rustc_span::Span;
+use rustc_span::{DesugaringKind, Span};
 
 use std::hash::Hash;
 use std::num::NonZeroU64;
@@ -569,8 +569,9 @@ pub fn deadlock<D: DepKind>(query_map: QueryMap<D>, registry: &rayon_core::Regis
 #[cold]
 pub(crate) fn report_cycle<'a, D: DepKind>(
     sess: &'a Session,
-    CycleError { usage, cycle: stack }: &CycleError<D>,
+    cycle_error: &CycleError<D>,
 ) -> DiagnosticBuilder<'a, ErrorGuaranteed> {
+    let CycleError { usage, cycle: stack } = cycle_error;
     assert!(!stack.is_empty());
 
     let span = stack[0].query.default_span(stack[1 % stack.len()].span);
@@ -602,6 +603,24 @@ pub(crate) fn report_cycle<'a, D: DepKind>(
         None
     };
 
+    // Checking an auto trait of an opaque type requires its hidden type, which is only known after
+    // type-checking its defining item. Explicit bounds avoid such cycles, except for `async fn`s,
+    // whose opaque types can't be given more bounds.
+    let mut opaque_types = FxIndexMap::default();
+    if stack.len() > 1 && stack.iter().any(|entry| entry.query.auto_trait_leakage) {
+        for entry in stack {
+            let query = &entry.query;
+            if query.def_kind != Some(DefKind::OpaqueTy) {
+                continue;
+            }
+            if let (Some(def_id), Some(span)) = (query.def_id, query.span) {
+                if !span.is_desugaring(DesugaringKind::Async) {
+                    opaque_types.insert(def_id, CycleOpaqueType { span });
+                }
+            }
+        }
+    }
+
     let cycle_diag = crate::error::Cycle {
         span,
         cycle_stack,
@@ -609,6 +628,7 @@ pub(crate) fn report_cycle<'a, D: DepKind>(
         alias,
         cycle_usage: cycle_usage,
         stack_count,
+        opaque_types: opaque_types.into_values().collect(),
     };
 
     cycle_diag.into_diagnostic(&sess.parse_sess.span_diagnostic)
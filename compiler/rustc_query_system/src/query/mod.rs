@@ -1,6 +1,8 @@
 mod plumbing;
 pub use self::plumbing::*;
 
+mod cycle_graph;
+
 mod job;
 #[cfg(parallel_compiler)]
 pub use self::job::deadlock;
@@ -33,6 +35,9 @@ pub struct QueryStackFrame<D: DepKind> {
     pub def_id: Option<DefId>,
     pub def_kind: Option<DefKind>,
     pub ty_adt_id: Option<DefId>,
+    /// Whether this query may check an auto trait of an opaque type, which requires its hidden
+    /// type.
+    pub auto_trait_leakage: bool,
     pub dep_kind: D,
     /// This hash is used to deterministically pick
     /// a query to remove cycles in the parallel compiler.
@@ -49,6 +54,7 @@ pub fn new(
         def_kind: Option<DefKind>,
         dep_kind: D,
         ty_adt_id: Option<DefId>,
+        auto_trait_leakage: bool,
         _hash: impl FnOnce() -> u64,
     ) -> Self {
         Self {
@@ -57,6 +63,7 @@ pub fn new(
             def_id,
             def_kind,
             ty_adt_id,
+            auto_trait_leakage,
             dep_kind,
             #[cfg(parallel_compiler)]
             hash: _hash(),
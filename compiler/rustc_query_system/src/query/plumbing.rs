@@ -5,6 +5,7 @@
 use crate::dep_graph::{DepContext, DepKind, DepNode, DepNodeIndex};
 use crate::ich::StableHashingContext;
 use crate::query::caches::QueryCache;
+use crate::query::cycle_graph::dump_cycle_graph;
 use crate::query::job::{report_cycle, QueryInfo, QueryJob, QueryJobId, QueryJobInfo};
 use crate::query::{QueryContext, QueryMap, QuerySideEffects, QueryStackFrame};
 use crate::values::Value;
@@ -147,10 +148,12 @@ fn handle_cycle_error<Tcx, V>(
     use HandleCycleError::*;
     match handler {
         Error => {
+            dump_cycle_graph(tcx.sess(), cycle_error);
             error.emit();
             Value::from_cycle_error(tcx, &cycle_error.cycle)
         }
         Fatal => {
+            dump_cycle_graph(tcx.sess(), cycle_error);
             error.emit();
             tcx.sess().abort_if_errors();
             unreachable!()
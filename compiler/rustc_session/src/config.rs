@@ -3,6 +3,7 @@
 
 pub use crate::options::*;
 
+use crate::lint_config::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::{CanonicalizedPath, NativeLib, NativeLibKind};
 use crate::{early_error, early_warn, Session};
@@ -174,6 +175,30 @@ pub enum InstrumentCoverage {
     Off,
 }
 
+/// Settings for `-Z coverage-options` flag.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CoverageOptions {
+    /// `-Z coverage-options=branch`, add branch regions for the conditions of `if`, `match` arms
+    /// and the operands of `&&` and `||`
+    pub branch: bool,
+    /// `-Z coverage-options=macros=call-site` or `macros=def-site`, choose where code generated
+    /// by macros is reported. By default, code expanded within a function body is reported as part
+    /// of the macro invocation, and functions generated by macros wherever their spans point.
+    pub macros: Option<CoverageMacroSite>,
+}
+
+/// Where `-Z coverage-options=macros=...` reports code generated by macros.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CoverageMacroSite {
+    /// Report generated code at the outermost macro invocation, in the source of the current crate,
+    /// including the functions generated by macros, such as by `#[derive]`.
+    CallSite,
+    /// Report generated code where its spans point, such as into the body of a `macro_rules!`
+    /// definition, if that is in a source file of the current crate. Otherwise, fall back to the
+    /// invocation, like `CallSite`.
+    DefSite,
+}
+
 /// Settings for `-Z instrument-xray` flag.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct InstrumentXRay {
@@ -399,6 +424,11 @@ pub enum ErrorOutputType {
         /// human output.
         json_rendered: HumanReadableErrorType,
     },
+    /// A single SARIF 2.1.0 log for code-scanning tools, written at the end of the session.
+    Sarif {
+        /// Render the log in a human readable way (with indents and newlines).
+        pretty: bool,
+    },
 }
 
 impl Default for ErrorOutputType {
@@ -568,10 +598,45 @@ pub enum PrintRequest {
     CodeModels,
     TlsModels,
     TargetSpec,
+    /// The JSON specs of all built-in targets, keyed by target name.
+    AllTargetSpecs,
     NativeStaticLibs,
     StackProtectorStrategies,
     LinkArgs,
     SplitDebuginfo,
+    /// The expected cfg names and values, in `--check-cfg` syntax.
+    CheckCfg,
+    /// One line per loaded crate: its name, SVH and the path it was loaded from.
+    Deps,
+    /// The language and library features enabled with `#![feature]`.
+    Features,
+    /// The locales that diagnostics can be translated to, see `-Z translate-lang`.
+    AvailableLocales,
+    /// The size of the incremental compilation directory and of its session directories, see
+    /// `-Z incremental-cache-limit`.
+    IncrementalCacheStats,
+}
+
+impl PrintRequest {
+    /// Whether this request can only be used with `-Z unstable-options`.
+    pub fn is_unstable(self) -> bool {
+        matches!(
+            self,
+            PrintRequest::TargetSpec
+                | PrintRequest::AllTargetSpecs
+                | PrintRequest::CheckCfg
+                | PrintRequest::Deps
+                | PrintRequest::Features
+                | PrintRequest::AvailableLocales
+                | PrintRequest::IncrementalCacheStats
+        )
+    }
+
+    /// Whether this request needs the crate to be expanded and its dependencies to be
+    /// loaded, rather than only the session or the crate attributes.
+    pub fn needs_expansion(self) -> bool {
+        matches!(self, PrintRequest::Deps | PrintRequest::Features)
+    }
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -584,6 +649,14 @@ pub enum TraitSolver {
     Next,
 }
 
+/// The locale requested with `-Z translate-lang`.
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub enum TranslateLang {
+    /// The locale requested by the `LC_ALL`, `LC_MESSAGES` and `LANG` environment variables.
+    Auto,
+    Lang(rustc_errors::LanguageIdentifier),
+}
+
 pub enum Input {
     /// Load source code from a file.
     File(PathBuf),
@@ -770,6 +843,7 @@ fn default() -> Options {
             debuginfo: DebugInfo::None,
             lint_opts: Vec::new(),
             lint_cap: None,
+            lint_config: LintConfig::default(),
             describe_lints: false,
             output_types: OutputTypes(BTreeMap::new()),
             search_paths: vec![],
@@ -781,6 +855,7 @@ fn default() -> Options {
             prints: Vec::new(),
             cg: Default::default(),
             error_format: ErrorOutputType::default(),
+            error_format_files: Vec::new(),
             diagnostic_width: None,
             externs: Externs(BTreeMap::new()),
             crate_name: None,
@@ -853,6 +928,8 @@ pub fn diagnostic_handler_flags(&self, can_emit_warnings: bool) -> HandlerFlags
             macro_backtrace: self.macro_backtrace,
             deduplicate_diagnostics: self.deduplicate_diagnostics,
             track_diagnostics: self.track_diagnostics,
+            apply_suggestions: self.apply_suggestions,
+            diagnostic_grouping: self.diagnostic_grouping,
         }
     }
 }
@@ -1410,8 +1487,9 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "Compiler information to print on stdout",
             "[crate-name|file-names|sysroot|target-libdir|cfg|calling-conventions|\
              target-list|target-cpus|target-features|relocation-models|code-models|\
-             tls-models|target-spec-json|native-static-libs|stack-protector-strategies|\
-             link-args]",
+             tls-models|target-spec-json|all-target-specs-json|native-static-libs|\
+             stack-protector-strategies|link-args|split-debuginfo|check-cfg|deps|features|\
+             available-locales|incremental-cache-stats]",
         ),
         opt::flagmulti_s("g", "", "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
@@ -1470,7 +1548,13 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "",
             "error-format",
             "How errors and other messages are produced",
-            "human|json|short",
+            "human|json|short|sarif",
+        ),
+        opt::multi(
+            "",
+            "error-format-file",
+            "Additionally write errors and other messages in the given format to a file",
+            "FORMAT:PATH",
         ),
         opt::multi_s("", "json", "Configure the JSON output of the compiler", "CONFIG"),
         opt::opt_s(
@@ -1494,6 +1578,12 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             "Remap source names in all output (compiler messages and output files)",
             "FROM=TO",
         ),
+        opt::opt(
+            "",
+            "lint-config",
+            "Read lint levels for the crate and for paths within it from a TOML file",
+            "PATH",
+        ),
     ]);
     opts
 }
@@ -1530,6 +1620,16 @@ pub fn get_cmd_lint_options(
     (lint_opts, describe_lints, lint_cap)
 }
 
+/// Parses the `--lint-config` flag.
+pub fn parse_lint_config(matches: &getopts::Matches, error_format: ErrorOutputType) -> LintConfig {
+    match matches.opt_str("lint-config") {
+        Some(path) => LintConfig::load(Path::new(&path)).unwrap_or_else(|e| {
+            early_error(error_format, &format!("failed to load lint config `{path}`: {e}"))
+        }),
+        None => LintConfig::default(),
+    }
+}
+
 /// Parses the `--color` flag.
 pub fn parse_color(matches: &getopts::Matches) -> ColorConfig {
     match matches.opt_str("color").as_deref() {
@@ -1641,22 +1741,15 @@ pub fn parse_error_format(
     // is unstable, it will not be present. We have to use `opts_present` not
     // `opt_present` because the latter will panic.
     let error_format = if matches.opts_present(&["error-format".to_owned()]) {
-        match matches.opt_str("error-format").as_deref() {
-            None | Some("human") => {
-                ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color))
-            }
-            Some("human-annotate-rs") => {
-                ErrorOutputType::HumanReadable(HumanReadableErrorType::AnnotateSnippet(color))
-            }
-            Some("json") => ErrorOutputType::Json { pretty: false, json_rendered },
-            Some("pretty-json") => ErrorOutputType::Json { pretty: true, json_rendered },
-            Some("short") => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
-
-            Some(arg) => early_error(
+        let arg = matches.opt_str("error-format");
+        match error_format_from_name(arg.as_deref().unwrap_or("human"), color, json_rendered) {
+            Some(error_format) => error_format,
+            None => early_error(
                 ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
                 &format!(
-                    "argument for `--error-format` must be `human`, `json` or \
-                     `short` (instead was `{arg}`)"
+                    "argument for `--error-format` must be `human`, `json`, `short` \
+                     or `sarif` (instead was `{}`)",
+                    arg.unwrap()
                 ),
             ),
         }
@@ -1683,6 +1776,60 @@ pub fn parse_error_format(
     error_format
 }
 
+fn error_format_from_name(
+    name: &str,
+    color: ColorConfig,
+    json_rendered: HumanReadableErrorType,
+) -> Option<ErrorOutputType> {
+    Some(match name {
+        "human" => ErrorOutputType::HumanReadable(HumanReadableErrorType::Default(color)),
+        "human-annotate-rs" => {
+            ErrorOutputType::HumanReadable(HumanReadableErrorType::AnnotateSnippet(color))
+        }
+        "json" => ErrorOutputType::Json { pretty: false, json_rendered },
+        "pretty-json" => ErrorOutputType::Json { pretty: true, json_rendered },
+        "short" => ErrorOutputType::HumanReadable(HumanReadableErrorType::Short(color)),
+        "sarif" => ErrorOutputType::Sarif { pretty: false },
+        "pretty-sarif" => ErrorOutputType::Sarif { pretty: true },
+        _ => return None,
+    })
+}
+
+/// Parses the `--error-format-file=FORMAT:PATH` options. Human-readable output
+/// written to files is never colored.
+pub fn parse_error_format_files(
+    matches: &getopts::Matches,
+    error_format: ErrorOutputType,
+    json_rendered: HumanReadableErrorType,
+) -> Vec<(ErrorOutputType, PathBuf)> {
+    matches
+        .opt_strs("error-format-file")
+        .into_iter()
+        .map(|arg| {
+            let Some((format, path)) = arg.split_once(':') else {
+                early_error(
+                    error_format,
+                    &format!(
+                        "argument for `--error-format-file` must be of the form \
+                         `FORMAT:PATH` (instead was `{arg}`)"
+                    ),
+                );
+            };
+            let Some(format) = error_format_from_name(format, ColorConfig::Never, json_rendered)
+            else {
+                early_error(
+                    error_format,
+                    &format!("unknown error format `{format}` in `--error-format-file`"),
+                );
+            };
+            if path.is_empty() {
+                early_error(error_format, "`--error-format-file` requires a path");
+            }
+            (format, PathBuf::from(path))
+        })
+        .collect()
+}
+
 pub fn parse_crate_edition(matches: &getopts::Matches) -> Edition {
     let edition = match matches.opt_str("edition") {
         Some(arg) => Edition::from_str(&arg).unwrap_or_else(|_| {
@@ -1733,6 +1880,12 @@ fn check_error_format_stability(
                 "`--error-format=human-annotate-rs` is unstable",
             );
         }
+        if let ErrorOutputType::Sarif { .. } = error_format {
+            early_error(
+                ErrorOutputType::Json { pretty: false, json_rendered },
+                "`--error-format=sarif` is unstable",
+            );
+        }
     }
 }
 
@@ -1858,22 +2011,28 @@ fn collect_print_requests(
         ("native-static-libs", PrintRequest::NativeStaticLibs),
         ("stack-protector-strategies", PrintRequest::StackProtectorStrategies),
         ("target-spec-json", PrintRequest::TargetSpec),
+        ("all-target-specs-json", PrintRequest::AllTargetSpecs),
         ("link-args", PrintRequest::LinkArgs),
         ("split-debuginfo", PrintRequest::SplitDebuginfo),
+        ("check-cfg", PrintRequest::CheckCfg),
+        ("deps", PrintRequest::Deps),
+        ("features", PrintRequest::Features),
+        ("available-locales", PrintRequest::AvailableLocales),
+        ("incremental-cache-stats", PrintRequest::IncrementalCacheStats),
     ];
 
     prints.extend(matches.opt_strs("print").into_iter().map(|req| {
         match PRINT_REQUESTS.iter().find(|&&(name, _)| name == req) {
-            Some((_, PrintRequest::TargetSpec)) => {
-                if unstable_opts.unstable_options {
-                    PrintRequest::TargetSpec
-                } else {
-                    early_error(
-                        error_format,
+            Some(&(name, print_request))
+                if print_request.is_unstable() && !unstable_opts.unstable_options =>
+            {
+                early_error(
+                    error_format,
+                    &format!(
                         "the `-Z unstable-options` flag must also be passed to \
-                     enable the target-spec-json print option",
-                    );
-                }
+                         enable the {name} print option"
+                    ),
+                );
             }
             Some(&(_, print_request)) => print_request,
             None => {
@@ -2291,6 +2450,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
     } = parse_json(matches);
 
     let error_format = parse_error_format(matches, color, json_rendered);
+    let error_format_files = parse_error_format_files(matches, error_format, json_rendered);
 
     let diagnostic_width = matches.opt_get("diagnostic-width").unwrap_or_else(|_| {
         early_error(error_format, "`--diagnostic-width` must be an positive integer");
@@ -2302,6 +2462,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let mut unstable_opts = UnstableOptions::build(matches, error_format);
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+    let lint_config = parse_lint_config(matches, error_format);
 
     check_error_format_stability(&unstable_opts, error_format, json_rendered);
 
@@ -2460,6 +2621,13 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let prints = collect_print_requests(&mut cg, &mut unstable_opts, matches, error_format);
 
+    if prints.contains(&PrintRequest::IncrementalCacheStats) && incremental.is_none() {
+        early_error(
+            error_format,
+            "the `incremental-cache-stats` print option requires `-C incremental`",
+        );
+    }
+
     let cg = cg;
 
     let sysroot_opt = matches.opt_str("sysroot").map(|m| PathBuf::from(&m));
@@ -2544,6 +2712,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         debuginfo,
         lint_opts,
         lint_cap,
+        lint_config,
         describe_lints,
         output_types,
         search_paths,
@@ -2555,6 +2724,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         prints,
         cg,
         error_format,
+        error_format_files,
         diagnostic_width,
         externs,
         unstable_features: UnstableFeatures::from_environment(crate_name.as_deref()),
@@ -2823,12 +2993,14 @@ pub fn needs_analysis(&self) -> bool {
 /// how the hash should be calculated when adding a new command-line argument.
 pub(crate) mod dep_tracking {
     use super::{
-        BranchProtection, CFGuard, CFProtection, CrateType, DebugInfo, ErrorOutputType,
-        InstrumentCoverage, InstrumentXRay, LdImpl, LinkerPluginLto, LocationDetail, LtoCli,
-        OomStrategy, OptLevel, OutputType, OutputTypes, Passes, SourceFileHashAlgorithm,
-        SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion, TraitSolver, TrimmedDefPaths,
+        BranchProtection, CFGuard, CFProtection, CoverageOptions, CrateType, DebugInfo,
+        ErrorOutputType, InstrumentCoverage, InstrumentXRay, LdImpl, LinkerPluginLto,
+        LocationDetail, LtoCli, OomStrategy, OptLevel, OutputType, OutputTypes, Passes,
+        SourceFileHashAlgorithm, SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion,
+        TraitSolver, TrimmedDefPaths,
     };
     use crate::lint;
+    use crate::lint_config::LintConfig;
     use crate::options::WasiExecModel;
     use crate::utils::{NativeLib, NativeLibKind};
     use rustc_errors::LanguageIdentifier;
@@ -2889,12 +3061,14 @@ fn hash(
         String,
         PathBuf,
         lint::Level,
+        LintConfig,
         WasiExecModel,
         u32,
         RelocModel,
         CodeModel,
         TlsModel,
         InstrumentCoverage,
+        CoverageOptions,
         InstrumentXRay,
         CrateType,
         MergeFunctions,
@@ -2928,6 +3102,7 @@ fn hash(
         OomStrategy,
         LanguageIdentifier,
         TraitSolver,
+        TranslateLang,
     );
 
     impl<T1, T2> DepTrackingHash for (T1, T2)
@@ -1,4 +1,5 @@
 use std::num::NonZeroU32;
+use std::path::PathBuf;
 
 use crate::cgu_reuse_tracker::CguReuse;
 use crate::parse::ParseSess;
@@ -387,3 +388,24 @@ fn fix_base_capitalisation(prefix: &str, suffix: &str) -> Option<String> {
 pub struct OptimisationFuelExhausted {
     pub msg: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(session_suggestion_conflict)]
+pub(crate) struct SuggestionConflict {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_suggestions_file_modified)]
+pub(crate) struct SuggestionsFileModified {
+    pub path: PathBuf,
+}
+
+#[derive(Diagnostic)]
+#[diag(session_failed_to_apply_suggestions)]
+pub(crate) struct FailedToApplySuggestions {
+    pub path: PathBuf,
+    pub err: std::io::Error,
+}
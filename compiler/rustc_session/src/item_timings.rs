@@ -0,0 +1,158 @@
+//! Per-item timings gathered for `-Z time-items`.
+//!
+//! The time of a query is its self time: the time spent in queries it runs for other items is
+//! attributed to those items instead. Trait selection is timed as part of the query that runs it,
+//! and is reported separately as well.
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::profiling::duration_to_secs_str;
+use rustc_data_structures::sync::Lock;
+use rustc_span::def_id::LocalDefId;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// The queries whose time is attributed to the item they run on.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ItemTimingKind {
+    Typeck,
+    MirBorrowck,
+    OptimizedMir,
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ItemTiming {
+    pub typeck: Duration,
+    pub mir_borrowck: Duration,
+    pub optimized_mir: Duration,
+    /// Time spent in trait selection by the queries above, which is included in their times.
+    pub trait_selection: Duration,
+}
+
+impl ItemTiming {
+    pub fn total(&self) -> Duration {
+        self.typeck + self.mir_borrowck + self.optimized_mir
+    }
+}
+
+#[derive(Default)]
+pub struct ItemTimings {
+    items: Lock<FxHashMap<LocalDefId, ItemTiming>>,
+}
+
+#[derive(Copy, Clone)]
+enum FrameKind {
+    Query(ItemTimingKind, LocalDefId),
+    TraitSelection,
+}
+
+struct Frame {
+    kind: FrameKind,
+    start: Instant,
+    /// Time spent in the queries nested in this frame.
+    nested: Duration,
+}
+
+thread_local! {
+    static FRAMES: RefCell<Vec<Frame>> = RefCell::new(Vec::new());
+}
+
+/// Pops its frame when dropped, so that frames are also popped when unwinding from errors.
+struct FrameGuard<'a> {
+    timings: &'a ItemTimings,
+}
+
+impl Drop for FrameGuard<'_> {
+    fn drop(&mut self) {
+        FRAMES.with(|frames| {
+            let mut frames = frames.borrow_mut();
+            let frame = frames.pop().unwrap();
+            let elapsed = frame.start.elapsed();
+            let self_time = elapsed.saturating_sub(frame.nested);
+            match frame.kind {
+                FrameKind::Query(kind, def_id) => {
+                    // Exclude this query from the enclosing query, and from the trait selection
+                    // it happened in, if any.
+                    for parent in frames.iter_mut().rev() {
+                        parent.nested += elapsed;
+                        if let FrameKind::Query(..) = parent.kind {
+                            break;
+                        }
+                    }
+                    let mut items = self.timings.items.lock();
+                    let timing = items.entry(def_id).or_default();
+                    match kind {
+                        ItemTimingKind::Typeck => timing.typeck += self_time,
+                        ItemTimingKind::MirBorrowck => timing.mir_borrowck += self_time,
+                        ItemTimingKind::OptimizedMir => timing.optimized_mir += self_time,
+                    }
+                }
+                FrameKind::TraitSelection => {
+                    let def_id = frames.iter().rev().find_map(|frame| match frame.kind {
+                        FrameKind::Query(_, def_id) => Some(def_id),
+                        FrameKind::TraitSelection => None,
+                    });
+                    // Trait selection outside of the timed queries, e.g. in coherence, is not
+                    // attributed to any item.
+                    if let Some(def_id) = def_id {
+                        self.timings.items.lock().entry(def_id).or_default().trait_selection +=
+                            self_time;
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl ItemTimings {
+    /// Runs `f`, the provider of the `kind` query for `def_id`, and records its self time.
+    pub fn time<R>(&self, kind: ItemTimingKind, def_id: LocalDefId, f: impl FnOnce() -> R) -> R {
+        let _guard = self.push(FrameKind::Query(kind, def_id));
+        f()
+    }
+
+    /// Runs `f`, which selects or evaluates a trait obligation, and records its time for the
+    /// item of the enclosing query. Trait selection nested in `f` is not timed separately.
+    pub fn time_trait_selection<R>(&self, f: impl FnOnce() -> R) -> R {
+        let nested = FRAMES.with(|frames| {
+            matches!(frames.borrow().last(), Some(Frame { kind: FrameKind::TraitSelection, .. }))
+        });
+        if nested {
+            return f();
+        }
+        let _guard = self.push(FrameKind::TraitSelection);
+        f()
+    }
+
+    fn push(&self, kind: FrameKind) -> FrameGuard<'_> {
+        FRAMES.with(|frames| {
+            frames.borrow_mut().push(Frame { kind, start: Instant::now(), nested: Duration::ZERO })
+        });
+        FrameGuard { timings: self }
+    }
+
+    /// Prints the `n` items with the largest total time, using `describe` to name them.
+    pub fn print(&self, n: usize, describe: impl Fn(LocalDefId) -> String) {
+        let items = self.items.lock();
+        let mut sorted: Vec<_> = items.iter().map(|(&def_id, &timing)| (def_id, timing)).collect();
+        sorted.sort_by(|(a_id, a), (b_id, b)| {
+            b.total().cmp(&a.total()).then_with(|| a_id.local_def_index.cmp(&b_id.local_def_index))
+        });
+
+        eprintln!("time-items: {} slowest of {} items", n.min(sorted.len()), sorted.len());
+        eprintln!(
+            "time-items: {:>9} {:>9} {:>9} {:>9} {:>9}  item",
+            "total", "typeck", "borrowck", "opt-mir", "traits"
+        );
+        for (def_id, timing) in sorted.into_iter().take(n) {
+            eprintln!(
+                "time-items: {:>9} {:>9} {:>9} {:>9} {:>9}  {}",
+                duration_to_secs_str(timing.total()),
+                duration_to_secs_str(timing.typeck),
+                duration_to_secs_str(timing.mir_borrowck),
+                duration_to_secs_str(timing.optimized_mir),
+                duration_to_secs_str(timing.trait_selection),
+                describe(def_id),
+            );
+        }
+    }
+}
@@ -29,6 +29,8 @@
 pub mod config;
 pub mod cstore;
 pub mod filesearch;
+mod item_timings;
+pub mod lint_config;
 mod options;
 pub mod search_paths;
 
@@ -0,0 +1,265 @@
+//! Lint levels read from a TOML file passed with `--lint-config`.
+//!
+//! The file sets levels for the whole crate, defines named groups of lints, and sets levels
+//! for the source files matching path globs:
+//!
+//! ```toml
+//! [lints]
+//! unused = "warn"
+//! missing_docs = { level = "deny", priority = 1 }
+//!
+//! [groups]
+//! generated-noise = ["dead_code", "non_snake_case"]
+//!
+//! [[overrides]]
+//! paths = ["src/generated/**"]
+//! lints = { generated-noise = "allow", missing_docs = "allow" }
+//! ```
+//!
+//! Entries of a table are applied by ascending `priority` (default 0), then by name, so a
+//! specific lint can be given a higher priority than the group it belongs to. Path overrides act
+//! like inner attributes at the top of the matching files, so the attributes in those files take
+//! precedence over them. The command-line flags take precedence over both the crate-wide levels
+//! and the path overrides.
+
+use crate::lint::Level;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// The lint levels of a `--lint-config` file, with groups already expanded.
+#[derive(Clone, Debug, Default, Hash, PartialEq)]
+pub struct LintConfig {
+    /// Levels for the whole crate.
+    pub lints: Vec<(String, Level)>,
+    /// Levels for the source files matching path globs, in the order they were written.
+    pub overrides: Vec<LintConfigOverride>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct LintConfigOverride {
+    /// Absolute path globs, see [`glob_matches`].
+    pub paths: Vec<String>,
+    pub lints: Vec<(String, Level)>,
+}
+
+impl LintConfig {
+    /// Reads and parses the file at `path`. Relative globs of path overrides are resolved
+    /// against the directory containing the file.
+    pub fn load(path: &Path) -> Result<LintConfig, String> {
+        let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let path = std::env::current_dir().map_err(|e| e.to_string())?.join(path);
+        LintConfig::parse(&contents, path.parent().unwrap_or(Path::new("")))
+    }
+
+    fn parse(contents: &str, base_dir: &Path) -> Result<LintConfig, String> {
+        let value: toml::Value = contents.parse().map_err(|e: toml::de::Error| e.to_string())?;
+        let Some(root) = value.as_table() else {
+            return Err("expected a table at the top level".to_string());
+        };
+
+        let mut groups = FxHashMap::default();
+        for (key, value) in root {
+            match &key[..] {
+                "lints" | "overrides" => {}
+                "groups" => {
+                    let table = expect_table(value, "groups")?;
+                    for (name, members) in table {
+                        let members = members
+                            .as_array()
+                            .and_then(|members| {
+                                members
+                                    .iter()
+                                    .map(|m| m.as_str().map(normalize_lint_name))
+                                    .collect::<Option<Vec<_>>>()
+                            })
+                            .ok_or_else(|| {
+                                format!("group `{name}` must be an array of lint names")
+                            })?;
+                        groups.insert(normalize_lint_name(name), members);
+                    }
+                }
+                _ => return Err(format!("unknown key `{key}`")),
+            }
+        }
+
+        let lints = match root.get("lints") {
+            Some(value) => parse_lints(expect_table(value, "lints")?, &groups)?,
+            None => Vec::new(),
+        };
+
+        let mut overrides = Vec::new();
+        if let Some(value) = root.get("overrides") {
+            let Some(array) = value.as_array() else {
+                return Err("`overrides` must be an array of tables".to_string());
+            };
+            for value in array {
+                let table = expect_table(value, "overrides")?;
+                if let Some(key) = table.keys().find(|k| *k != "paths" && *k != "lints") {
+                    return Err(format!("unknown key `{key}` in `overrides`"));
+                }
+                let paths = table
+                    .get("paths")
+                    .and_then(|paths| paths.as_array())
+                    .and_then(|paths| {
+                        paths
+                            .iter()
+                            .map(|p| p.as_str().map(|p| absolute_glob(base_dir, p)))
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .ok_or_else(|| "`overrides.paths` must be an array of globs".to_string())?;
+                let lints = match table.get("lints") {
+                    Some(value) => parse_lints(expect_table(value, "overrides.lints")?, &groups)?,
+                    None => Vec::new(),
+                };
+                overrides.push(LintConfigOverride { paths, lints });
+            }
+        }
+
+        Ok(LintConfig { lints, overrides })
+    }
+
+    /// Returns the levels of every path override matching `path`, or `None` if there are
+    /// none. `path` must be absolute.
+    pub fn levels_for_path(&self, path: &Path) -> Option<Vec<(String, Level)>> {
+        let path = path_to_glob_str(&normalize_path(path));
+        let mut levels = Vec::new();
+        for o in &self.overrides {
+            if o.paths.iter().any(|glob| glob_matches(glob, &path)) {
+                levels.extend(o.lints.iter().cloned());
+            }
+        }
+        if levels.is_empty() { None } else { Some(levels) }
+    }
+}
+
+fn expect_table<'a>(value: &'a toml::Value, key: &str) -> Result<&'a toml::value::Table, String> {
+    value.as_table().ok_or_else(|| format!("`{key}` must be a table"))
+}
+
+/// Lint names are accepted in the same forms as on the command line.
+fn normalize_lint_name(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+fn parse_lints(
+    table: &toml::value::Table,
+    groups: &FxHashMap<String, Vec<String>>,
+) -> Result<Vec<(String, Level)>, String> {
+    let mut entries = Vec::new();
+    for (name, value) in table {
+        let (level, priority) = match value {
+            toml::Value::String(level) => (&level[..], 0),
+            toml::Value::Table(t) => {
+                if let Some(key) = t.keys().find(|k| *k != "level" && *k != "priority") {
+                    return Err(format!("unknown key `{key}` for lint `{name}`"));
+                }
+                let level = t.get("level").and_then(|l| l.as_str());
+                let priority = match t.get("priority") {
+                    Some(p) => p
+                        .as_integer()
+                        .ok_or_else(|| format!("priority of lint `{name}` must be an integer"))?,
+                    None => 0,
+                };
+                (level.ok_or_else(|| format!("lint `{name}` is missing a `level`"))?, priority)
+            }
+            _ => return Err(format!("level of lint `{name}` must be a string or a table")),
+        };
+        let level = match level {
+            "force-warn" => Level::ForceWarn(None),
+            _ => Level::from_str(level)
+                .ok_or_else(|| format!("unknown level `{level}` for lint `{name}`"))?,
+        };
+        entries.push((priority, normalize_lint_name(name), level));
+    }
+    entries.sort_by(|(p1, n1, _), (p2, n2, _)| p1.cmp(p2).then_with(|| n1.cmp(n2)));
+
+    let mut lints = Vec::new();
+    for (_, name, level) in entries {
+        expand_group(&name, level, groups, &mut FxHashSet::default(), &mut lints)?;
+    }
+    Ok(lints)
+}
+
+/// Pushes `name` at `level`, replacing user-defined groups by their members.
+fn expand_group(
+    name: &str,
+    level: Level,
+    groups: &FxHashMap<String, Vec<String>>,
+    visiting: &mut FxHashSet<String>,
+    out: &mut Vec<(String, Level)>,
+) -> Result<(), String> {
+    let Some(members) = groups.get(name) else {
+        out.push((name.to_string(), level));
+        return Ok(());
+    };
+    if !visiting.insert(name.to_string()) {
+        return Err(format!("group `{name}` contains itself"));
+    }
+    for member in members {
+        expand_group(member, level, groups, visiting, out)?;
+    }
+    visiting.remove(name);
+    Ok(())
+}
+
+/// Removes `.` components and resolves `..` components lexically.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+fn path_to_glob_str(path: &Path) -> String {
+    let s = path.to_string_lossy();
+    if cfg!(windows) { s.replace('\\', "/") } else { s.into_owned() }
+}
+
+fn absolute_glob(base_dir: &Path, glob: &str) -> String {
+    // Only the leading components without wildcards can go through `Path`, since `..` before a
+    // wildcard must be resolved lexically along with the base directory.
+    let split =
+        glob.find(['*', '?']).map_or(glob.len(), |i| glob[..i].rfind('/').map_or(0, |j| j + 1));
+    let (prefix, rest) = glob.split_at(split);
+    let mut prefix = path_to_glob_str(&normalize_path(&base_dir.join(prefix)));
+    if !rest.is_empty() && !prefix.ends_with('/') {
+        prefix.push('/');
+    }
+    prefix + rest
+}
+
+/// Matches `path` against `glob`, where `**` matches any sequence of characters, `*` any
+/// sequence of characters other than `/`, and `?` any single character other than `/`.
+/// A `**/` component can also match nothing, so `a/**/b` matches `a/b`.
+pub fn glob_matches(glob: &str, path: &str) -> bool {
+    fn matches(glob: &[u8], path: &[u8]) -> bool {
+        match glob {
+            [] => path.is_empty(),
+            [b'*', b'*', b'/', rest @ ..] => {
+                matches(rest, path)
+                    || path
+                        .iter()
+                        .enumerate()
+                        .any(|(i, &c)| c == b'/' && matches(rest, &path[i + 1..]))
+            }
+            [b'*', b'*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+            [b'*', rest @ ..] => {
+                let end = path.iter().position(|&c| c == b'/').unwrap_or(path.len());
+                (0..=end).any(|i| matches(rest, &path[i..]))
+            }
+            [b'?', rest @ ..] => matches!(path, [c, ..] if *c != b'/') && matches(rest, &path[1..]),
+            [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..]),
+        }
+    }
+    matches(glob.as_bytes(), path.as_bytes())
+}
@@ -0,0 +1,187 @@
+use super::*;
+
+fn parse(contents: &str) -> Result<LintConfig, String> {
+    LintConfig::parse(contents, Path::new("/crate"))
+}
+
+fn lints(lints: &[(&str, Level)]) -> Vec<(String, Level)> {
+    lints.iter().map(|&(name, level)| (name.to_string(), level)).collect()
+}
+
+#[test]
+fn test_glob_matches() {
+    assert!(glob_matches("/crate/src/lib.rs", "/crate/src/lib.rs"));
+    assert!(!glob_matches("/crate/src/lib.rs", "/crate/src/lib.rsx"));
+
+    assert!(glob_matches("/crate/*.rs", "/crate/main.rs"));
+    assert!(glob_matches("/crate/*.rs", "/crate/.rs"));
+    assert!(!glob_matches("/crate/*.rs", "/crate/src/main.rs"));
+
+    assert!(glob_matches("/crate/?.rs", "/crate/a.rs"));
+    assert!(!glob_matches("/crate/?.rs", "/crate/ab.rs"));
+    assert!(!glob_matches("/crate?a.rs", "/crate/a.rs"));
+
+    assert!(glob_matches("/crate/**", "/crate/src/generated/a.rs"));
+    assert!(glob_matches("/crate/**.rs", "/crate/src/a.rs"));
+    assert!(!glob_matches("/crate/**", "/other/a.rs"));
+
+    // `**/` also matches no directory at all, but only whole directories.
+    assert!(glob_matches("/crate/**/gen/*.rs", "/crate/gen/a.rs"));
+    assert!(glob_matches("/crate/**/gen/*.rs", "/crate/src/x/gen/a.rs"));
+    assert!(!glob_matches("/crate/**/gen/*.rs", "/crate/src/xgen/a.rs"));
+    assert!(!glob_matches("/crate/**/gen/*.rs", "/crate/gen/x/a.rs"));
+}
+
+#[test]
+fn test_parse() {
+    let config = parse(
+        r#"
+        [lints]
+        unused = "warn"
+        missing-docs = { level = "deny", priority = 1 }
+        dead_code = { level = "force-warn" }
+
+        [groups]
+        generated-noise = ["dead_code", "non-snake-case"]
+
+        [[overrides]]
+        paths = ["src/generated/**", "../shared/*.rs", "/abs/lib.rs"]
+        lints = { generated-noise = "allow" }
+
+        [[overrides]]
+        paths = ["src/generated/mod.rs"]
+        "#,
+    )
+    .unwrap();
+
+    // By ascending priority, then by name.
+    assert_eq!(
+        config.lints,
+        lints(&[
+            ("dead_code", Level::ForceWarn(None)),
+            ("unused", Level::Warn),
+            ("missing_docs", Level::Deny),
+        ])
+    );
+    assert_eq!(
+        config.overrides,
+        [
+            LintConfigOverride {
+                paths: vec![
+                    "/crate/src/generated/**".to_string(),
+                    "/shared/*.rs".to_string(),
+                    "/abs/lib.rs".to_string(),
+                ],
+                lints: lints(&[("dead_code", Level::Allow), ("non_snake_case", Level::Allow)]),
+            },
+            LintConfigOverride {
+                paths: vec!["/crate/src/generated/mod.rs".to_string()],
+                lints: vec![],
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = |contents| parse(contents).unwrap_err();
+    assert_eq!(error("[lint]"), "unknown key `lint`");
+    assert_eq!(error("lints = 1"), "`lints` must be a table");
+    assert_eq!(error("[lints]\nunused = \"loud\""), "unknown level `loud` for lint `unused`");
+    assert_eq!(error("[lints]\nunused = { priority = 1 }"), "lint `unused` is missing a `level`");
+    assert_eq!(
+        error("[lints]\nunused = { level = \"warn\", priority = \"high\" }"),
+        "priority of lint `unused` must be an integer"
+    );
+    assert_eq!(
+        error("[lints]\nunused = { level = \"warn\", reason = \"\" }"),
+        "unknown key `reason` for lint `unused`"
+    );
+    assert_eq!(
+        error("[groups]\nnoise = \"unused\""),
+        "group `noise` must be an array of lint names"
+    );
+    assert_eq!(error("[[overrides]]\nlints = {}"), "`overrides.paths` must be an array of globs");
+    assert_eq!(
+        error("[[overrides]]\npaths = []\nlevels = {}"),
+        "unknown key `levels` in `overrides`"
+    );
+    assert!(parse("[lints").is_err());
+}
+
+#[test]
+fn test_expand_group() {
+    let groups: FxHashMap<_, _> = [
+        ("outer".to_string(), vec!["inner".to_string(), "dead_code".to_string()]),
+        ("inner".to_string(), vec!["unused_parens".to_string(), "unused_braces".to_string()]),
+        ("cycle".to_string(), vec!["dead_code".to_string(), "cycle_back".to_string()]),
+        ("cycle_back".to_string(), vec!["cycle".to_string()]),
+    ]
+    .into_iter()
+    .collect();
+    let expand = |name| {
+        let mut out = Vec::new();
+        expand_group(name, Level::Allow, &groups, &mut FxHashSet::default(), &mut out).map(|()| out)
+    };
+
+    assert_eq!(expand("unused"), Ok(lints(&[("unused", Level::Allow)])));
+    assert_eq!(
+        expand("outer"),
+        Ok(lints(&[
+            ("unused_parens", Level::Allow),
+            ("unused_braces", Level::Allow),
+            ("dead_code", Level::Allow),
+        ]))
+    );
+    assert_eq!(expand("cycle"), Err("group `cycle` contains itself".to_string()));
+}
+
+#[test]
+fn test_group_used_twice() {
+    // A group used by two other groups isn't a cycle.
+    let config = parse(
+        r#"
+        [lints]
+        both = "deny"
+
+        [groups]
+        both = ["first", "second"]
+        first = ["shared"]
+        second = ["shared"]
+        shared = ["dead_code"]
+        "#,
+    )
+    .unwrap();
+    assert_eq!(config.lints, lints(&[("dead_code", Level::Deny), ("dead_code", Level::Deny)]));
+}
+
+#[test]
+fn test_levels_for_path() {
+    let config = parse(
+        r#"
+        [[overrides]]
+        paths = ["src/generated/**"]
+        lints = { dead_code = "allow" }
+
+        [[overrides]]
+        paths = ["src/generated/mod.rs", "src/bindings.rs"]
+        lints = { dead_code = "warn", non_snake_case = "allow" }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(config.levels_for_path(Path::new("/crate/src/main.rs")), None);
+    assert_eq!(
+        config.levels_for_path(Path::new("/crate/src/generated/a.rs")),
+        Some(lints(&[("dead_code", Level::Allow)]))
+    );
+    // In the order of the overrides, with the path normalized first.
+    assert_eq!(
+        config.levels_for_path(Path::new("/crate/src/x/../generated/./mod.rs")),
+        Some(lints(&[
+            ("dead_code", Level::Allow),
+            ("dead_code", Level::Warn),
+            ("non_snake_case", Level::Allow),
+        ]))
+    );
+}
@@ -2,9 +2,11 @@
 
 use crate::early_error;
 use crate::lint;
+use crate::lint_config::LintConfig;
 use crate::search_paths::SearchPath;
 use crate::utils::NativeLib;
-use rustc_errors::LanguageIdentifier;
+use rustc_data_structures::profiling::SelfProfileSummaryFormat;
+use rustc_errors::{Applicability, LanguageIdentifier};
 use rustc_target::spec::{CodeModel, LinkerFlavorCli, MergeFunctions, PanicStrategy, SanitizerSet};
 use rustc_target::spec::{
     RelocModel, RelroLevel, SplitDebuginfo, StackProtector, TargetTriple, TlsModel,
@@ -140,6 +142,8 @@ pub struct Options {
         debuginfo: DebugInfo [TRACKED],
         lint_opts: Vec<(String, lint::Level)> [TRACKED_NO_CRATE_HASH],
         lint_cap: Option<lint::Level> [TRACKED_NO_CRATE_HASH],
+        /// Lint levels read from the file given with `--lint-config`.
+        lint_config: LintConfig [TRACKED_NO_CRATE_HASH],
         describe_lints: bool [UNTRACKED],
         output_types: OutputTypes [TRACKED],
         search_paths: Vec<SearchPath> [UNTRACKED],
@@ -150,6 +154,8 @@ pub struct Options {
 
         test: bool [TRACKED],
         error_format: ErrorOutputType [UNTRACKED],
+        /// Additional diagnostic outputs requested with `--error-format-file`.
+        error_format_files: Vec<(ErrorOutputType, PathBuf)> [UNTRACKED],
         diagnostic_width: Option<usize> [UNTRACKED],
 
         /// If `Some`, enable incremental compilation, using the given
@@ -354,7 +360,7 @@ mod desc {
     pub const parse_string: &str = "a string";
     pub const parse_opt_string: &str = parse_string;
     pub const parse_string_push: &str = parse_string;
-    pub const parse_opt_langid: &str = "a language identifier";
+    pub const parse_translate_lang: &str = "`auto` or a language identifier";
     pub const parse_opt_pathbuf: &str = "a path";
     pub const parse_list: &str = "a space-separated list of strings";
     pub const parse_list_with_polarity: &str =
@@ -378,8 +384,13 @@ mod desc {
     pub const parse_optimization_fuel: &str = "crate=integer";
     pub const parse_mir_spanview: &str = "`statement` (default), `terminator`, or `block`";
     pub const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub const parse_self_profile_summary: &str = "`text` (default) or `json`";
+    pub const parse_opt_applicability: &str =
+        "one of: `machine-applicable`, `maybe-incorrect`, `has-placeholders`, or `unspecified`";
     pub const parse_instrument_coverage: &str =
         "`all` (default), `except-unused-generics`, `except-unused-functions`, or `off`";
+    pub const parse_coverage_options: &str = "a comma separated list of settings: `branch` or \
+        `no-branch`, and `macros=call-site`, `macros=def-site` or `no-macros`";
     pub const parse_instrument_xray: &str = "either a boolean (`yes`, `no`, `on`, `off`, etc), or a comma separated list of settings: `always` or `never` (mutually exclusive), `ignore-loops`, `instruction-threshold=N`, `skip-entry`, `skip-exit`";
     pub const parse_unpretty: &str = "`string` or `string=string`";
     pub const parse_treat_err_as_bug: &str = "either no value or a number bigger than 0";
@@ -484,15 +495,14 @@ pub(crate) fn parse_opt_string(slot: &mut Option<String>, v: Option<&str>) -> bo
         }
     }
 
-    /// Parse an optional language identifier, e.g. `en-US` or `zh-CN`.
-    pub(crate) fn parse_opt_langid(slot: &mut Option<LanguageIdentifier>, v: Option<&str>) -> bool {
+    /// Parse `auto` or a language identifier, e.g. `en-US` or `zh-CN`.
+    pub(crate) fn parse_translate_lang(slot: &mut Option<TranslateLang>, v: Option<&str>) -> bool {
         match v {
-            Some(s) => {
-                *slot = rustc_errors::LanguageIdentifier::from_str(s).ok();
-                true
-            }
-            None => false,
+            Some("auto") => *slot = Some(TranslateLang::Auto),
+            Some(s) => *slot = LanguageIdentifier::from_str(s).ok().map(TranslateLang::Lang),
+            None => return false,
         }
+        true
     }
 
     pub(crate) fn parse_opt_pathbuf(slot: &mut Option<PathBuf>, v: Option<&str>) -> bool {
@@ -839,6 +849,32 @@ pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_opt_applicability(
+        slot: &mut Option<Applicability>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = Some(match v {
+            Some("machine-applicable") => Applicability::MachineApplicable,
+            Some("maybe-incorrect") => Applicability::MaybeIncorrect,
+            Some("has-placeholders") => Applicability::HasPlaceholders,
+            Some("unspecified") => Applicability::Unspecified,
+            _ => return false,
+        });
+        true
+    }
+
+    pub(crate) fn parse_self_profile_summary(
+        slot: &mut Option<SelfProfileSummaryFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        *slot = Some(match v {
+            None | Some("text") => SelfProfileSummaryFormat::Text,
+            Some("json") => SelfProfileSummaryFormat::Json,
+            _ => return false,
+        });
+        true
+    }
+
     pub(crate) fn parse_instrument_coverage(
         slot: &mut Option<InstrumentCoverage>,
         v: Option<&str>,
@@ -870,6 +906,22 @@ pub(crate) fn parse_instrument_coverage(
         true
     }
 
+    pub(crate) fn parse_coverage_options(slot: &mut CoverageOptions, v: Option<&str>) -> bool {
+        let Some(v) = v else { return true };
+
+        for option in v.split(',') {
+            match option {
+                "branch" => slot.branch = true,
+                "no-branch" => slot.branch = false,
+                "macros=call-site" => slot.macros = Some(CoverageMacroSite::CallSite),
+                "macros=def-site" => slot.macros = Some(CoverageMacroSite::DefSite),
+                "no-macros" => slot.macros = None,
+                _ => return false,
+            }
+        }
+        true
+    }
+
     pub(crate) fn parse_instrument_xray(
         slot: &mut Option<InstrumentXRay>,
         v: Option<&str>,
@@ -1307,6 +1359,12 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "only allow the listed language features to be enabled in code (comma separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<Applicability> = (None, parse_opt_applicability, [UNTRACKED],
+        "rewrite the source files with the suggestions of emitted diagnostics that are at least \
+        this applicable"),
+    apply_suggestions_dry_run: bool = (false, parse_bool, [UNTRACKED],
+        "print the changes `-Z apply-suggestions` would make as a diff instead of rewriting \
+        the source files (default: no)"),
     asm_comments: bool = (false, parse_bool, [TRACKED],
         "generate comments into the assembly (may change behavior) (default: no)"),
     assert_incr_state: Option<String> = (None, parse_opt_string, [UNTRACKED],
@@ -1325,11 +1383,16 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
     cgu_partitioning_strategy: Option<String> = (None, parse_opt_string, [TRACKED],
-        "the codegen unit partitioning strategy to use"),
+        "the codegen unit partitioning strategy to use: `default`, or `size` to balance \
+        the sizes of codegen units (default: `default`)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    coverage_options: CoverageOptions = (CoverageOptions::default(), parse_coverage_options, [TRACKED],
+        "control details of `-C instrument-coverage`: `branch` adds branch regions to the coverage \
+        map, `macros=call-site` or `macros=def-site` reports macro-generated code at the macro \
+        invocation or definition (default: no-branch,no-macros)"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
         "inject the given attribute in the crate"),
     debug_info_for_profiling: bool = (false, parse_bool, [TRACKED],
@@ -1344,6 +1407,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     dep_tasks: bool = (false, parse_bool, [UNTRACKED],
         "print tasks that execute and the color their dep node gets (requires debug build) \
         (default: no)"),
+    diagnostic_grouping: bool = (false, parse_bool, [UNTRACKED],
+        "report identical warnings emitted from expansions of the same macro once, \
+        listing all call sites (default: no)"),
     diagnostic_width: Option<usize> = (None, parse_opt_number, [UNTRACKED],
         "set the current output width for diagnostic truncation"),
     dlltool: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
@@ -1386,11 +1452,20 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         computed `block` spans (one span encompassing a block's terminator and \
         all statements). If `-Z instrument-coverage` is also enabled, create \
         an additional `.html` file showing the computed coverage spans."),
+    dump_mono_bloat: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "output the size of the code generated for each instantiation of the generic items, \
+        grouped by generic item"),
     dump_mono_stats: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
-        "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+        "the format to use for -Z dump-mono-stats and -Z dump-mono-bloat (`markdown` (default) or `json`)"),
+    dump_trait_selection_tree: Option<String> = (None, parse_opt_string, [UNTRACKED],
+        "print the candidates considered by trait selection for the obligations of the item \
+        with the given path"),
+    dump_trait_selection_tree_graphviz: bool = (false, parse_bool, [UNTRACKED],
+        "print the tree of `-Z dump-trait-selection-tree` in Graphviz format (default: no)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1427,6 +1502,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    incremental_cache_limit: Option<u64> = (None, parse_opt_number, [UNTRACKED],
+        "evict the least recently used data from the incremental compilation directory until \
+        it takes at most this many bytes"),
     incremental_ignore_spans: bool = (false, parse_bool, [TRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
@@ -1594,6 +1672,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "use the given `.prof` file for sampled profile-guided optimization (also known as AutoFDO)"),
     profiler_runtime: String = (String::from("profiler_builtins"), parse_string, [TRACKED],
         "name of the profiler runtime crate to automatically inject (default: `profiler_builtins`)"),
+    query_cycle_graph: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write each query cycle to the given directory, both as a Graphviz graph \
+        (`query-cycle-N.dot`) and as JSON (`query-cycle-N.json`)"),
     query_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "enable queries of the dependency graph for regression testing (default: no)"),
     randomize_layout: bool = (false, parse_bool, [TRACKED],
@@ -1633,6 +1714,10 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, incr-result-hashing, query-keys, function-args, args, llvm, artifact-sizes"),
+    self_profile_summary: Option<SelfProfileSummaryFormat> = (None,
+        parse_self_profile_summary, [UNTRACKED],
+        "write a per-query summary of the self-profile data next to it \
+        (requires `-Z self-profile`)"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
@@ -1685,6 +1770,9 @@ pub(crate) fn parse_proc_macro_execution_strategy(
     #[rustc_lint_opt_deny_field_access("use `Session::threads` instead of this field")]
     threads: usize = (1, parse_threads, [UNTRACKED],
         "use a thread pool with N threads"),
+    time_items: Option<usize> = (None, parse_opt_number, [UNTRACKED],
+        "measure the time spent type checking, borrow checking and optimizing each function, \
+        and print the N slowest"),
     time_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "measure time of each LLVM pass (default: no)"),
     time_passes: bool = (false, parse_bool, [UNTRACKED],
@@ -1707,10 +1795,12 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         "additional fluent translation to preferentially use (for testing translation)"),
     translate_directionality_markers: bool = (false, parse_bool, [TRACKED],
         "emit directionality isolation markers in translated diagnostics"),
-    translate_lang: Option<LanguageIdentifier> = (None, parse_opt_langid, [TRACKED],
-        "language identifier for diagnostic output"),
+    translate_lang: Option<TranslateLang> = (None, parse_translate_lang, [TRACKED],
+        "language identifier for diagnostic output, or `auto` to use the locale of the environment"),
     translate_remapped_path_to_local_path: bool = (true, parse_bool, [TRACKED],
         "translate remapped paths into local paths when possible (default: yes)"),
+    translate_report_missing: bool = (false, parse_bool, [UNTRACKED],
+        "warn about the diagnostic messages that the loaded translations are missing (default: no)"),
     trap_unreachable: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "generate trap instructions for unreachable intrinsics (default: use target setting, usually yes)"),
     treat_err_as_bug: Option<NonZeroUsize> = (None, parse_treat_err_as_bug, [TRACKED],
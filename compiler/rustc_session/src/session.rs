@@ -1,6 +1,8 @@
 use crate::cgu_reuse_tracker::CguReuseTracker;
 use crate::code_stats::CodeStats;
 pub use crate::code_stats::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+use crate::item_timings::ItemTimings;
+pub use crate::item_timings::{ItemTiming, ItemTimingKind};
 use crate::config::Input;
 use crate::config::{self, CrateType, InstrumentCoverage, OptLevel, OutputType, SwitchWithOptPath};
 use crate::errors;
@@ -18,9 +20,11 @@
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
-use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
+use rustc_errors::apply_suggestions;
+use rustc_errors::emitter::{Emitter, EmitterWriter, FanOutEmitter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{
     error_code, fallback_fluent_bundle, DiagnosticBuilder, DiagnosticId, DiagnosticMessage,
     ErrorGuaranteed, FluentBundle, IntoDiagnostic, LazyFallbackBundle, MultiSpan, Noted,
@@ -29,7 +33,7 @@
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
 use rustc_span::source_map::{FileLoader, RealFileLoader, SourceMap, Span};
-use rustc_span::{sym, SourceFileHashAlgorithm, Symbol};
+use rustc_span::{sym, BytePos, FileName, SourceFile, SourceFileHashAlgorithm, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
 use rustc_target::spec::{
@@ -39,6 +43,8 @@
 use std::cell::{self, RefCell};
 use std::env;
 use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::ops::{Div, Mul};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -175,6 +181,17 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// Time spent on each item, gathered if `-Z time-items` is specified.
+    pub item_timings: ItemTimings,
+
+    /// The number of instructions generated by the codegen backend for each function, by symbol
+    /// name, gathered if `-Z dump-mono-bloat` is specified.
+    pub codegen_instruction_counts: Lock<FxHashMap<String, usize>>,
+
+    /// The number of query cycles written so far if `-Z query-cycle-graph` is specified, used to
+    /// give each cycle its own files.
+    pub query_cycle_graph_count: AtomicUsize,
+
     /// Tracks fuel info if `-zfuel=crate=n` is specified.
     optimization_fuel: Lock<OptimizationFuel>,
 
@@ -202,6 +219,11 @@ pub struct Session {
     /// drown everything else in noise.
     miri_unleashed_features: Lock<Vec<(Span, Option<Symbol>)>>,
 
+    /// The lint levels that the path overrides of `--lint-config` set for each source file, by
+    /// the start position of the file. Filled in the first time the levels of a file are needed,
+    /// as matching the globs of the overrides is costly.
+    lint_config_levels: Lock<FxHashMap<BytePos, Option<Lrc<[(String, lint::Level)]>>>>,
+
     /// Architecture to use for interpreting asm!.
     pub asm_arch: Option<InlineAsmArch>,
 
@@ -262,10 +284,35 @@ fn check_miri_unleashed_features(&self) {
     /// Invoked all the way at the end to finish off diagnostics printing.
     pub fn finish_diagnostics(&self, registry: &Registry) {
         self.check_miri_unleashed_features();
+        self.apply_suggestions();
         self.diagnostic().print_error_count(registry);
         self.emit_future_breakage();
     }
 
+    /// Rewrites the source files with the suggestions collected for `-Z apply-suggestions`,
+    /// or prints them as a diff with `-Z apply-suggestions-dry-run`.
+    fn apply_suggestions(&self) {
+        if self.opts.unstable_opts.apply_suggestions.is_none() {
+            return;
+        }
+
+        let suggestions = self.diagnostic().take_applicable_suggestions();
+        let plan = apply_suggestions::plan_fixes(self.source_map(), &suggestions);
+        for span in plan.conflicts {
+            self.emit_warning(errors::SuggestionConflict { span });
+        }
+        for path in plan.modified_files {
+            self.emit_warning(errors::SuggestionsFileModified { path });
+        }
+        for fix in plan.files {
+            if self.opts.unstable_opts.apply_suggestions_dry_run {
+                print!("{}", fix.unified_diff());
+            } else if let Err(err) = std::fs::write(&fix.path, &fix.fixed) {
+                self.emit_err(errors::FailedToApplySuggestions { path: fix.path, err });
+            }
+        }
+    }
+
     fn emit_future_breakage(&self) {
         if !self.opts.json_future_incompat {
             return;
@@ -683,6 +730,26 @@ pub fn source_map(&self) -> &SourceMap {
         self.parse_sess.source_map()
     }
 
+    /// Returns the lint levels that the path overrides of `--lint-config` set for `file`, or
+    /// `None` if no override matches it.
+    pub fn lint_config_levels_for_file(
+        &self,
+        file: &SourceFile,
+    ) -> Option<Lrc<[(String, lint::Level)]>> {
+        if self.opts.lint_config.overrides.is_empty() {
+            return None;
+        }
+        self.lint_config_levels
+            .lock()
+            .entry(file.start_pos)
+            .or_insert_with(|| {
+                let FileName::Real(name) = &file.name else { return None };
+                let path = self.opts.working_dir.local_path_if_available().join(name.local_path()?);
+                self.opts.lint_config.levels_for_path(&path).map(Lrc::from)
+            })
+            .clone()
+    }
+
     /// Returns `true` if internal lints should be added to the lint store - i.e. if
     /// `-Zunstable-options` is provided and this isn't rustdoc (internal lints can trigger errors
     /// to be emitted under rustdoc).
@@ -702,6 +769,11 @@ pub fn instrument_coverage_except_unused_functions(&self) -> bool {
         self.opts.cg.instrument_coverage() == InstrumentCoverage::ExceptUnusedFunctions
     }
 
+    /// True if branch regions are added to the coverage map, with `-Z coverage-options=branch`.
+    pub fn instrument_coverage_branch(&self) -> bool {
+        self.instrument_coverage() && self.opts.unstable_opts.coverage_options.branch
+    }
+
     /// Gets the features enabled for the current compilation session.
     /// DO NOT USE THIS METHOD if there is a TyCtxt available, as it circumvents
     /// dependency tracking. Use tcx.features() instead.
@@ -1315,6 +1387,73 @@ fn default_emitter(
             )
             .ui_testing(sopts.unstable_opts.ui_testing),
         ),
+        config::ErrorOutputType::Sarif { pretty } => Box::new(SarifEmitter::stderr(
+            Some(registry),
+            source_map,
+            bundle,
+            fallback_bundle,
+            pretty,
+        )),
+    }
+}
+
+/// Creates the emitter for an additional output requested with `--error-format-file`.
+fn error_format_file_emitter(
+    sopts: &config::Options,
+    error_format: config::ErrorOutputType,
+    path: &Path,
+    registry: rustc_errors::registry::Registry,
+    source_map: Lrc<SourceMap>,
+    bundle: Option<Lrc<FluentBundle>>,
+    fallback_bundle: LazyFallbackBundle,
+) -> Box<dyn Emitter + sync::Send> {
+    let file = File::create(path).unwrap_or_else(|e| {
+        early_error(
+            sopts.error_format,
+            &format!("failed to create diagnostic output file `{}`: {e}", path.display()),
+        )
+    });
+    let dst: Box<dyn Write + Send> = Box::new(BufWriter::new(file));
+    let macro_backtrace = sopts.unstable_opts.macro_backtrace;
+    let track_diagnostics = sopts.unstable_opts.track_diagnostics;
+    match error_format {
+        // `human-annotate-rs` can only write to stderr, so it's rendered like `human` here.
+        config::ErrorOutputType::HumanReadable(kind) => Box::new(
+            kind.new_emitter(
+                dst,
+                Some(source_map),
+                bundle,
+                fallback_bundle,
+                sopts.unstable_opts.teach,
+                sopts.diagnostic_width,
+                macro_backtrace,
+                track_diagnostics,
+            )
+            .ui_testing(sopts.unstable_opts.ui_testing),
+        ),
+        config::ErrorOutputType::Json { pretty, json_rendered } => Box::new(
+            JsonEmitter::new(
+                dst,
+                Some(registry),
+                source_map,
+                bundle,
+                fallback_bundle,
+                pretty,
+                json_rendered,
+                sopts.diagnostic_width,
+                macro_backtrace,
+                track_diagnostics,
+            )
+            .ui_testing(sopts.unstable_opts.ui_testing),
+        ),
+        config::ErrorOutputType::Sarif { pretty } => Box::new(SarifEmitter::new(
+            dst,
+            Some(registry),
+            source_map,
+            bundle,
+            fallback_bundle,
+            pretty,
+        )),
     }
 }
 
@@ -1372,7 +1511,26 @@ pub fn build_session(
         rustc_errors::DEFAULT_LOCALE_RESOURCES,
         sopts.unstable_opts.translate_directionality_markers,
     );
-    let emitter = default_emitter(&sopts, registry, source_map.clone(), bundle, fallback_bundle);
+    let file_emitters = sopts
+        .error_format_files
+        .iter()
+        .map(|(error_format, path)| {
+            error_format_file_emitter(
+                &sopts,
+                *error_format,
+                path,
+                registry.clone(),
+                source_map.clone(),
+                bundle.clone(),
+                fallback_bundle.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut emitter =
+        default_emitter(&sopts, registry, source_map.clone(), bundle, fallback_bundle);
+    if !file_emitters.is_empty() {
+        emitter = Box::new(FanOutEmitter::new(emitter, file_emitters));
+    }
 
     let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(
         emitter,
@@ -1389,6 +1547,7 @@ pub fn build_session(
             sopts.crate_name.as_deref(),
             sopts.unstable_opts.self_profile_events.as_deref(),
             &sopts.unstable_opts.self_profile_counter,
+            sopts.unstable_opts.self_profile_summary,
         );
         match profiler {
             Ok(profiler) => Some(Arc::new(profiler)),
@@ -1398,6 +1557,12 @@ pub fn build_session(
             }
         }
     } else {
+        if sopts.unstable_opts.self_profile_summary.is_some() {
+            early_warn(
+                sopts.error_format,
+                "`-Z self-profile-summary` has no effect without `-Z self-profile`",
+            );
+        }
         None
     };
 
@@ -1460,12 +1625,16 @@ pub fn build_session(
             normalize_projection_ty: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
+        item_timings: Default::default(),
+        codegen_instruction_counts: Default::default(),
+        query_cycle_graph_count: AtomicUsize::new(0),
         optimization_fuel,
         print_fuel,
         jobserver: jobserver::client(),
         driver_lint_caps,
         ctfe_backtrace,
         miri_unleashed_features: Lock::new(Default::default()),
+        lint_config_levels: Default::default(),
         asm_arch,
         target_features: FxHashSet::default(),
         unstable_target_features: FxHashSet::default(),
@@ -1639,6 +1808,9 @@ fn early_error_handler(output: config::ErrorOutputType) -> rustc_errors::Handler
             false,
             false,
         )),
+        config::ErrorOutputType::Sarif { pretty } => {
+            Box::new(SarifEmitter::basic(pretty, None, fallback_bundle))
+        }
     };
     rustc_errors::Handler::with_emitter(true, None, emitter)
 }
@@ -1,5 +1,6 @@
-use crate::session::Session;
+use crate::session::{ItemTimingKind, Session};
 use rustc_data_structures::profiling::VerboseTimingGuard;
+use rustc_span::def_id::LocalDefId;
 use std::path::{Path, PathBuf};
 
 impl Session {
@@ -9,6 +10,27 @@ pub fn timer(&self, what: &'static str) -> VerboseTimingGuard<'_> {
     pub fn time<R>(&self, what: &'static str, f: impl FnOnce() -> R) -> R {
         self.prof.verbose_generic_activity(what).run(f)
     }
+    /// Attributes the time of `f`, the provider of the `kind` query, to `def_id` for
+    /// `-Z time-items`.
+    pub fn time_item<R>(
+        &self,
+        kind: ItemTimingKind,
+        def_id: LocalDefId,
+        f: impl FnOnce() -> R,
+    ) -> R {
+        if self.opts.unstable_opts.time_items.is_none() {
+            return f();
+        }
+        self.item_timings.time(kind, def_id, f)
+    }
+    /// Attributes the time of `f`, which selects or evaluates a trait obligation, to the item
+    /// whose query is running for `-Z time-items`.
+    pub fn time_trait_selection<R>(&self, f: impl FnOnce() -> R) -> R {
+        if self.opts.unstable_opts.time_items.is_none() {
+            return f();
+        }
+        self.item_timings.time_trait_selection(f)
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encodable, Decodable)]
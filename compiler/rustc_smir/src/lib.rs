@@ -14,6 +14,15 @@
 #![deny(rustc::untranslatable_diagnostic)]
 #![deny(rustc::diagnostic_outside_of_impl)]
 
+// The derives of `rustc_macros` refer to `::rustc_serialize`.
+#[cfg(not(feature = "default"))]
+extern crate rustc_serialize;
+
 pub mod mir;
 
 pub mod very_unstable;
+
+pub mod rustc_smir;
+pub mod stable_mir;
+
+pub use crate::rustc_smir::driver::run;
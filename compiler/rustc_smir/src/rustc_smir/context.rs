@@ -0,0 +1,102 @@
+//! The implementation of [`stable_mir::Context`] on top of the queries of the compiler.
+
+use crate::rustc_smir::{Stable, Tables};
+use crate::stable_mir::{self, Context};
+use crate::very_unstable::middle::ty::{self, TyCtxt};
+use crate::very_unstable::span::def_id::DefId;
+use crate::very_unstable::span::DUMMY_SP;
+use crate::very_unstable::trait_selection::infer::TyCtxtInferExt;
+use crate::very_unstable::trait_selection::traits::{Obligation, ObligationCause, ObligationCtxt};
+
+impl<'tcx> Context for Tables<'tcx> {
+    fn mir_body(&mut self, item: stable_mir::DefId) -> stable_mir::mir::Body {
+        let def_id = self.internal_def_id(item);
+        self.tcx.optimized_mir(def_id).stable(self)
+    }
+
+    fn fn_sig(&mut self, item: stable_mir::DefId) -> stable_mir::ty::FnSig {
+        let def_id = self.internal_def_id(item);
+        let sig = self.tcx.fn_sig(def_id).subst_identity();
+        // Turn the late-bound lifetimes into the lifetime parameters of the function, instead of
+        // leaking the bound regions, which are only meaningful under their binder.
+        self.tcx.liberate_late_bound_regions(def_id, sig).stable(self)
+    }
+
+    fn generics_of(&mut self, item: stable_mir::DefId) -> stable_mir::ty::Generics {
+        let def_id = self.internal_def_id(item);
+        self.tcx.generics_of(def_id).stable(self)
+    }
+
+    fn trait_impls(&mut self, ty: &stable_mir::ty::Ty) -> Vec<stable_mir::ty::TraitImpl> {
+        let tcx = self.tcx;
+        let ty = self.internal_ty(ty);
+        let mut impls = vec![];
+        for trait_def_id in tcx.all_traits() {
+            tcx.for_each_relevant_impl(trait_def_id, ty, |impl_def_id| {
+                if impl_may_apply(tcx, impl_def_id, ty) {
+                    impls.push(impl_def_id);
+                }
+            });
+        }
+
+        impls
+            .into_iter()
+            .map(|impl_def_id| {
+                let trait_ref = tcx.impl_trait_ref(impl_def_id).unwrap().subst_identity();
+                stable_mir::ty::TraitImpl {
+                    impl_def: self.item(impl_def_id),
+                    trait_ref: trait_ref.stable(self),
+                }
+            })
+            .collect()
+    }
+
+    fn layout_of(&mut self, ty: &stable_mir::ty::Ty) -> Option<stable_mir::ty::Layout> {
+        let ty = self.internal_ty(ty);
+        let layout = self.tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)).ok()?;
+        Some(stable_mir::ty::Layout { size: layout.size.bytes(), align: layout.align.abi.bytes() })
+    }
+
+    fn resolve_instance(
+        &mut self,
+        fn_def: &stable_mir::ty::Ty,
+    ) -> Option<stable_mir::mono::Instance> {
+        let ty::FnDef(def_id, substs) = *self.internal_ty(fn_def).kind() else { return None };
+        let instance =
+            ty::Instance::resolve(self.tcx, ty::ParamEnv::reveal_all(), def_id, substs).ok()??;
+        Some(instance.stable(self))
+    }
+
+    fn instance_body(&mut self, instance: &stable_mir::mono::Instance) -> stable_mir::mir::Body {
+        let instance = self.internal_instance(instance);
+        let body = self.tcx.instance_mir(instance.def);
+        instance
+            .subst_mir_and_normalize_erasing_regions(
+                self.tcx,
+                ty::ParamEnv::reveal_all(),
+                body.clone(),
+            )
+            .stable(self)
+    }
+}
+
+/// Returns whether the impl may apply to `ty`, i.e. whether its self type unifies with `ty` and
+/// none of its where clauses is known not to hold.
+fn impl_may_apply<'tcx>(tcx: TyCtxt<'tcx>, impl_def_id: DefId, ty: ty::Ty<'tcx>) -> bool {
+    let param_env = ty::ParamEnv::reveal_all();
+    let infcx = tcx.infer_ctxt().build();
+    infcx.probe(|_| {
+        let ocx = ObligationCtxt::new_in_snapshot(&infcx);
+        let impl_substs = infcx.fresh_substs_for_item(DUMMY_SP, impl_def_id);
+        let impl_self_ty = tcx.bound_type_of(impl_def_id).subst(tcx, impl_substs);
+        if ocx.eq(&ObligationCause::dummy(), param_env, impl_self_ty, ty).is_err() {
+            return false;
+        }
+
+        let impl_predicates = tcx.predicates_of(impl_def_id).instantiate(tcx, impl_substs);
+        ocx.register_obligations(impl_predicates.predicates.iter().map(|&predicate| {
+            Obligation::new(tcx, ObligationCause::dummy(), param_env, predicate)
+        }));
+        ocx.select_where_possible().is_empty()
+    })
+}
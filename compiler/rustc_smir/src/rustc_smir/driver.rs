@@ -0,0 +1,91 @@
+//! The entry point for analysis tools, which runs the compiler and hands the crate being compiled
+//! to the tool.
+
+use crate::rustc_smir::{Stable, Tables};
+use crate::stable_mir::{CompilerError, Crate};
+use crate::very_unstable::data_structures::fx::FxIndexSet;
+use crate::very_unstable::driver::{self, Callbacks, Compilation, RunCompiler};
+use crate::very_unstable::interface::{interface, Queries};
+use crate::very_unstable::span::def_id::LOCAL_CRATE;
+
+/// Runs the compiler with the given command line arguments, which start with the name of the
+/// program like for `rustc` itself, and calls `callback` with the crate being compiled once it has
+/// been analyzed. The compilation stops after the callback returns, so nothing is codegened.
+///
+/// ```ignore (requires a source file to compile)
+/// let args: Vec<String> = std::env::args().collect();
+/// rustc_smir::run(&args, |mut krate| {
+///     for item in &krate.local_items {
+///         let body = krate.cx.mir_body(item.def_id);
+///         println!("{}: {} blocks", item.path, body.blocks.len());
+///     }
+/// })
+/// .unwrap();
+/// ```
+pub fn run<T, F>(args: &[String], callback: F) -> Result<T, CompilerError>
+where
+    T: Send,
+    F: FnOnce(Crate<'_>) -> T + Send,
+{
+    let mut callbacks = StableMirCallbacks { callback: Some(callback), result: None };
+    let compiled =
+        driver::catch_fatal_errors(|| RunCompiler::new(args, &mut callbacks).run()).and_then(|r| r);
+    match (compiled, callbacks.result) {
+        (Ok(()), Some(result)) => Ok(result),
+        // The compilation may also stop before the analysis without any error, like for
+        // `--version`, so the callback isn't called in that case either.
+        _ => Err(CompilerError),
+    }
+}
+
+struct StableMirCallbacks<T, F> {
+    callback: Option<F>,
+    result: Option<T>,
+}
+
+impl<T, F> Callbacks for StableMirCallbacks<T, F>
+where
+    T: Send,
+    F: FnOnce(Crate<'_>) -> T + Send,
+{
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().enter(|tcx| {
+            tcx.sess.abort_if_errors();
+
+            let mut tables = Tables::new(tcx);
+            let local_items = tcx
+                .mir_keys(())
+                .iter()
+                .map(|def_id| def_id.to_def_id())
+                .filter(|&def_id| tcx.def_kind(def_id).is_fn_like())
+                .map(|def_id| tables.item(def_id))
+                .collect();
+            let entry_fn = tcx.entry_fn(()).map(|(def_id, _)| tables.item(def_id));
+
+            // Items with shared linkage may be in multiple codegen units.
+            let (_, codegen_units) = tcx.collect_and_partition_mono_items(());
+            let mono_items: FxIndexSet<_> = codegen_units
+                .iter()
+                .flat_map(|codegen_unit| codegen_unit.items_in_deterministic_order(tcx))
+                .map(|(mono_item, _)| mono_item)
+                .collect();
+            let mono_items =
+                mono_items.iter().map(|mono_item| mono_item.stable(&mut tables)).collect();
+
+            let krate = Crate {
+                name: tcx.crate_name(LOCAL_CRATE).to_string(),
+                local_items,
+                entry_fn,
+                mono_items,
+                cx: &mut tables,
+            };
+            let callback = self.callback.take().unwrap();
+            self.result = Some(callback(krate));
+        });
+        Compilation::Stop
+    }
+}
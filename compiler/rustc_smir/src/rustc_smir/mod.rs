@@ -0,0 +1,883 @@
+//! Module that implements what will become the rustc side of Stable MIR.
+//!
+//! This module is responsible for building Stable MIR components from internal components, i.e.
+//! for converting `rustc_middle::mir` and `rustc_middle::ty` into the owned types of
+//! [`crate::stable_mir`].
+
+use crate::stable_mir;
+use crate::very_unstable::data_structures::fx::FxIndexSet;
+use crate::very_unstable::hir;
+use crate::very_unstable::middle::mir;
+use crate::very_unstable::middle::mir::interpret::{ConstValue, Scalar};
+use crate::very_unstable::middle::mir::mono;
+use crate::very_unstable::middle::ty::adjustment::PointerCast;
+use crate::very_unstable::middle::ty::{self, TyCtxt};
+use crate::very_unstable::span::def_id::DefId;
+use crate::very_unstable::span::{Pos, Span};
+
+mod context;
+pub(crate) mod driver;
+
+/// The state of a conversion to Stable MIR. This maps the opaque ids of Stable MIR to the
+/// compiler internal ids.
+pub struct Tables<'tcx> {
+    /// The escape hatch for anything which isn't exposed by Stable MIR yet.
+    pub tcx: TyCtxt<'tcx>,
+    def_ids: FxIndexSet<DefId>,
+    types: FxIndexSet<ty::Ty<'tcx>>,
+    instances: FxIndexSet<ty::Instance<'tcx>>,
+}
+
+impl<'tcx> Tables<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Self {
+        Tables {
+            tcx,
+            def_ids: FxIndexSet::default(),
+            types: FxIndexSet::default(),
+            instances: FxIndexSet::default(),
+        }
+    }
+
+    pub fn stable_def_id(&mut self, def_id: DefId) -> stable_mir::DefId {
+        stable_mir::DefId(self.def_ids.insert_full(def_id).0)
+    }
+
+    pub fn internal_def_id(&self, def_id: stable_mir::DefId) -> DefId {
+        self.def_ids[def_id.0]
+    }
+
+    pub fn internal_ty(&self, ty: &stable_mir::ty::Ty) -> ty::Ty<'tcx> {
+        self.types[ty.id().0]
+    }
+
+    pub fn internal_instance(&self, instance: &stable_mir::mono::Instance) -> ty::Instance<'tcx> {
+        self.instances[instance.id().0]
+    }
+
+    pub fn item(&mut self, def_id: DefId) -> stable_mir::Item {
+        stable_mir::Item { def_id: self.stable_def_id(def_id), path: self.tcx.def_path_str(def_id) }
+    }
+
+    pub fn span(&self, span: Span) -> stable_mir::Span {
+        let source_map = self.tcx.sess.source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        stable_mir::Span {
+            file_name: lo.file.name.prefer_remapped().to_string(),
+            start_line: lo.line,
+            start_col: lo.col.to_usize() + 1,
+            end_line: hi.line,
+            end_col: hi.col.to_usize() + 1,
+        }
+    }
+}
+
+/// Trait used to convert between an internal MIR type to a Stable MIR type.
+pub trait Stable<'tcx> {
+    /// The stable representation of the type implementing Stable.
+    type T;
+    /// Converts an object to the equivalent Stable MIR representation.
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T;
+}
+
+impl<'tcx, T: Stable<'tcx>> Stable<'tcx> for Option<T> {
+    type T = Option<T::T>;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        self.as_ref().map(|value| value.stable(tables))
+    }
+}
+
+impl<'tcx, T: Stable<'tcx>> Stable<'tcx> for [T] {
+    type T = Vec<T::T>;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        self.iter().map(|value| value.stable(tables)).collect()
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = stable_mir::mir::Body;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::mir::Body {
+            blocks: self.basic_blocks.raw.stable(tables),
+            locals: self.local_decls.raw.stable(tables),
+            arg_count: self.arg_count,
+            span: tables.span(self.span),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::LocalDecl<'tcx> {
+    type T = stable_mir::mir::LocalDecl;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::mir::LocalDecl {
+            ty: self.ty.stable(tables),
+            mutability: self.mutability.stable(tables),
+            span: tables.span(self.source_info.span),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BasicBlockData<'tcx> {
+    type T = stable_mir::mir::BasicBlockData;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::mir::BasicBlockData {
+            statements: self.statements.stable(tables),
+            terminator: self.terminator().stable(tables),
+            is_cleanup: self.is_cleanup,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
+    type T = stable_mir::mir::Statement;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::StatementKind;
+        let kind = match &self.kind {
+            mir::StatementKind::Assign(assign) => {
+                StatementKind::Assign(assign.0.stable(tables), assign.1.stable(tables))
+            }
+            mir::StatementKind::FakeRead(fake_read) => {
+                StatementKind::FakeRead(fake_read.0.stable(tables), fake_read.1.stable(tables))
+            }
+            mir::StatementKind::SetDiscriminant { place, variant_index } => {
+                StatementKind::SetDiscriminant {
+                    place: place.stable(tables),
+                    variant_index: variant_index.as_usize(),
+                }
+            }
+            mir::StatementKind::Deinit(place) => StatementKind::Deinit(place.stable(tables)),
+            mir::StatementKind::StorageLive(local) => StatementKind::StorageLive(local.as_usize()),
+            mir::StatementKind::StorageDead(local) => StatementKind::StorageDead(local.as_usize()),
+            mir::StatementKind::Retag(kind, place) => {
+                StatementKind::Retag(kind.stable(tables), place.stable(tables))
+            }
+            mir::StatementKind::AscribeUserType(ascription, _) => {
+                StatementKind::AscribeUserType(ascription.0.stable(tables))
+            }
+            mir::StatementKind::Coverage(_) => StatementKind::Coverage,
+            mir::StatementKind::Intrinsic(intrinsic) => {
+                StatementKind::Intrinsic(intrinsic.stable(tables))
+            }
+            mir::StatementKind::ConstEvalCounter => StatementKind::ConstEvalCounter,
+            mir::StatementKind::Nop => StatementKind::Nop,
+        };
+        stable_mir::mir::Statement { kind, span: tables.span(self.source_info.span) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::FakeReadCause {
+    type T = stable_mir::mir::FakeReadCause;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::FakeReadCause;
+        match self {
+            mir::FakeReadCause::ForMatchGuard => FakeReadCause::ForMatchGuard,
+            mir::FakeReadCause::ForMatchedPlace(_) => FakeReadCause::ForMatchedPlace,
+            mir::FakeReadCause::ForGuardBinding => FakeReadCause::ForGuardBinding,
+            mir::FakeReadCause::ForLet(_) => FakeReadCause::ForLet,
+            mir::FakeReadCause::ForIndex => FakeReadCause::ForIndex,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::RetagKind {
+    type T = stable_mir::mir::RetagKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::RetagKind;
+        match self {
+            mir::RetagKind::FnEntry => RetagKind::FnEntry,
+            mir::RetagKind::TwoPhase => RetagKind::TwoPhase,
+            mir::RetagKind::Raw => RetagKind::Raw,
+            mir::RetagKind::Default => RetagKind::Default,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NonDivergingIntrinsic<'tcx> {
+    type T = stable_mir::mir::NonDivergingIntrinsic;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::NonDivergingIntrinsic;
+        match self {
+            mir::NonDivergingIntrinsic::Assume(op) => {
+                NonDivergingIntrinsic::Assume(op.stable(tables))
+            }
+            mir::NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                NonDivergingIntrinsic::CopyNonOverlapping {
+                    src: copy.src.stable(tables),
+                    dst: copy.dst.stable(tables),
+                    count: copy.count.stable(tables),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Terminator<'tcx> {
+    type T = stable_mir::mir::Terminator;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::TerminatorKind;
+        let kind = match &self.kind {
+            mir::TerminatorKind::Goto { target } => {
+                TerminatorKind::Goto { target: target.as_usize() }
+            }
+            mir::TerminatorKind::SwitchInt { discr, targets } => TerminatorKind::SwitchInt {
+                discr: discr.stable(tables),
+                targets: targets.iter().map(|(value, target)| (value, target.as_usize())).collect(),
+                otherwise: targets.otherwise().as_usize(),
+            },
+            mir::TerminatorKind::Resume => TerminatorKind::Resume,
+            mir::TerminatorKind::Abort => TerminatorKind::Abort,
+            mir::TerminatorKind::Return => TerminatorKind::Return,
+            mir::TerminatorKind::Unreachable => TerminatorKind::Unreachable,
+            mir::TerminatorKind::Drop { place, target, unwind } => TerminatorKind::Drop {
+                place: place.stable(tables),
+                target: target.as_usize(),
+                unwind: unwind.map(|unwind| unwind.as_usize()),
+            },
+            mir::TerminatorKind::DropAndReplace { place, value, target, unwind } => {
+                TerminatorKind::DropAndReplace {
+                    place: place.stable(tables),
+                    value: value.stable(tables),
+                    target: target.as_usize(),
+                    unwind: unwind.map(|unwind| unwind.as_usize()),
+                }
+            }
+            mir::TerminatorKind::Call { func, args, destination, target, cleanup, .. } => {
+                TerminatorKind::Call {
+                    func: func.stable(tables),
+                    args: args.stable(tables),
+                    destination: destination.stable(tables),
+                    target: target.map(|target| target.as_usize()),
+                    cleanup: cleanup.map(|cleanup| cleanup.as_usize()),
+                }
+            }
+            mir::TerminatorKind::Assert { cond, expected, msg, target, cleanup } => {
+                TerminatorKind::Assert {
+                    cond: cond.stable(tables),
+                    expected: *expected,
+                    msg: msg.stable(tables),
+                    target: target.as_usize(),
+                    cleanup: cleanup.map(|cleanup| cleanup.as_usize()),
+                }
+            }
+            mir::TerminatorKind::Yield { value, resume, resume_arg, drop } => {
+                TerminatorKind::Yield {
+                    value: value.stable(tables),
+                    resume: resume.as_usize(),
+                    resume_arg: resume_arg.stable(tables),
+                    drop: drop.map(|drop| drop.as_usize()),
+                }
+            }
+            mir::TerminatorKind::GeneratorDrop => TerminatorKind::GeneratorDrop,
+            mir::TerminatorKind::FalseEdge { real_target, imaginary_target } => {
+                TerminatorKind::FalseEdge {
+                    real_target: real_target.as_usize(),
+                    imaginary_target: imaginary_target.as_usize(),
+                }
+            }
+            mir::TerminatorKind::FalseUnwind { real_target, unwind } => {
+                TerminatorKind::FalseUnwind {
+                    real_target: real_target.as_usize(),
+                    unwind: unwind.map(|unwind| unwind.as_usize()),
+                }
+            }
+            mir::TerminatorKind::InlineAsm { template, operands, destination, cleanup, .. } => {
+                TerminatorKind::InlineAsm {
+                    template: template.iter().map(|piece| piece.to_string()).collect(),
+                    operands: operands.stable(tables),
+                    destination: destination.map(|destination| destination.as_usize()),
+                    cleanup: cleanup.map(|cleanup| cleanup.as_usize()),
+                }
+            }
+        };
+        stable_mir::mir::Terminator { kind, span: tables.span(self.source_info.span) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AssertMessage<'tcx> {
+    type T = stable_mir::mir::AssertMessage;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::AssertMessage;
+        match self {
+            mir::AssertKind::BoundsCheck { len, index } => {
+                AssertMessage::BoundsCheck { len: len.stable(tables), index: index.stable(tables) }
+            }
+            mir::AssertKind::Overflow(bin_op, op1, op2) => AssertMessage::Overflow(
+                bin_op.stable(tables),
+                op1.stable(tables),
+                op2.stable(tables),
+            ),
+            mir::AssertKind::OverflowNeg(op) => AssertMessage::OverflowNeg(op.stable(tables)),
+            mir::AssertKind::DivisionByZero(op) => AssertMessage::DivisionByZero(op.stable(tables)),
+            mir::AssertKind::RemainderByZero(op) => {
+                AssertMessage::RemainderByZero(op.stable(tables))
+            }
+            mir::AssertKind::ResumedAfterReturn(generator) => {
+                AssertMessage::ResumedAfterReturn(generator.stable(tables))
+            }
+            mir::AssertKind::ResumedAfterPanic(generator) => {
+                AssertMessage::ResumedAfterPanic(generator.stable(tables))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::GeneratorKind {
+    type T = stable_mir::mir::GeneratorKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::GeneratorKind;
+        match self {
+            hir::GeneratorKind::Async(hir::AsyncGeneratorKind::Block) => GeneratorKind::AsyncBlock,
+            hir::GeneratorKind::Async(hir::AsyncGeneratorKind::Closure) => {
+                GeneratorKind::AsyncClosure
+            }
+            hir::GeneratorKind::Async(hir::AsyncGeneratorKind::Fn) => GeneratorKind::AsyncFn,
+            hir::GeneratorKind::Gen => GeneratorKind::Gen,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::InlineAsmOperand<'tcx> {
+    type T = stable_mir::mir::InlineAsmOperand;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let (in_value, out_place) = match self {
+            mir::InlineAsmOperand::In { value, .. } => (Some(value.stable(tables)), None),
+            mir::InlineAsmOperand::Out { place, .. } => (None, place.stable(tables)),
+            mir::InlineAsmOperand::InOut { in_value, out_place, .. } => {
+                (Some(in_value.stable(tables)), out_place.stable(tables))
+            }
+            mir::InlineAsmOperand::Const { .. }
+            | mir::InlineAsmOperand::SymFn { .. }
+            | mir::InlineAsmOperand::SymStatic { .. } => (None, None),
+        };
+        stable_mir::mir::InlineAsmOperand { in_value, out_place, raw: format!("{:?}", self) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Rvalue<'tcx> {
+    type T = stable_mir::mir::Rvalue;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::Rvalue;
+        match self {
+            mir::Rvalue::Use(op) => Rvalue::Use(op.stable(tables)),
+            mir::Rvalue::Repeat(op, count) => {
+                Rvalue::Repeat(op.stable(tables), count.stable(tables))
+            }
+            mir::Rvalue::Ref(region, kind, place) => {
+                Rvalue::Ref(region.stable(tables), kind.stable(tables), place.stable(tables))
+            }
+            mir::Rvalue::ThreadLocalRef(def_id) => Rvalue::ThreadLocalRef(tables.item(*def_id)),
+            mir::Rvalue::AddressOf(mutability, place) => {
+                Rvalue::AddressOf(mutability.stable(tables), place.stable(tables))
+            }
+            mir::Rvalue::Len(place) => Rvalue::Len(place.stable(tables)),
+            mir::Rvalue::Cast(kind, op, ty) => {
+                Rvalue::Cast(kind.stable(tables), op.stable(tables), ty.stable(tables))
+            }
+            mir::Rvalue::BinaryOp(bin_op, ops) => {
+                Rvalue::BinaryOp(bin_op.stable(tables), ops.0.stable(tables), ops.1.stable(tables))
+            }
+            mir::Rvalue::CheckedBinaryOp(bin_op, ops) => Rvalue::CheckedBinaryOp(
+                bin_op.stable(tables),
+                ops.0.stable(tables),
+                ops.1.stable(tables),
+            ),
+            mir::Rvalue::NullaryOp(null_op, ty) => {
+                Rvalue::NullaryOp(null_op.stable(tables), ty.stable(tables))
+            }
+            mir::Rvalue::UnaryOp(un_op, op) => {
+                Rvalue::UnaryOp(un_op.stable(tables), op.stable(tables))
+            }
+            mir::Rvalue::Discriminant(place) => Rvalue::Discriminant(place.stable(tables)),
+            mir::Rvalue::Aggregate(kind, ops) => {
+                Rvalue::Aggregate(kind.stable(tables), ops.stable(tables))
+            }
+            mir::Rvalue::ShallowInitBox(op, ty) => {
+                Rvalue::ShallowInitBox(op.stable(tables), ty.stable(tables))
+            }
+            mir::Rvalue::CopyForDeref(place) => Rvalue::CopyForDeref(place.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BorrowKind {
+    type T = stable_mir::mir::BorrowKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::BorrowKind;
+        match *self {
+            mir::BorrowKind::Shared => BorrowKind::Shared,
+            mir::BorrowKind::Shallow => BorrowKind::Shallow,
+            mir::BorrowKind::Unique => BorrowKind::Unique,
+            mir::BorrowKind::Mut { allow_two_phase_borrow } => {
+                BorrowKind::Mut { allow_two_phase_borrow }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::CastKind {
+    type T = stable_mir::mir::CastKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::CastKind;
+        match self {
+            mir::CastKind::PointerExposeAddress => CastKind::PointerExposeAddress,
+            mir::CastKind::PointerFromExposedAddress => CastKind::PointerFromExposedAddress,
+            mir::CastKind::Pointer(cast) => CastKind::Pointer(cast.stable(tables)),
+            mir::CastKind::DynStar => CastKind::DynStar,
+            mir::CastKind::IntToInt => CastKind::IntToInt,
+            mir::CastKind::FloatToInt => CastKind::FloatToInt,
+            mir::CastKind::FloatToFloat => CastKind::FloatToFloat,
+            mir::CastKind::IntToFloat => CastKind::IntToFloat,
+            mir::CastKind::PtrToPtr => CastKind::PtrToPtr,
+            mir::CastKind::FnPtrToPtr => CastKind::FnPtrToPtr,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for PointerCast {
+    type T = stable_mir::mir::PointerCast;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::PointerCast as StablePointerCast;
+        match self {
+            PointerCast::ReifyFnPointer => StablePointerCast::ReifyFnPointer,
+            PointerCast::UnsafeFnPointer => StablePointerCast::UnsafeFnPointer,
+            PointerCast::ClosureFnPointer(unsafety) => {
+                StablePointerCast::ClosureFnPointer(unsafety.stable(tables))
+            }
+            PointerCast::MutToConstPointer => StablePointerCast::MutToConstPointer,
+            PointerCast::ArrayToPointer => StablePointerCast::ArrayToPointer,
+            PointerCast::Unsize => StablePointerCast::Unsize,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::AggregateKind<'tcx> {
+    type T = stable_mir::mir::AggregateKind;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::AggregateKind;
+        match self {
+            mir::AggregateKind::Array(ty) => AggregateKind::Array(ty.stable(tables)),
+            mir::AggregateKind::Tuple => AggregateKind::Tuple,
+            mir::AggregateKind::Adt(def_id, variant_index, substs, _, active_field) => {
+                AggregateKind::Adt(
+                    tables.item(*def_id),
+                    variant_index.as_usize(),
+                    substs.stable(tables),
+                    *active_field,
+                )
+            }
+            mir::AggregateKind::Closure(def_id, substs) => {
+                AggregateKind::Closure(tables.item(*def_id), substs.stable(tables))
+            }
+            mir::AggregateKind::Generator(def_id, substs, movability) => AggregateKind::Generator(
+                tables.item(*def_id),
+                substs.stable(tables),
+                movability.stable(tables),
+            ),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::BinOp {
+    type T = stable_mir::mir::BinOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::BinOp;
+        match self {
+            mir::BinOp::Add => BinOp::Add,
+            mir::BinOp::Sub => BinOp::Sub,
+            mir::BinOp::Mul => BinOp::Mul,
+            mir::BinOp::Div => BinOp::Div,
+            mir::BinOp::Rem => BinOp::Rem,
+            mir::BinOp::BitXor => BinOp::BitXor,
+            mir::BinOp::BitAnd => BinOp::BitAnd,
+            mir::BinOp::BitOr => BinOp::BitOr,
+            mir::BinOp::Shl => BinOp::Shl,
+            mir::BinOp::Shr => BinOp::Shr,
+            mir::BinOp::Eq => BinOp::Eq,
+            mir::BinOp::Lt => BinOp::Lt,
+            mir::BinOp::Le => BinOp::Le,
+            mir::BinOp::Ne => BinOp::Ne,
+            mir::BinOp::Ge => BinOp::Ge,
+            mir::BinOp::Gt => BinOp::Gt,
+            mir::BinOp::Offset => BinOp::Offset,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::UnOp {
+    type T = stable_mir::mir::UnOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::UnOp;
+        match self {
+            mir::UnOp::Not => UnOp::Not,
+            mir::UnOp::Neg => UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::NullOp {
+    type T = stable_mir::mir::NullOp;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::NullOp;
+        match self {
+            mir::NullOp::SizeOf => NullOp::SizeOf,
+            mir::NullOp::AlignOf => NullOp::AlignOf,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Operand<'tcx> {
+    type T = stable_mir::mir::Operand;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::Operand;
+        match self {
+            mir::Operand::Copy(place) => Operand::Copy(place.stable(tables)),
+            mir::Operand::Move(place) => Operand::Move(place.stable(tables)),
+            mir::Operand::Constant(constant) => Operand::Constant(stable_mir::mir::Constant {
+                span: tables.span(constant.span),
+                literal: constant.literal.stable(tables),
+            }),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
+    type T = stable_mir::mir::Place;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::mir::Place {
+            local: self.local.as_usize(),
+            projection: self.projection.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = stable_mir::mir::ProjectionElem;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::ProjectionElem;
+        match *self {
+            mir::ProjectionElem::Deref => ProjectionElem::Deref,
+            mir::ProjectionElem::Field(field, ty) => {
+                ProjectionElem::Field(field.as_usize(), ty.stable(tables))
+            }
+            mir::ProjectionElem::Index(local) => ProjectionElem::Index(local.as_usize()),
+            mir::ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                ProjectionElem::ConstantIndex { offset, min_length, from_end }
+            }
+            mir::ProjectionElem::Subslice { from, to, from_end } => {
+                ProjectionElem::Subslice { from, to, from_end }
+            }
+            mir::ProjectionElem::Downcast(_, variant_index) => {
+                ProjectionElem::Downcast(variant_index.as_usize())
+            }
+            mir::ProjectionElem::OpaqueCast(ty) => ProjectionElem::OpaqueCast(ty.stable(tables)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::ConstantKind<'tcx> {
+    type T = stable_mir::ty::Const;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::ConstKind;
+        let kind = match *self {
+            mir::ConstantKind::Ty(c) => return c.stable(tables),
+            mir::ConstantKind::Unevaluated(unevaluated, _) => ConstKind::Unevaluated(
+                tables.item(unevaluated.def.did),
+                unevaluated.substs.stable(tables),
+            ),
+            mir::ConstantKind::Val(ConstValue::Scalar(Scalar::Int(int)), _) => scalar_int(int),
+            mir::ConstantKind::Val(ConstValue::ZeroSized, _) => ConstKind::ZeroSized,
+            mir::ConstantKind::Val(..) => ConstKind::Other(self.to_string()),
+        };
+        stable_mir::ty::Const { ty: self.ty().stable(tables), kind }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Const<'tcx> {
+    type T = stable_mir::ty::Const;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::{ConstKind, ParamConst};
+        let kind = match self.kind() {
+            ty::ConstKind::Param(param) => {
+                ConstKind::Param(ParamConst { index: param.index, name: param.name.to_string() })
+            }
+            ty::ConstKind::Unevaluated(unevaluated) => ConstKind::Unevaluated(
+                tables.item(unevaluated.def.did),
+                unevaluated.substs.stable(tables),
+            ),
+            ty::ConstKind::Value(ty::ValTree::Leaf(int)) => scalar_int(int),
+            ty::ConstKind::Value(ty::ValTree::Branch(branches)) if branches.is_empty() => {
+                ConstKind::ZeroSized
+            }
+            _ => ConstKind::Other(self.to_string()),
+        };
+        stable_mir::ty::Const { ty: self.ty().stable(tables), kind }
+    }
+}
+
+fn scalar_int(int: ty::ScalarInt) -> stable_mir::ty::ConstKind {
+    stable_mir::ty::ConstKind::Scalar {
+        bits: int.assert_bits(int.size()),
+        size: u8::try_from(int.size().bytes()).unwrap(),
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Ty<'tcx> {
+    type T = stable_mir::ty::Ty;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::{ParamTy, Ty, TyId, TyKind};
+        let kind = match *self.kind() {
+            ty::Bool => TyKind::Bool,
+            ty::Char => TyKind::Char,
+            ty::Int(int_ty) => TyKind::Int(int_ty.stable(tables)),
+            ty::Uint(uint_ty) => TyKind::Uint(uint_ty.stable(tables)),
+            ty::Float(float_ty) => TyKind::Float(float_ty.stable(tables)),
+            ty::Adt(adt_def, substs) => {
+                TyKind::Adt(tables.item(adt_def.did()), substs.stable(tables))
+            }
+            ty::Foreign(def_id) => TyKind::Foreign(tables.item(def_id)),
+            ty::Str => TyKind::Str,
+            ty::Array(elem_ty, len) => TyKind::Array(elem_ty.stable(tables), len.stable(tables)),
+            ty::Slice(elem_ty) => TyKind::Slice(elem_ty.stable(tables)),
+            ty::RawPtr(ty::TypeAndMut { ty: pointee_ty, mutbl }) => {
+                TyKind::RawPtr(pointee_ty.stable(tables), mutbl.stable(tables))
+            }
+            ty::Ref(region, pointee_ty, mutbl) => {
+                TyKind::Ref(region.stable(tables), pointee_ty.stable(tables), mutbl.stable(tables))
+            }
+            ty::FnDef(def_id, substs) => TyKind::FnDef(tables.item(def_id), substs.stable(tables)),
+            ty::FnPtr(sig) => TyKind::FnPtr(sig.skip_binder().stable(tables)),
+            ty::Dynamic(predicates, region, _) => TyKind::Dynamic {
+                principal: predicates.principal_def_id().map(|def_id| tables.item(def_id)),
+                auto_traits: predicates.auto_traits().map(|def_id| tables.item(def_id)).collect(),
+                region: region.stable(tables),
+            },
+            ty::Closure(def_id, substs) => {
+                TyKind::Closure(tables.item(def_id), substs.stable(tables))
+            }
+            ty::Generator(def_id, substs, movability) => TyKind::Generator(
+                tables.item(def_id),
+                substs.stable(tables),
+                movability.stable(tables),
+            ),
+            ty::Never => TyKind::Never,
+            ty::Tuple(tys) => TyKind::Tuple(tys.stable(tables)),
+            ty::Alias(kind, alias_ty) => TyKind::Alias(
+                kind.stable(tables),
+                tables.item(alias_ty.def_id),
+                alias_ty.substs.stable(tables),
+            ),
+            ty::Param(param) => {
+                TyKind::Param(ParamTy { index: param.index, name: param.name.to_string() })
+            }
+            ty::GeneratorWitness(_)
+            | ty::GeneratorWitnessMIR(..)
+            | ty::Bound(..)
+            | ty::Placeholder(_)
+            | ty::Infer(_)
+            | ty::Error(_) => TyKind::Unsupported(self.to_string()),
+        };
+        Ty { id: TyId(tables.types.insert_full(*self).0), kind: Box::new(kind) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::FnSig<'tcx> {
+    type T = stable_mir::ty::FnSig;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::FnSig {
+            inputs: self.inputs().stable(tables),
+            output: self.output().stable(tables),
+            c_variadic: self.c_variadic,
+            safety: self.unsafety.stable(tables),
+            abi: self.abi.name().to_string(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::SubstsRef<'tcx> {
+    type T = stable_mir::ty::GenericArgs;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::GenericArgKind;
+        let args = self
+            .iter()
+            .map(|arg| match arg.unpack() {
+                ty::GenericArgKind::Lifetime(region) => {
+                    GenericArgKind::Lifetime(region.stable(tables))
+                }
+                ty::GenericArgKind::Type(ty) => GenericArgKind::Type(ty.stable(tables)),
+                ty::GenericArgKind::Const(c) => GenericArgKind::Const(c.stable(tables)),
+            })
+            .collect();
+        stable_mir::ty::GenericArgs(args)
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Region<'tcx> {
+    type T = stable_mir::ty::Region;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::Region(self.to_string())
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::IntTy {
+    type T = stable_mir::ty::IntTy;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::IntTy;
+        match self {
+            ty::IntTy::Isize => IntTy::Isize,
+            ty::IntTy::I8 => IntTy::I8,
+            ty::IntTy::I16 => IntTy::I16,
+            ty::IntTy::I32 => IntTy::I32,
+            ty::IntTy::I64 => IntTy::I64,
+            ty::IntTy::I128 => IntTy::I128,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::UintTy {
+    type T = stable_mir::ty::UintTy;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::UintTy;
+        match self {
+            ty::UintTy::Usize => UintTy::Usize,
+            ty::UintTy::U8 => UintTy::U8,
+            ty::UintTy::U16 => UintTy::U16,
+            ty::UintTy::U32 => UintTy::U32,
+            ty::UintTy::U64 => UintTy::U64,
+            ty::UintTy::U128 => UintTy::U128,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::FloatTy {
+    type T = stable_mir::ty::FloatTy;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::FloatTy;
+        match self {
+            ty::FloatTy::F32 => FloatTy::F32,
+            ty::FloatTy::F64 => FloatTy::F64,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::AliasKind {
+    type T = stable_mir::ty::AliasKind;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::AliasKind;
+        match self {
+            ty::Projection => AliasKind::Projection,
+            ty::Opaque => AliasKind::Opaque,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Mutability {
+    type T = stable_mir::ty::Mutability;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::Mutability;
+        match self {
+            hir::Mutability::Not => Mutability::Not,
+            hir::Mutability::Mut => Mutability::Mut,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Movability {
+    type T = stable_mir::ty::Movability;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::Movability;
+        match self {
+            hir::Movability::Static => Movability::Static,
+            hir::Movability::Movable => Movability::Movable,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::Unsafety {
+    type T = stable_mir::ty::Safety;
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::Safety;
+        match self {
+            hir::Unsafety::Unsafe => Safety::Unsafe,
+            hir::Unsafety::Normal => Safety::Normal,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Instance<'tcx> {
+    type T = stable_mir::mono::Instance;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mono::{Instance, InstanceId, InstanceKind};
+        let kind = match self.def {
+            ty::InstanceDef::Item(_) => InstanceKind::Item,
+            ty::InstanceDef::Intrinsic(_) => InstanceKind::Intrinsic,
+            ty::InstanceDef::Virtual(_, idx) => InstanceKind::Virtual { idx },
+            ty::InstanceDef::VTableShim(_)
+            | ty::InstanceDef::ReifyShim(_)
+            | ty::InstanceDef::FnPtrShim(..)
+            | ty::InstanceDef::ClosureOnceShim { .. }
+            | ty::InstanceDef::DropGlue(..)
+            | ty::InstanceDef::CloneShim(..) => InstanceKind::Shim,
+        };
+        Instance {
+            id: InstanceId(tables.instances.insert_full(*self).0),
+            def: tables.item(self.def_id()),
+            args: self.substs.stable(tables),
+            kind,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Generics {
+    type T = stable_mir::ty::Generics;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::Generics {
+            parent: self.parent.map(|parent| tables.item(parent)),
+            parent_count: self.parent_count,
+            params: self.params.stable(tables),
+            has_self: self.has_self,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::GenericParamDef {
+    type T = stable_mir::ty::GenericParamDef;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::ty::GenericParamDefKind;
+        let kind = match self.kind {
+            ty::GenericParamDefKind::Lifetime => GenericParamDefKind::Lifetime,
+            ty::GenericParamDefKind::Type { has_default, synthetic } => {
+                GenericParamDefKind::Type { has_default, synthetic }
+            }
+            ty::GenericParamDefKind::Const { has_default } => {
+                GenericParamDefKind::Const { has_default }
+            }
+        };
+        stable_mir::ty::GenericParamDef {
+            name: self.name.to_string(),
+            def_id: tables.stable_def_id(self.def_id),
+            index: self.index,
+            kind,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::TraitRef<'tcx> {
+    type T = stable_mir::ty::TraitRef;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::ty::TraitRef { def: tables.item(self.def_id), args: self.substs.stable(tables) }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mono::MonoItem<'tcx> {
+    type T = stable_mir::mono::MonoItem;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mono::MonoItem;
+        match *self {
+            mono::MonoItem::Fn(instance) => MonoItem::Fn(instance.stable(tables)),
+            mono::MonoItem::Static(def_id) => MonoItem::Static(tables.item(def_id)),
+            mono::MonoItem::GlobalAsm(item_id) => {
+                MonoItem::GlobalAsm(tables.item(item_id.owner_id.to_def_id()))
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+//! Module that implements the public interface to the Stable MIR.
+//!
+//! This module contains the type definitions that we expect 3P tools to use to interact with the
+//! compiler. All of them are owned and free of lifetimes and compiler internal types, so they can
+//! outlive the compiler session and be serialized with `rustc_serialize`, e.g. to hand them to an
+//! analyzer running in another process.
+//!
+//! The goal is to eventually move this module to its own crate which shall be published on
+//! [crates.io](https://crates.io).
+
+use crate::very_unstable::macros::{Decodable, Encodable};
+
+pub mod mir;
+pub mod mono;
+pub mod ty;
+
+/// An opaque identifier of an item (function, type, trait, ...).
+///
+/// These are only meaningful for the [`Tables`](crate::rustc_smir::Tables) that created them, and
+/// in particular they are not stable across compiler sessions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct DefId(pub(crate) usize);
+
+/// A reference to an item, together with its path for consumers which don't have access to the
+/// compiler session which created it anymore.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Item {
+    pub def_id: DefId,
+    /// The path of the item, like `std::vec::Vec` or `<Foo as Bar>::baz`.
+    pub path: String,
+}
+
+/// A source code region. Lines and columns start at 1, and the end is exclusive.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Span {
+    pub file_name: String,
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+/// The interface to the compiler for the questions which can't be answered by the Stable MIR types
+/// themselves. It is implemented by [`Tables`](crate::rustc_smir::Tables).
+pub trait Context {
+    /// Returns the optimized MIR of a function. The MIR of a generic function still refers to its
+    /// generic parameters. This panics if the item doesn't have MIR.
+    fn mir_body(&mut self, item: DefId) -> mir::Body;
+
+    /// Returns the signature of a function or method, which may refer to its generic parameters.
+    /// Its late-bound lifetimes, like `'a` in `fn f<'a>(x: &'a u8)`, refer to the function's own
+    /// lifetime parameters, as they would in its body.
+    fn fn_sig(&mut self, item: DefId) -> ty::FnSig;
+
+    /// Returns the generic parameters of an item.
+    fn generics_of(&mut self, item: DefId) -> ty::Generics;
+
+    /// Returns the impls of any trait which may apply to `ty`. The generic parameters of `ty`
+    /// are not assumed to implement any trait, so for example the impl of `Clone` for `Vec<T>`
+    /// is only returned for `ty` being `Vec<u8>` and not for `Vec<T>`.
+    fn trait_impls(&mut self, ty: &ty::Ty) -> Vec<ty::TraitImpl>;
+
+    /// Returns the layout of `ty`, or `None` if it depends on generic parameters or the type
+    /// is too big.
+    fn layout_of(&mut self, ty: &ty::Ty) -> Option<ty::Layout>;
+
+    /// Resolves the function called through a function item type, like the type of the callee of
+    /// a `Call` terminator, to the instance which is actually called. This returns `None` if
+    /// `fn_def` isn't a function item type, or if it is a trait method whose impl can't be
+    /// selected because it depends on generic parameters.
+    fn resolve_instance(&mut self, fn_def: &ty::Ty) -> Option<mono::Instance>;
+
+    /// Returns the MIR of an instance, in which all generic parameters have been substituted.
+    /// This panics if the instance doesn't have MIR, like intrinsics.
+    fn instance_body(&mut self, instance: &mono::Instance) -> mir::Body;
+}
+
+/// The crate being compiled, as handed to the callback of [`run`](crate::run).
+pub struct Crate<'a> {
+    pub name: String,
+    /// The functions and closures defined in this crate.
+    pub local_items: Vec<Item>,
+    /// The `main` function if this is an executable.
+    pub entry_fn: Option<Item>,
+    /// The items which are codegened for this crate, including the instances of generic functions
+    /// of other crates used by this crate.
+    pub mono_items: Vec<mono::MonoItem>,
+    /// The context to query the compiler about the items of this crate.
+    pub cx: &'a mut dyn Context,
+}
+
+/// The error returned by [`run`](crate::run) if the compilation failed. The errors have already
+/// been reported to the user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompilerError;
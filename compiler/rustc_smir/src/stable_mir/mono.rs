@@ -0,0 +1,45 @@
+//! Instances, i.e. functions together with the generic arguments they are used with.
+
+use crate::stable_mir::ty::GenericArgs;
+use crate::stable_mir::Item;
+use crate::very_unstable::macros::{Decodable, Encodable};
+
+/// A function, method, closure or shim with concrete generic arguments, which can be codegened.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Instance {
+    pub(crate) id: InstanceId,
+    pub def: Item,
+    pub args: GenericArgs,
+    pub kind: InstanceKind,
+}
+
+impl Instance {
+    pub fn id(&self) -> InstanceId {
+        self.id
+    }
+}
+
+/// An opaque identifier of an instance. Like [`DefId`](crate::stable_mir::DefId), it is only
+/// meaningful for the [`Tables`](crate::rustc_smir::Tables) that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct InstanceId(pub(crate) usize);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum InstanceKind {
+    /// A function with a MIR body.
+    Item,
+    /// A compiler intrinsic, which doesn't have a MIR body.
+    Intrinsic,
+    /// A call through a vtable, with the index of the method in the vtable.
+    Virtual { idx: usize },
+    /// A compiler generated function, like drop glue or the `FnOnce::call_once` of a closure.
+    Shim,
+}
+
+/// An item which is codegened.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum MonoItem {
+    Fn(Instance),
+    Static(Item),
+    GlobalAsm(Item),
+}
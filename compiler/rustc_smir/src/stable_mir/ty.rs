@@ -0,0 +1,236 @@
+//! Types, generic arguments and constants of the Stable MIR.
+
+use crate::stable_mir::{DefId, Item};
+use crate::very_unstable::macros::{Decodable, Encodable};
+
+/// A type. Types are trees which own all their components. Each type also has an opaque id, which
+/// is what the [`Context`](crate::stable_mir::Context) uses to answer questions about it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Ty {
+    pub(crate) id: TyId,
+    pub(crate) kind: Box<TyKind>,
+}
+
+impl Ty {
+    pub fn id(&self) -> TyId {
+        self.id
+    }
+
+    pub fn kind(&self) -> &TyKind {
+        &self.kind
+    }
+}
+
+/// An opaque identifier of a type. Like [`DefId`](crate::stable_mir::DefId), it is only
+/// meaningful for the [`Tables`](crate::rustc_smir::Tables) that created it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct TyId(pub(crate) usize);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum TyKind {
+    Bool,
+    Char,
+    Int(IntTy),
+    Uint(UintTy),
+    Float(FloatTy),
+    Adt(Item, GenericArgs),
+    Foreign(Item),
+    Str,
+    Array(Ty, Const),
+    Slice(Ty),
+    RawPtr(Ty, Mutability),
+    Ref(Region, Ty, Mutability),
+    FnDef(Item, GenericArgs),
+    FnPtr(FnSig),
+    /// A trait object. Only the traits are kept, without the generic arguments of the principal
+    /// trait and the associated type bindings.
+    Dynamic {
+        principal: Option<Item>,
+        auto_traits: Vec<Item>,
+        region: Region,
+    },
+    Closure(Item, GenericArgs),
+    Generator(Item, GenericArgs, Movability),
+    Never,
+    Tuple(Vec<Ty>),
+    /// A projection like `<T as Iterator>::Item` or an opaque type like `impl Trait`.
+    Alias(AliasKind, Item, GenericArgs),
+    Param(ParamTy),
+    /// A type which can't occur in optimized MIR, like inference variables and bound types. The
+    /// string is how the compiler prints the type.
+    Unsupported(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Movability {
+    Static,
+    Movable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum Safety {
+    Unsafe,
+    Normal,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum AliasKind {
+    Projection,
+    Opaque,
+}
+
+/// A lifetime. Lifetimes are erased in optimized MIR, so they are only kept as the string the
+/// compiler prints for them, like `'static` or `'_`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Region(pub String);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct ParamTy {
+    pub index: u32,
+    pub name: String,
+}
+
+/// The signature of a function or function pointer. Bound lifetimes are kept like any other
+/// lifetime.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct FnSig {
+    pub inputs: Vec<Ty>,
+    pub output: Ty,
+    pub c_variadic: bool,
+    pub safety: Safety,
+    /// The ABI as written in source, like `Rust` or `C`.
+    pub abi: String,
+}
+
+/// The generic arguments of an item, in the order of its generic parameters, which starts with
+/// those of its parents.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct GenericArgs(pub Vec<GenericArgKind>);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum GenericArgKind {
+    Lifetime(Region),
+    Type(Ty),
+    Const(Const),
+}
+
+/// A constant, either from the type system, like the length of an array type, or from MIR.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Const {
+    pub ty: Ty,
+    pub kind: ConstKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum ConstKind {
+    /// An integer, `bool`, `char` or float constant, or any other constant which fits in a scalar
+    /// without being a pointer. The value is the bits of the first `size` bytes of `bits`.
+    Scalar {
+        bits: u128,
+        size: u8,
+    },
+    /// The value of a zero-sized type, like `()` or a function item.
+    ZeroSized,
+    Param(ParamConst),
+    /// A constant which hasn't been evaluated yet, like an associated constant of a generic type
+    /// parameter.
+    Unevaluated(Item, GenericArgs),
+    /// Any other constant, like a string literal or a reference to a static. The string is how
+    /// the compiler prints the constant.
+    Other(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct ParamConst {
+    pub index: u32,
+    pub name: String,
+}
+
+/// The generic parameters of an item.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Generics {
+    /// The item whose generic parameters come before the ones of this item, like the impl of a
+    /// method.
+    pub parent: Option<Item>,
+    pub parent_count: usize,
+    /// The own generic parameters of this item. The index of the first one is `parent_count`.
+    pub params: Vec<GenericParamDef>,
+    /// Whether this is a trait, whose first parameter is `Self`.
+    pub has_self: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct GenericParamDef {
+    pub name: String,
+    pub def_id: DefId,
+    pub index: u32,
+    pub kind: GenericParamDefKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub enum GenericParamDefKind {
+    Lifetime,
+    /// A type parameter. Synthetic parameters are the ones created for `impl Trait` arguments.
+    Type {
+        has_default: bool,
+        synthetic: bool,
+    },
+    Const {
+        has_default: bool,
+    },
+}
+
+/// A trait with the generic arguments for it, like `Iterator` or `From<u8>`. The first argument
+/// is the `Self` type.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct TraitRef {
+    pub def: Item,
+    pub args: GenericArgs,
+}
+
+/// A trait impl. The generic arguments of the trait ref refer to the generic parameters of the
+/// impl.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct TraitImpl {
+    pub impl_def: Item,
+    pub trait_ref: TraitRef,
+}
+
+/// The size and alignment of a type in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Encodable, Decodable)]
+pub struct Layout {
+    pub size: u64,
+    pub align: u64,
+}
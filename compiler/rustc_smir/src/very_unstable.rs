@@ -16,12 +16,15 @@ macro_rules! crates {
 
 crates! {
     rustc_borrowck -> borrowck,
+    rustc_data_structures -> data_structures,
     rustc_driver -> driver,
     rustc_hir -> hir,
     rustc_interface -> interface,
+    rustc_macros -> macros,
     rustc_middle -> middle,
     rustc_mir_dataflow -> dataflow,
     rustc_mir_transform -> transform,
     rustc_serialize -> serialize,
+    rustc_span -> span,
     rustc_trait_selection -> trait_selection,
 }
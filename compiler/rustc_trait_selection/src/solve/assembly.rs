@@ -7,6 +7,8 @@
 use rustc_hir::def_id::DefId;
 use rustc_infer::traits::query::NoSolution;
 use rustc_infer::traits::util::elaborate_predicates;
+use crate::traits::select::describe_impl;
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::TypeFoldable;
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use std::fmt::Debug;
@@ -287,7 +289,10 @@ fn assemble_impl_candidates<G: GoalKind<'tcx>>(
         tcx.for_each_relevant_impl(
             goal.predicate.trait_def_id(tcx),
             goal.predicate.self_ty(),
-            |impl_def_id| match G::consider_impl_candidate(self, goal, impl_def_id) {
+            |impl_def_id| match self.consider_recorded(
+                |_| describe_impl(tcx, impl_def_id),
+                |ecx| G::consider_impl_candidate(ecx, goal, impl_def_id),
+            ) {
                 Ok(result) => candidates
                     .push(Candidate { source: CandidateSource::Impl(impl_def_id), result }),
                 Err(NoSolution) => (),
@@ -334,6 +339,9 @@ fn assemble_builtin_impl_candidates<G: GoalKind<'tcx>>(
 
         match result {
             Ok(result) => {
+                // Builtin candidates are recorded once evaluated, so their nested goals are
+                // recorded as nested in the goal itself.
+                let _ = self.consider_recorded(|_| "builtin impl".to_string(), |_| Ok(result));
                 candidates.push(Candidate { source: CandidateSource::BuiltinImpl, result })
             }
             Err(NoSolution) => (),
@@ -354,7 +362,10 @@ fn assemble_param_env_candidates<G: GoalKind<'tcx>>(
         candidates: &mut Vec<Candidate<'tcx>>,
     ) {
         for (i, assumption) in goal.param_env.caller_bounds().iter().enumerate() {
-            match G::consider_assumption(self, goal, assumption) {
+            match self.consider_recorded(
+                |_| with_no_trimmed_paths!(format!("where {assumption}")),
+                |ecx| G::consider_assumption(ecx, goal, assumption),
+            ) {
                 Ok(result) => {
                     candidates.push(Candidate { source: CandidateSource::ParamEnv(i), result })
                 }
@@ -401,7 +412,10 @@ fn assemble_alias_bound_candidates<G: GoalKind<'tcx>>(
 
         for assumption in self.tcx().item_bounds(alias_ty.def_id).subst(self.tcx(), alias_ty.substs)
         {
-            match G::consider_assumption(self, goal, assumption) {
+            match self.consider_recorded(
+                |_| with_no_trimmed_paths!(format!("alias bound {assumption}")),
+                |ecx| G::consider_assumption(ecx, goal, assumption),
+            ) {
                 Ok(result) => {
                     candidates.push(Candidate { source: CandidateSource::AliasBound, result })
                 }
@@ -451,7 +465,10 @@ fn assemble_object_bound_candidates<G: GoalKind<'tcx>>(
         for assumption in
             elaborate_predicates(tcx, bounds.iter().map(|bound| bound.with_self_ty(tcx, self_ty)))
         {
-            match G::consider_assumption(self, goal, assumption.predicate) {
+            match self.consider_recorded(
+                |_| with_no_trimmed_paths!(format!("object bound {}", assumption.predicate)),
+                |ecx| G::consider_assumption(ecx, goal, assumption.predicate),
+            ) {
                 Ok(result) => {
                     candidates.push(Candidate { source: CandidateSource::BuiltinImpl, result })
                 }
@@ -460,6 +477,31 @@ fn assemble_object_bound_candidates<G: GoalKind<'tcx>>(
         }
     }
 
+    /// Evaluates a candidate with `consider`, and records it with the description returned by
+    /// `candidate` if the goals are recorded for `-Z dump-trait-selection-tree`.
+    fn consider_recorded(
+        &mut self,
+        candidate: impl FnOnce(&Self) -> String,
+        consider: impl FnOnce(&mut Self) -> QueryResult<'tcx>,
+    ) -> QueryResult<'tcx> {
+        if self.search_graph.selection_tree.is_none() {
+            return consider(self);
+        }
+
+        let description = candidate(self);
+        let prev = self.search_graph.selection_tree.as_mut().unwrap().enter_candidate(description);
+        let result = consider(self);
+        let outcome = match &result {
+            Ok(response) => match response.value.certainty {
+                Certainty::Yes => "applies".to_string(),
+                Certainty::Maybe(cause) => format!("may apply, {cause:?}"),
+            },
+            Err(NoSolution) => "rejected".to_string(),
+        };
+        self.search_graph.selection_tree.as_mut().unwrap().exit_candidate(outcome, prev);
+        result
+    }
+
     #[instrument(level = "debug", skip(self), ret)]
     pub(super) fn merge_candidates_and_discard_reservation_impls(
         &mut self,
@@ -8,7 +8,7 @@
 use rustc_middle::ty;
 use rustc_middle::ty::error::{ExpectedFound, TypeError};
 
-use super::{Certainty, InferCtxtEvalExt};
+use super::{Certainty, Goal, InferCtxtEvalExt};
 
 /// A trait engine using the new trait solver.
 ///
@@ -60,8 +60,8 @@ fn select_where_possible(&mut self, infcx: &InferCtxt<'tcx>) -> Vec<FulfillmentE
 
             let mut has_changed = false;
             for obligation in mem::take(&mut self.obligations) {
-                let goal = obligation.clone().into();
-                let (changed, certainty) = match infcx.evaluate_root_goal(goal) {
+                let goal: Goal<'tcx, ty::Predicate<'tcx>> = obligation.clone().into();
+                let (changed, certainty) = match infcx.evaluate_root_obligation(&obligation) {
                     Ok(result) => result,
                     Err(NoSolution) => {
                         errors.push(FulfillmentError {
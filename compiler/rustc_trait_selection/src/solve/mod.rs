@@ -22,9 +22,11 @@
 use rustc_infer::infer::canonical::{OriginalQueryValues, QueryRegionConstraints, QueryResponse};
 use rustc_infer::infer::{InferCtxt, InferOk, TyCtxtInferExt};
 use rustc_infer::traits::query::NoSolution;
-use rustc_infer::traits::Obligation;
+use rustc_infer::traits::{Obligation, PredicateObligation};
 use rustc_middle::infer::canonical::Certainty as OldCertainty;
+use rustc_middle::traits::selection_tree::SelectionNode;
 use rustc_middle::traits::solve::{ExternalConstraints, ExternalConstraintsData};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, TyCtxt};
 use rustc_middle::ty::{
     CoercePredicate, RegionOutlivesPredicate, SubtypePredicate, ToPredicate, TypeOutlivesPredicate,
@@ -32,12 +34,14 @@
 use rustc_span::DUMMY_SP;
 
 use crate::traits::ObligationCause;
+use selection_tree::SelectionTreeBuilder;
 
 mod assembly;
 mod fulfill;
 mod infcx_ext;
 mod project_goals;
 mod search_graph;
+mod selection_tree;
 mod trait_goals;
 
 pub use fulfill::FulfillmentCtxt;
@@ -140,6 +144,14 @@ fn evaluate_root_goal(
         &self,
         goal: Goal<'tcx, ty::Predicate<'tcx>>,
     ) -> Result<(bool, Certainty), NoSolution>;
+
+    /// Evaluates the goal of `obligation` like [`InferCtxtEvalExt::evaluate_root_goal`], and
+    /// records the goals and candidates it evaluated if `-Z dump-trait-selection-tree`
+    /// dumps the item of the obligation.
+    fn evaluate_root_obligation(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+    ) -> Result<(bool, Certainty), NoSolution>;
 }
 
 impl<'tcx> InferCtxtEvalExt<'tcx> for InferCtxt<'tcx> {
@@ -147,19 +159,46 @@ fn evaluate_root_goal(
         &self,
         goal: Goal<'tcx, ty::Predicate<'tcx>>,
     ) -> Result<(bool, Certainty), NoSolution> {
-        let mut search_graph = search_graph::SearchGraph::new(self.tcx);
+        evaluate_root(self, goal, false).0
+    }
+
+    fn evaluate_root_obligation(
+        &self,
+        obligation: &PredicateObligation<'tcx>,
+    ) -> Result<(bool, Certainty), NoSolution> {
+        let record = self.tcx.records_trait_selection(obligation.cause.body_id);
+        let (result, tree) = evaluate_root(self, obligation.clone().into(), record);
+        if let Some(mut tree) = tree {
+            let span = self.tcx.sess.source_map().span_to_embeddable_string(obligation.cause.span);
+            tree.span = Some(span);
+            self.tcx.selection_tree.lock().add_root(tree);
+        }
+        result
+    }
+}
+
+fn evaluate_root<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    goal: Goal<'tcx, ty::Predicate<'tcx>>,
+    record: bool,
+) -> (Result<(bool, Certainty), NoSolution>, Option<SelectionNode>) {
+    infcx.tcx.sess.time_trait_selection(|| {
+        let mut search_graph = search_graph::SearchGraph::new(infcx.tcx);
+        if record {
+            search_graph.selection_tree = Some(SelectionTreeBuilder::default());
+        }
 
         let result = EvalCtxt {
             search_graph: &mut search_graph,
-            infcx: self,
+            infcx,
             var_values: CanonicalVarValues::dummy(),
             in_projection_eq_hack: false,
         }
         .evaluate_goal(goal);
 
         assert!(search_graph.is_empty());
-        result
-    }
+        (result, search_graph.selection_tree.and_then(|tree| tree.root))
+    })
 }
 
 struct EvalCtxt<'a, 'tcx> {
@@ -231,6 +270,27 @@ fn make_canonical_response(&self, certainty: Certainty) -> QueryResult<'tcx> {
     fn evaluate_goal(
         &mut self,
         goal: Goal<'tcx, ty::Predicate<'tcx>>,
+    ) -> Result<(bool, Certainty), NoSolution> {
+        if self.search_graph.selection_tree.is_none() {
+            return self.evaluate_goal_unrecorded(goal);
+        }
+
+        let predicate = self.infcx.resolve_vars_if_possible(goal.predicate);
+        let description = with_no_trimmed_paths!(predicate.to_string());
+        self.search_graph.selection_tree.as_mut().unwrap().enter_goal(description);
+        let result = self.evaluate_goal_unrecorded(goal);
+        let description = match result {
+            Ok((_, Certainty::Yes)) => "holds".to_string(),
+            Ok((_, Certainty::Maybe(cause))) => format!("ambiguous, {cause:?}"),
+            Err(NoSolution) => "no solution".to_string(),
+        };
+        self.search_graph.selection_tree.as_mut().unwrap().exit_goal(description);
+        result
+    }
+
+    fn evaluate_goal_unrecorded(
+        &mut self,
+        goal: Goal<'tcx, ty::Predicate<'tcx>>,
     ) -> Result<(bool, Certainty), NoSolution> {
         let mut orig_values = OriginalQueryValues::default();
         let canonical_goal = self.infcx.canonicalize_query(goal, &mut orig_values);
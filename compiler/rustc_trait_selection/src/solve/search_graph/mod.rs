@@ -2,6 +2,7 @@
 mod overflow;
 
 use self::cache::ProvisionalEntry;
+use super::selection_tree::SelectionTreeBuilder;
 use super::{CanonicalGoal, Certainty, MaybeCause, QueryResult};
 use cache::ProvisionalCache;
 use overflow::OverflowData;
@@ -25,6 +26,8 @@ pub(super) struct SearchGraph<'tcx> {
     stack: IndexVec<StackDepth, StackElem<'tcx>>,
     overflow_data: OverflowData,
     provisional_cache: ProvisionalCache<'tcx>,
+    /// The goals and candidates evaluated, if `-Z dump-trait-selection-tree` records them.
+    pub(super) selection_tree: Option<SelectionTreeBuilder>,
 }
 
 impl<'tcx> SearchGraph<'tcx> {
@@ -33,6 +36,7 @@ pub(super) fn new(tcx: TyCtxt<'tcx>) -> SearchGraph<'tcx> {
             stack: Default::default(),
             overflow_data: OverflowData::new(tcx),
             provisional_cache: ProvisionalCache::empty(),
+            selection_tree: None,
         }
     }
 
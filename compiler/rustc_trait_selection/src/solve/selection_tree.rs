@@ -0,0 +1,52 @@
+//! Records the goals and candidates evaluated for a root goal, for
+//! `-Z dump-trait-selection-tree`.
+
+use rustc_middle::traits::selection_tree::{CandidateOutcome, SelectionAttempt, SelectionNode};
+
+/// Builds the tree of a root goal. Nested goals are evaluated recursively, so the goals being
+/// evaluated form a stack.
+#[derive(Default)]
+pub(super) struct SelectionTreeBuilder {
+    stack: Vec<GoalFrame>,
+    /// The tree of the root goal, once it has been evaluated.
+    pub(super) root: Option<SelectionNode>,
+}
+
+struct GoalFrame {
+    node: SelectionNode,
+    attempt: SelectionAttempt,
+    /// The candidate being evaluated, which requires the goals nested in it.
+    candidate: Option<String>,
+}
+
+impl SelectionTreeBuilder {
+    pub(super) fn enter_goal(&mut self, goal: String) {
+        self.stack.push(GoalFrame {
+            node: SelectionNode::new(goal, None),
+            attempt: SelectionAttempt::default(),
+            candidate: None,
+        });
+    }
+
+    pub(super) fn exit_goal(&mut self, result: String) {
+        let GoalFrame { mut node, mut attempt, .. } = self.stack.pop().unwrap();
+        attempt.result = result;
+        node.attempts.push(attempt);
+        match self.stack.last_mut() {
+            Some(parent) => parent.node.add_child(parent.candidate.clone(), node),
+            None => self.root = Some(node),
+        }
+    }
+
+    /// Starts evaluating `candidate` for the innermost goal, returning the candidate that was
+    /// being evaluated before, which must be passed to [`Self::exit_candidate`].
+    pub(super) fn enter_candidate(&mut self, candidate: String) -> Option<String> {
+        self.stack.last_mut().unwrap().candidate.replace(candidate)
+    }
+
+    pub(super) fn exit_candidate(&mut self, outcome: String, prev: Option<String>) {
+        let frame = self.stack.last_mut().unwrap();
+        let candidate = std::mem::replace(&mut frame.candidate, prev).unwrap();
+        frame.attempt.candidates.push(CandidateOutcome { candidate, outcome });
+    }
+}
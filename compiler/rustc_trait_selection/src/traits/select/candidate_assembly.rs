@@ -359,6 +359,12 @@ fn assemble_candidates_from_impls(
                 // and so forth that we need to.
                 let impl_trait_ref = self.tcx().impl_trait_ref(impl_def_id).unwrap();
                 if self.fast_reject_trait_refs(obligation, &impl_trait_ref.0) {
+                    if self.is_recording_selection() {
+                        self.record_candidate(
+                            self.describe_impl(impl_def_id),
+                            "rejected, the types of its header can't match",
+                        );
+                    }
                     return;
                 }
 
@@ -58,10 +58,14 @@
 use std::iter;
 
 pub use rustc_middle::traits::select::*;
+use rustc_middle::traits::selection_tree::SelectionAttempt;
 use rustc_middle::ty::print::with_no_trimmed_paths;
 
 mod candidate_assembly;
 mod confirmation;
+mod selection_tree;
+
+pub(crate) use selection_tree::describe_impl;
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum IntercrateAmbiguityCause {
@@ -126,6 +130,11 @@ pub struct SelectionContext<'cx, 'tcx> {
     /// policy. In essence, canonicalized queries need their errors propagated
     /// rather than immediately reported because we do not have accurate spans.
     query_mode: TraitQueryMode,
+
+    /// The candidates considered for the obligations being selected, innermost last, if
+    /// `-Z dump-trait-selection-tree` is enabled. An entry is `None` if its obligation
+    /// doesn't belong to the dumped item.
+    selection_tree_attempts: Vec<Option<SelectionAttempt>>,
 }
 
 // A stack that walks back up the stack frame.
@@ -213,6 +222,7 @@ pub fn new(infcx: &'cx InferCtxt<'tcx>) -> SelectionContext<'cx, 'tcx> {
             freshener: infcx.freshener_keep_static(),
             intercrate_ambiguity_causes: None,
             query_mode: TraitQueryMode::Standard,
+            selection_tree_attempts: Vec::new(),
         }
     }
 
@@ -270,6 +280,14 @@ pub fn is_intercrate(&self) -> bool {
     pub fn select(
         &mut self,
         obligation: &TraitObligation<'tcx>,
+    ) -> SelectionResult<'tcx, Selection<'tcx>> {
+        let tcx = self.tcx();
+        tcx.sess.time_trait_selection(|| self.select_and_confirm(obligation))
+    }
+
+    fn select_and_confirm(
+        &mut self,
+        obligation: &TraitObligation<'tcx>,
     ) -> SelectionResult<'tcx, Selection<'tcx>> {
         let candidate = match self.select_from_obligation(obligation) {
             Err(SelectionError::Overflow(OverflowError::Canonical)) => {
@@ -330,7 +348,10 @@ fn candidate_from_obligation<'o>(
             self.check_candidate_cache(stack.obligation.param_env, cache_fresh_trait_pred)
         {
             debug!("CACHE HIT");
-            return c;
+            return self.record_selection(stack, |this| {
+                this.record_cached_selection();
+                c
+            });
         }
 
         // If no match, compute result and insert into cache.
@@ -339,8 +360,9 @@ fn candidate_from_obligation<'o>(
         // account cycles that may have occurred in forming the
         // candidate. I don't know of any specific problems that
         // result but it seems awfully suspicious.
-        let (candidate, dep_node) =
-            self.in_task(|this| this.candidate_from_obligation_no_cache(stack));
+        let (candidate, dep_node) = self.in_task(|this| {
+            this.record_selection(stack, |this| this.candidate_from_obligation_no_cache(stack))
+        });
 
         debug!("CACHE MISS");
         self.insert_candidate_cache(
@@ -403,6 +425,14 @@ fn candidate_from_obligation_no_cache<'o>(
 
         let candidate_set = self.assemble_candidates(stack)?;
 
+        // The outcome of each candidate is updated below as it is filtered and winnowed, while
+        // the candidates of an ambiguous set stay as assembled.
+        if self.is_recording_selection() {
+            for c in &candidate_set.vec {
+                self.record_candidate(self.describe_candidate(c), "assembled");
+            }
+        }
+
         if candidate_set.ambiguous {
             debug!("candidate set contains ambig");
             return Ok(None);
@@ -421,7 +451,18 @@ fn candidate_from_obligation_no_cache<'o>(
         // candidate which assumes $0 == int, one that assumes `$0 ==
         // usize`, etc. This spells an ambiguity.
 
-        let mut candidates = self.filter_impls(candidates, stack.obligation);
+        let mut candidates = if self.is_recording_selection() {
+            let filtered = self.filter_impls(candidates.clone(), stack.obligation);
+            for c in candidates.iter().filter(|c| !filtered.contains(c)) {
+                self.record_candidate(
+                    self.describe_candidate(c),
+                    "rejected, its constness or polarity doesn't match",
+                );
+            }
+            filtered
+        } else {
+            self.filter_impls(candidates, stack.obligation)
+        };
 
         // If there is more than one candidate, first winnow them down
         // by considering extra conditions (nested obligations and so
@@ -438,6 +479,12 @@ fn candidate_from_obligation_no_cache<'o>(
         // Instead, we select the right impl now but report "`Bar` does
         // not implement `Clone`".
         if candidates.len() == 1 {
+            if self.is_recording_selection() {
+                self.record_candidate(
+                    self.describe_candidate(&candidates[0]),
+                    "applies, the only candidate left",
+                );
+            }
             return self.filter_reservation_impls(candidates.pop().unwrap(), stack.obligation);
         }
 
@@ -447,9 +494,23 @@ fn candidate_from_obligation_no_cache<'o>(
             .into_iter()
             .map(|c| match self.evaluate_candidate(stack, &c) {
                 Ok(eval) if eval.may_apply() => {
+                    if self.is_recording_selection() {
+                        self.record_candidate(
+                            self.describe_candidate(&c),
+                            format_args!("applies, evaluated to `{eval:?}`"),
+                        );
+                    }
                     Ok(Some(EvaluatedCandidate { candidate: c, evaluation: eval }))
                 }
-                Ok(_) => Ok(None),
+                Ok(eval) => {
+                    if self.is_recording_selection() {
+                        self.record_candidate(
+                            self.describe_candidate(&c),
+                            format_args!("rejected, evaluated to `{eval:?}`"),
+                        );
+                    }
+                    Ok(None)
+                }
                 Err(OverflowError::Canonical) => Err(Overflow(OverflowError::Canonical)),
                 Err(OverflowError::ErrorReporting) => Err(ErrorReporting),
                 Err(OverflowError::Error(e)) => Err(Overflow(OverflowError::Error(e))),
@@ -476,6 +537,12 @@ fn candidate_from_obligation_no_cache<'o>(
                 });
                 if is_dup {
                     debug!(candidate = ?candidates[i], "Dropping candidate #{}/{}", i, candidates.len());
+                    if self.is_recording_selection() {
+                        self.record_candidate(
+                            self.describe_candidate(&candidates[i].candidate),
+                            "winnowed, dropped in favor of another candidate",
+                        );
+                    }
                     candidates.swap_remove(i);
                 } else {
                     debug!(candidate = ?candidates[i], "Retaining candidate #{}/{}", i, candidates.len());
@@ -546,15 +613,18 @@ pub fn evaluate_root_obligation(
         &mut self,
         obligation: &PredicateObligation<'tcx>,
     ) -> Result<EvaluationResult, OverflowError> {
-        self.evaluation_probe(|this| {
-            if this.tcx().sess.opts.unstable_opts.trait_solver != TraitSolver::Next {
-                this.evaluate_predicate_recursively(
-                    TraitObligationStackList::empty(&ProvisionalEvaluationCache::default()),
-                    obligation.clone(),
-                )
-            } else {
-                this.evaluate_predicates_recursively_in_new_solver([obligation.clone()])
-            }
+        let tcx = self.tcx();
+        tcx.sess.time_trait_selection(|| {
+            self.evaluation_probe(|this| {
+                if this.tcx().sess.opts.unstable_opts.trait_solver != TraitSolver::Next {
+                    this.evaluate_predicate_recursively(
+                        TraitObligationStackList::empty(&ProvisionalEvaluationCache::default()),
+                        obligation.clone(),
+                    )
+                } else {
+                    this.evaluate_predicates_recursively_in_new_solver([obligation.clone()])
+                }
+            })
         })
     }
 
@@ -2455,7 +2525,13 @@ fn match_impl(
             .define_opaque_types(false)
             .eq(placeholder_obligation_trait_ref, impl_trait_ref)
             .map_err(|e| {
-                debug!("match_impl: failed eq_trait_refs due to `{}`", e.to_string(self.tcx()))
+                debug!("match_impl: failed eq_trait_refs due to `{}`", e.to_string(self.tcx()));
+                if self.is_recording_selection() {
+                    self.record_candidate(
+                        self.describe_impl(impl_def_id),
+                        format_args!("rejected, {}", e.to_string(self.tcx())),
+                    );
+                }
             })?;
         nested_obligations.extend(obligations);
 
@@ -2463,6 +2539,12 @@ fn match_impl(
             && self.tcx().impl_polarity(impl_def_id) == ty::ImplPolarity::Reservation
         {
             debug!("reservation impls only apply in intercrate mode");
+            if self.is_recording_selection() {
+                self.record_candidate(
+                    self.describe_impl(impl_def_id),
+                    "rejected, reservation impls only apply in coherence",
+                );
+            }
             return Err(());
         }
 
@@ -0,0 +1,139 @@
+//! Records the candidates considered for the obligations of an item, for
+//! `-Z dump-trait-selection-tree`.
+//!
+//! Nested obligations are selected separately from the obligations requiring them, so an
+//! obligation is placed in the tree by following the chain of its cause.
+
+use super::{SelectionCandidate, SelectionContext, SelectionResult, TraitObligationStack};
+use crate::traits::specialize::to_pretty_impl_header;
+use crate::traits::{ObligationCause, ObligationCauseCode, SelectionError};
+use rustc_hir::def_id::DefId;
+use rustc_middle::traits::selection_tree::{CandidateOutcome, SelectionAttempt};
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, TyCtxt, TypeFoldable};
+use std::fmt::Display;
+
+/// Describes an impl on a single line, e.g. `impl<T> Trait for Vec<T> where T: Trait`.
+pub(crate) fn describe_impl(tcx: TyCtxt<'_>, impl_def_id: DefId) -> String {
+    with_no_trimmed_paths!(match to_pretty_impl_header(tcx, impl_def_id) {
+        Some(header) => header.trim_end_matches(';').replace("\n  where", " where"),
+        None => tcx.def_path_str(impl_def_id),
+    })
+}
+
+impl<'cx, 'tcx> SelectionContext<'cx, 'tcx> {
+    /// Runs `select`, which selects a candidate for the obligation of `stack`, and records the
+    /// candidates it considered if the obligation belongs to the dumped item.
+    pub(super) fn record_selection(
+        &mut self,
+        stack: &TraitObligationStack<'_, 'tcx>,
+        select: impl FnOnce(&mut Self) -> SelectionResult<'tcx, SelectionCandidate<'tcx>>,
+    ) -> SelectionResult<'tcx, SelectionCandidate<'tcx>> {
+        let tcx = self.tcx();
+        if tcx.sess.opts.unstable_opts.dump_trait_selection_tree.is_none() {
+            return select(self);
+        }
+
+        let obligation = stack.obligation;
+        let recorded = tcx.records_trait_selection(obligation.cause.body_id);
+        self.selection_tree_attempts.push(recorded.then(SelectionAttempt::default));
+        let result = select(self);
+        let Some(mut attempt) = self.selection_tree_attempts.pop().unwrap() else {
+            return result;
+        };
+
+        attempt.result = match &result {
+            Ok(Some(candidate)) => format!("selected `{}`", self.describe_candidate(candidate)),
+            Ok(None) => "ambiguous".to_string(),
+            Err(SelectionError::Unimplemented) => "no candidate applies".to_string(),
+            Err(e) => format!("error: {e:?}"),
+        };
+        let path = self.selection_tree_path(obligation.predicate, &obligation.cause);
+        let span = tcx.sess.source_map().span_to_embeddable_string(obligation.cause.span);
+        tcx.selection_tree.lock().add_attempt(&path, Some(span), attempt);
+        result
+    }
+
+    /// Whether the candidates of the obligation being selected are recorded.
+    pub(super) fn is_recording_selection(&self) -> bool {
+        matches!(self.selection_tree_attempts.last(), Some(Some(_)))
+    }
+
+    /// Records that the result of the obligation being selected was taken from a cache.
+    pub(super) fn record_cached_selection(&mut self) {
+        if let Some(Some(attempt)) = self.selection_tree_attempts.last_mut() {
+            attempt.cached = true;
+        }
+    }
+
+    /// Records the outcome of a candidate of the obligation being selected, replacing its
+    /// previous outcome.
+    pub(super) fn record_candidate(&mut self, candidate: String, outcome: impl Display) {
+        let Some(Some(attempt)) = self.selection_tree_attempts.last_mut() else {
+            return;
+        };
+        let outcome = outcome.to_string();
+        match attempt.candidates.iter_mut().find(|c| c.candidate == candidate) {
+            Some(existing) => existing.outcome = outcome,
+            None => attempt.candidates.push(CandidateOutcome { candidate, outcome }),
+        }
+    }
+
+    pub(super) fn describe_candidate(&self, candidate: &SelectionCandidate<'tcx>) -> String {
+        match candidate {
+            SelectionCandidate::ImplCandidate(impl_def_id) => self.describe_impl(*impl_def_id),
+            SelectionCandidate::ParamCandidate(bound) => {
+                format!("where {}", self.tree_string(*bound))
+            }
+            _ => format!("{candidate:?}"),
+        }
+    }
+
+    pub(super) fn describe_impl(&self, impl_def_id: DefId) -> String {
+        describe_impl(self.tcx(), impl_def_id)
+    }
+
+    fn tree_string<T: TypeFoldable<'tcx> + Display>(&self, value: T) -> String {
+        with_no_trimmed_paths!(self.infcx.resolve_vars_if_possible(value).to_string())
+    }
+
+    /// Returns the obligations that led to `predicate`, from the root obligation to `predicate`,
+    /// with the impl that required each one from the one before it, if any.
+    fn selection_tree_path(
+        &self,
+        predicate: ty::PolyTraitPredicate<'tcx>,
+        cause: &ObligationCause<'tcx>,
+    ) -> Vec<(Option<String>, String)> {
+        let mut path = Vec::new();
+        let mut obligation = self.tree_string(predicate);
+        let mut code = cause.code();
+        loop {
+            code = code.peel_match_impls();
+            let via = match code {
+                ObligationCauseCode::ImplDerivedObligation(cause) => {
+                    Some(self.describe_impl(cause.impl_def_id))
+                }
+                _ => None,
+            };
+            // Skip the causes that don't have an obligation of their own.
+            let parent = loop {
+                match code.parent() {
+                    Some((parent_code, parent)) => {
+                        code = parent_code;
+                        if parent.is_some() {
+                            break parent;
+                        }
+                    }
+                    None => break None,
+                }
+            };
+            path.push((via, obligation));
+            match parent {
+                Some(parent) => obligation = self.tree_string(parent),
+                None => break,
+            }
+        }
+        path.reverse();
+        path
+    }
+}
@@ -0,0 +1,36 @@
+// compile-flags:-Zpolymorphize=on -Zprint-mono-items=lazy -Copt-level=0
+
+#![crate_type = "rlib"]
+
+// This test checks that the functions only using a type parameter for its size and alignment are
+// instantiated once for all the types with the same layout.
+
+pub fn size<T>() -> usize {
+    std::mem::size_of::<T>()
+}
+
+//~ MONO_ITEM fn size::<[u32; 1]>
+//~ MONO_ITEM fn size::<[u8; 3]>
+
+pub fn align<T>() -> usize {
+    std::mem::align_of::<T>()
+}
+
+//~ MONO_ITEM fn align::<[u32; 1]>
+
+pub fn dispatch() -> usize {
+    size::<u32>()
+        + size::<f32>()
+        + size::<char>()
+        + size::<[u8; 3]>()
+        + size::<(u8, u8, u8)>()
+        + align::<u32>()
+        + align::<char>()
+}
+
+//~ MONO_ITEM fn dispatch
+
+// These are all the items that aren't relevant to the test.
+//~ MONO_ITEM fn std::mem::size_of::<[u32; 1]>
+//~ MONO_ITEM fn std::mem::size_of::<[u8; 3]>
+//~ MONO_ITEM fn std::mem::align_of::<[u32; 1]>
@@ -1,3 +1,4 @@
+// unit-test: ConstProp
 // EMIT_MIR bad_op_mod_by_zero.main.ConstProp.diff
 #[allow(unconditional_panic)]
 fn main() {
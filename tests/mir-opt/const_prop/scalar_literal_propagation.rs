@@ -1,3 +1,4 @@
+// unit-test: ConstProp
 // EMIT_MIR scalar_literal_propagation.main.ConstProp.diff
 fn main() {
     let x = 1;
@@ -0,0 +1,96 @@
+// unit-test: GVN
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::intrinsics::mir::*;
+
+// EMIT_MIR gvn.dominance.GVN.diff
+#[custom_mir(dialect = "analysis", phase = "post-cleanup")]
+fn dominance(c: bool, x: u64, y: u64) -> u64 {
+    mir!(
+        let a: u64;
+        let b: u64;
+        let d: u64;
+        let e: u64;
+        {
+            a = x + y;
+            match c { false => bb2, _ => bb1 }
+        }
+        bb1 = {
+            b = x * y;
+            RET = b;
+            Goto(bb3)
+        }
+        bb2 = {
+            // `b` is assigned in `bb1`, which does not dominate `bb2`, so `d` is not replaced.
+            d = x * y;
+            RET = d;
+            Goto(bb3)
+        }
+        bb3 = {
+            // `a` is assigned in `bb0`, which dominates `bb3`, so `e` is replaced by `a`.
+            e = x + y;
+            RET = RET + e;
+            Return()
+        }
+    )
+}
+
+// EMIT_MIR gvn.projections.GVN.diff
+#[custom_mir(dialect = "analysis", phase = "post-cleanup")]
+fn projections(s: &[u64; 4], t: (u64, u64)) -> (usize, usize, u64, u64) {
+    mir!(
+        let a: usize;
+        let b: usize;
+        let c: u64;
+        let d: u64;
+        {
+            // `s` is a shared reference to a `Freeze` type, so `*s` always has the same value.
+            a = Len(*s);
+            b = Len(*s);
+            c = t.0;
+            d = t.0;
+            RET = (a, b, c, d);
+            Return()
+        }
+    )
+}
+
+// EMIT_MIR gvn.aggregates.GVN.diff
+#[custom_mir(dialect = "analysis", phase = "post-cleanup")]
+fn aggregates(x: u64, y: u64) -> ((u64, u64), (u64, u64)) {
+    mir!(
+        let a: (u64, u64);
+        let b: (u64, u64);
+        {
+            a = (x, y);
+            b = (x, y);
+            RET = (a, b);
+            Return()
+        }
+    )
+}
+
+// EMIT_MIR gvn.mutable_ref.GVN.diff
+#[custom_mir(dialect = "analysis", phase = "post-cleanup")]
+fn mutable_ref(r: &mut u64) -> u64 {
+    mir!(
+        let a: u64;
+        let b: u64;
+        {
+            a = *r;
+            *r = 5;
+            // `r` is a mutable reference, so the two reads of `*r` are not merged.
+            b = *r;
+            RET = a + b;
+            Return()
+        }
+    )
+}
+
+fn main() {
+    assert_eq!(dominance(true, 2, 3), 11);
+    assert_eq!(projections(&[1, 2, 3, 4], (5, 6)), (4, 4, 5, 5));
+    assert_eq!(aggregates(1, 2), ((1, 2), (1, 2)));
+    assert_eq!(mutable_ref(&mut 1), 6);
+}
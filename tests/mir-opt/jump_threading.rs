@@ -0,0 +1,52 @@
+// unit-test: JumpThreading
+
+use std::ops::ControlFlow;
+
+// EMIT_MIR jump_threading.too_complex.JumpThreading.diff
+fn too_complex(x: Result<i32, usize>) -> Option<i32> {
+    // Each arm of the inner match constructs a known variant of the
+    // `ControlFlow`, so the outer match is threaded along both arms.
+    // The blocks reading the discriminant are duplicated, and the
+    // outer `switchInt` is removed as it becomes unreachable.
+    match {
+        match x {
+            Ok(v) => ControlFlow::Continue(v),
+            Err(r) => ControlFlow::Break(r),
+        }
+    } {
+        ControlFlow::Continue(v) => Some(v),
+        ControlFlow::Break(r) => None,
+    }
+}
+
+enum CustomDiscr {
+    A = 35,
+    B = 73,
+    C = 99,
+}
+
+// EMIT_MIR jump_threading.custom_discr.JumpThreading.diff
+fn custom_discr(x: bool) -> u8 {
+    match if x { CustomDiscr::A } else { CustomDiscr::B } {
+        CustomDiscr::A => 5,
+        _ => 13,
+    }
+}
+
+// EMIT_MIR jump_threading.borrowed.JumpThreading.diff
+fn borrowed(x: bool) -> u8 {
+    let mut a = if x { 1 } else { 2 };
+    let r = &mut a;
+    *r = 3;
+    // `a` is modified through `r`, so its value is not tracked.
+    match a {
+        1 => 5,
+        _ => 13,
+    }
+}
+
+fn main() {
+    too_complex(Ok(0));
+    custom_discr(false);
+    borrowed(true);
+}
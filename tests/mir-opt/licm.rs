@@ -0,0 +1,82 @@
+// unit-test: LoopInvariantCodeMotion
+
+#![feature(custom_mir, core_intrinsics)]
+extern crate core;
+use core::intrinsics::mir::*;
+
+// EMIT_MIR licm.hoisted.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "analysis", phase = "post-cleanup")]
+fn hoisted(n: u64, x: u64, y: u64) -> u64 {
+    mir!(
+        let i: u64;
+        let cond: bool;
+        let a: u64;
+        let b: u64;
+        {
+            i = 0;
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            cond = i < n;
+            match cond { false => bb3, _ => bb2 }
+        }
+        bb2 = {
+            // `a` only depends on the arguments, and `b` on `a`, so both are moved to `bb0`.
+            a = x * y;
+            b = a + 1;
+            RET = RET + b;
+            i = i + 1;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    )
+}
+
+// EMIT_MIR licm.refused.LoopInvariantCodeMotion.diff
+#[custom_mir(dialect = "analysis", phase = "post-cleanup")]
+fn refused(n: u64, x: u64, y: u64) -> u64 {
+    mir!(
+        let i: u64;
+        let cond: bool;
+        let c: u64;
+        let d: u64;
+        let e: (u64, u64);
+        let f: u64;
+        let j: u64;
+        {
+            i = 0;
+            j = 0;
+            RET = 0;
+            Goto(bb1)
+        }
+        bb1 = {
+            // `i` is assigned in each iteration, so `cond` is not invariant.
+            cond = i < n;
+            match cond { false => bb3, _ => bb2 }
+        }
+        bb2 = {
+            // Divisions and shifts may be guarded by a check in the loop.
+            c = x / y;
+            d = x << y;
+            // `e` is not a scalar, and `f` is a projection of it.
+            e = (x, y);
+            f = e.0;
+            // `j` is also assigned before the loop, so it is not SSA.
+            j = x + y;
+            RET = RET + c;
+            i = i + 1;
+            Goto(bb1)
+        }
+        bb3 = {
+            Return()
+        }
+    )
+}
+
+fn main() {
+    assert_eq!(hoisted(3, 2, 3), 21);
+    assert_eq!(refused(2, 6, 1), 12);
+}
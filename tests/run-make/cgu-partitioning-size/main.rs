@@ -0,0 +1,172 @@
+// A crate where most of the code is in a single module, which the default partitioning
+// strategy places into a single codegen unit.
+
+mod big {
+    #[inline(never)]
+    pub fn step0(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..3 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 0);
+            if acc % 7 == 0 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step1(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..4 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 1);
+            if acc % 7 == 1 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step2(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..5 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 2);
+            if acc % 7 == 2 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step3(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..6 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 3);
+            if acc % 7 == 3 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step4(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..7 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 4);
+            if acc % 7 == 4 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step5(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..8 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 5);
+            if acc % 7 == 5 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step6(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..9 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 6);
+            if acc % 7 == 6 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step7(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..10 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 7);
+            if acc % 7 == 0 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step8(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..11 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 8);
+            if acc % 7 == 1 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step9(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..12 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 9);
+            if acc % 7 == 2 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step10(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..13 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 10);
+            if acc % 7 == 3 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+
+    #[inline(never)]
+    pub fn step11(x: u64) -> u64 {
+        let mut acc = x;
+        for i in 0..14 {
+            acc = acc.wrapping_mul(31).wrapping_add(i ^ 11);
+            if acc % 7 == 4 {
+                acc /= 3;
+            }
+        }
+        acc
+    }
+}
+
+mod small {
+    #[inline(never)]
+    pub fn start() -> u64 {
+        42
+    }
+}
+
+fn main() {
+    let mut x = small::start();
+    x = big::step0(x);
+    x = big::step1(x);
+    x = big::step2(x);
+    x = big::step3(x);
+    x = big::step4(x);
+    x = big::step5(x);
+    x = big::step6(x);
+    x = big::step7(x);
+    x = big::step8(x);
+    x = big::step9(x);
+    x = big::step10(x);
+    x = big::step11(x);
+    println!("{x}");
+}
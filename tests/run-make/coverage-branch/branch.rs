@@ -0,0 +1,32 @@
+// Each function is called with the values 0 to 4, see `filecheck.branch.txt` for the counts.
+
+fn if_else(x: u32) -> u32 {
+    if x < 3 { 1 } else { 2 }
+}
+
+fn lazy_boolean(x: u32) -> bool {
+    x == 1 || x == 4
+}
+
+fn condition(x: u32, y: bool) -> u32 {
+    if x > 0 && y { 1 } else { 0 }
+}
+
+fn matching(x: u32) -> u32 {
+    match x {
+        0 => 10,
+        1 | 2 => 20,
+        _ => 30,
+    }
+}
+
+fn main() {
+    let mut total = 0;
+    for x in 0..5 {
+        total += if_else(x);
+        total += lazy_boolean(x) as u32;
+        total += condition(x, x % 2 == 0);
+        total += matching(x);
+    }
+    assert_eq!(total, 121);
+}
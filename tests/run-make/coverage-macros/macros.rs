@@ -0,0 +1,25 @@
+// The code generated by `hit!` and `#[derive(PartialEq)]` is reported at the macro invocation
+// or definition, see `filecheck.macros.txt`.
+
+macro_rules! hit {
+    () => {
+        record_hit()
+    };
+}
+
+fn record_hit() -> u32 {
+    1
+}
+
+#[derive(PartialEq)]
+struct Point {
+    x: u32,
+}
+
+fn main() {
+    let mut total = 0;
+    for _ in 0..3 {
+        total += hit!();
+    }
+    assert!(Point { x: 1 } == Point { x: total - 2 });
+}
@@ -0,0 +1,11 @@
+macro_rules! make_unused {
+    () => {
+        let x = 1;
+    };
+}
+
+fn main() {
+    make_unused!();
+    make_unused!();
+    make_unused!();
+}
@@ -0,0 +1,7 @@
+pub fn bar<T: Default + Clone>() -> Vec<T> {
+    vec![T::default(); 4]
+}
+
+pub fn baz() -> (Vec<u8>, Vec<String>) {
+    (bar::<u8>(), bar::<String>())
+}
@@ -0,0 +1,25 @@
+trait Service {}
+
+struct Handler<T>(T);
+struct Body;
+
+impl<T: Service> Service for Handler<T> {}
+impl Service for Handler<u32> {}
+
+fn require<T: Service>(_: T) {}
+
+fn check() {
+    require(Handler(Handler(Body)));
+}
+
+fn main() {
+    check();
+}
+
+fn with_bound<T>(handler: Handler<T>)
+where
+    T: Service,
+    Handler<T>: Service,
+{
+    require(handler);
+}
@@ -0,0 +1,8 @@
+fn unused_generated() {}
+
+#[warn(non_snake_case)]
+fn BadName() {}
+
+pub fn parens() -> i32 {
+    (1)
+}
@@ -0,0 +1,12 @@
+mod generated;
+
+fn unused_in_main() {}
+
+fn parens() -> i32 {
+    (2)
+}
+
+fn main() {
+    parens();
+    generated::parens();
+}
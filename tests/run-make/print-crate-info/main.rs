@@ -0,0 +1,9 @@
+#![feature(test)]
+#![feature(decl_macro)]
+
+extern crate dep;
+extern crate test;
+
+fn main() {
+    dep::dep();
+}
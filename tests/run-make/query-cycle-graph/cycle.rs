@@ -0,0 +1,17 @@
+#![feature(type_alias_impl_trait)]
+
+use std::fmt::Debug;
+
+type Foo = impl Debug;
+
+fn is_send<T: Send>() {}
+
+fn not_good() {
+    is_send::<Foo>();
+}
+
+fn defining() -> Foo {
+    22_u32
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Describe for u32 {
+    fn describe(&self) -> String {
+        format!("u32 {self}")
+    }
+}
+
+fn describe_all<T: Describe>(items: &[T]) -> Vec<String> {
+    items.iter().map(|item| item.describe()).collect()
+}
+
+fn main() {
+    for line in describe_all(&[1u32, 2, 3]) {
+        println!("{line}");
+    }
+}
@@ -0,0 +1,194 @@
+// run-pass
+// Test the type, trait and instance queries of the stable MIR context, including signatures with
+// late-bound lifetimes, impls whose where clauses don't hold and calls which can't be resolved.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_smir;
+
+use rustc_smir::stable_mir::mir::{Body, Operand, TerminatorKind};
+use rustc_smir::stable_mir::mono::InstanceKind;
+use rustc_smir::stable_mir::ty::{
+    GenericArgKind, GenericParamDefKind, Layout, Mutability, Region, Ty, TyKind, UintTy,
+};
+use rustc_smir::stable_mir::{Context, Item};
+
+const CRATE_NAME: &str = "input";
+
+/// Returns the types of the functions called by the body.
+fn callees(body: &Body) -> Vec<Ty> {
+    body.blocks
+        .iter()
+        .filter_map(|block| match &block.terminator.kind {
+            TerminatorKind::Call { func: Operand::Constant(constant), .. } => {
+                Some(constant.literal.ty.clone())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn find<'a>(items: &'a [Item], path: &str) -> &'a Item {
+    items.iter().find(|item| item.path == path).unwrap()
+}
+
+fn trait_names(cx: &mut dyn Context, ty: &Ty) -> Vec<String> {
+    cx.trait_impls(ty).into_iter().map(|trait_impl| trait_impl.trait_ref.def.path).collect()
+}
+
+fn main() {
+    let file_name = format!("stable_mir_context_queries_{}.rs", std::process::id());
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(
+        &path,
+        r#"
+        pub trait Shape {
+            fn area(&self) -> u32;
+        }
+
+        #[derive(Clone, Copy)]
+        pub struct Square(pub u32);
+
+        impl Shape for Square {
+            fn area(&self) -> u32 {
+                self.0 * self.0
+            }
+        }
+
+        #[derive(Clone, Copy)]
+        pub struct Pair<T>(pub T, pub T);
+
+        pub fn first<'a>(x: &'a u32, _y: &u32) -> &'a u32 {
+            x
+        }
+
+        pub fn pair(s: String) -> Pair<String> {
+            Pair(s.clone(), s)
+        }
+
+        pub fn generic<'a, T: Shape, const N: usize>(shapes: &'a [T; N]) -> u32 {
+            shapes[0].area() * N as u32
+        }
+
+        pub fn dyn_area(shape: &dyn Shape) -> u32 {
+            shape.area()
+        }
+
+        fn main() {
+            let _ = first(&1, &2);
+            let _ = pair(String::new());
+            let _ = generic(&[Square(1), Square(2)]);
+            let _ = dyn_area(&Square(2));
+        }
+        "#,
+    )
+    .unwrap();
+
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.display().to_string(),
+    ];
+    rustc_smir::run(&args, |krate| {
+        let cx = krate.cx;
+        let items = &krate.local_items;
+
+        // The late-bound lifetime is the named parameter of `first`, not a bound region.
+        let sig = cx.fn_sig(find(items, "first").def_id);
+        let TyKind::Ref(region, pointee, Mutability::Not) = sig.inputs[0].kind() else { panic!() };
+        assert_eq!(region, &Region("'a".to_string()));
+        assert_eq!(pointee.kind(), &TyKind::Uint(UintTy::U32));
+        assert_eq!(sig.output, sig.inputs[0]);
+        assert_eq!(sig.abi, "Rust");
+
+        // Late-bound lifetimes aren't generic parameters.
+        let generic = find(items, "generic");
+        let generics = cx.generics_of(generic.def_id);
+        assert_eq!(generics.parent, None);
+        assert_eq!(generics.parent_count, 0);
+        assert!(!generics.has_self);
+        let params: Vec<_> = generics
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), param.index, param.kind))
+            .collect();
+        assert_eq!(
+            params,
+            [
+                ("T", 0, GenericParamDefKind::Type { has_default: false, synthetic: false }),
+                ("N", 1, GenericParamDefKind::Const { has_default: false }),
+            ]
+        );
+
+        let area = find(items, "<Square as Shape>::area");
+        let area_generics = cx.generics_of(area.def_id);
+        assert_eq!(area_generics.parent.unwrap().path, "<Square as Shape>");
+        assert!(area_generics.params.is_empty());
+
+        // The impls of `Square`, with the type itself as the first argument of the trait.
+        let square = cx.fn_sig(area.def_id).inputs[0].clone();
+        let TyKind::Ref(_, square, _) = square.kind().clone() else { panic!() };
+        let impls = cx.trait_impls(&square);
+        let shape_impl = impls.iter().find(|trait_impl| trait_impl.trait_ref.def.path == "Shape");
+        assert_eq!(shape_impl.unwrap().trait_ref.args.0[0], GenericArgKind::Type(square.clone()));
+        let names = trait_names(cx, &square);
+        assert!(names.iter().any(|name| name.ends_with("::Clone")));
+        assert!(names.iter().any(|name| name.ends_with("::Copy")));
+
+        // The `Copy` impl of `Pair<T>` requires `T: Copy`, which doesn't hold for `String`.
+        let pair = cx.fn_sig(find(items, "pair").def_id).output;
+        let names = trait_names(cx, &pair);
+        assert!(names.iter().any(|name| name.ends_with("::Clone")));
+        assert!(!names.iter().any(|name| name.ends_with("::Copy")));
+
+        assert_eq!(cx.layout_of(&square), Some(Layout { size: 4, align: 4 }));
+        let pointer_size = std::mem::size_of::<usize>() as u64;
+        let dyn_ref = &cx.fn_sig(find(items, "dyn_area").def_id).inputs[0];
+        assert_eq!(
+            cx.layout_of(dyn_ref),
+            Some(Layout { size: 2 * pointer_size, align: pointer_size })
+        );
+        // The layout of `[T; N]` depends on the generic parameters.
+        let TyKind::Ref(_, array, _) = cx.fn_sig(generic.def_id).inputs[0].kind().clone() else {
+            panic!()
+        };
+        assert_eq!(cx.layout_of(&array), None);
+
+        // `T::area` can't be resolved without knowing `T`.
+        let generic_callees = callees(&cx.mir_body(generic.def_id));
+        let unresolved = generic_callees.iter().find(|callee| match callee.kind() {
+            TyKind::FnDef(def, _) => def.path.ends_with("::area"),
+            _ => false,
+        });
+        assert_eq!(cx.resolve_instance(unresolved.unwrap()), None);
+
+        // Once `generic` is instantiated, it calls the method of the impl.
+        let main_callees = callees(&cx.mir_body(krate.entry_fn.unwrap().def_id));
+        let instances: Vec<_> =
+            main_callees.iter().filter_map(|callee| cx.resolve_instance(callee)).collect();
+        let generic_instance = instances.iter().find(|instance| instance.def.path == "generic");
+        let generic_instance = generic_instance.unwrap();
+        assert_eq!(generic_instance.kind, InstanceKind::Item);
+        assert_eq!(generic_instance.args.0[0], GenericArgKind::Type(square.clone()));
+        let instance_callees = callees(&cx.instance_body(generic_instance));
+        let area_instance = instance_callees
+            .iter()
+            .filter_map(|callee| cx.resolve_instance(callee))
+            .find(|instance| instance.def.path == "<Square as Shape>::area");
+        assert_eq!(area_instance.unwrap().kind, InstanceKind::Item);
+
+        // A call through a trait object resolves to a virtual call.
+        let dyn_callees = callees(&cx.mir_body(find(items, "dyn_area").def_id));
+        let virtual_instance = cx.resolve_instance(&dyn_callees[0]).unwrap();
+        assert!(matches!(virtual_instance.kind, InstanceKind::Virtual { .. }));
+    })
+    .unwrap();
+
+    std::fs::remove_file(path).unwrap();
+}
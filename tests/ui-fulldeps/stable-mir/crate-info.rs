@@ -0,0 +1,94 @@
+// run-pass
+// Test that the stable MIR interface hands the crate being compiled to the callback of `run`, and
+// that its queries and the serialization of bodies work.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_serialize;
+extern crate rustc_smir;
+
+use rustc_serialize::opaque::{MemDecoder, MemEncoder};
+use rustc_serialize::{Decodable, Encodable};
+use rustc_smir::stable_mir::mir::{Body, Operand, TerminatorKind};
+use rustc_smir::stable_mir::mono::MonoItem;
+use rustc_smir::stable_mir::ty::{Layout, TyKind, UintTy};
+
+const CRATE_NAME: &str = "input";
+
+fn main() {
+    let file_name = format!("stable_mir_crate_info_{}.rs", std::process::id());
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(
+        &path,
+        r#"
+        pub fn add(a: u32, b: u32) -> u32 {
+            a + b
+        }
+
+        pub fn generic<T: Clone>(t: &T) -> T {
+            t.clone()
+        }
+
+        fn main() {
+            let _ = add(1, 2);
+            let _ = generic(&5u8);
+        }
+        "#,
+    )
+    .unwrap();
+
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.display().to_string(),
+    ];
+    rustc_smir::run(&args, |mut krate| {
+        assert_eq!(krate.name, CRATE_NAME);
+        let entry_fn = krate.entry_fn.clone().unwrap();
+        assert_eq!(entry_fn.path, "main");
+
+        let add = krate.local_items.iter().find(|item| item.path == "add").unwrap();
+        let sig = krate.cx.fn_sig(add.def_id);
+        assert_eq!(sig.inputs.len(), 2);
+        assert_eq!(sig.output.kind(), &TyKind::Uint(UintTy::U32));
+        assert_eq!(krate.cx.layout_of(&sig.output), Some(Layout { size: 4, align: 4 }));
+        let impls = krate.cx.trait_impls(&sig.output);
+        assert!(impls.iter().any(|trait_impl| trait_impl.trait_ref.def.path.ends_with("::Clone")));
+
+        let generic = krate.local_items.iter().find(|item| item.path == "generic").unwrap();
+        let generics = krate.cx.generics_of(generic.def_id);
+        assert_eq!(generics.params.len(), 1);
+        assert_eq!(generics.params[0].name, "T");
+
+        let main_body = krate.cx.mir_body(entry_fn.def_id);
+        let callees: Vec<_> = main_body
+            .blocks
+            .iter()
+            .filter_map(|block| match &block.terminator.kind {
+                TerminatorKind::Call { func: Operand::Constant(constant), .. } => {
+                    krate.cx.resolve_instance(&constant.literal.ty)
+                }
+                _ => None,
+            })
+            .collect();
+        let generic_instance =
+            callees.iter().find(|instance| instance.def.path == "generic").unwrap();
+        let generic_body = krate.cx.instance_body(generic_instance);
+        assert_eq!(generic_body.locals[0].ty.kind(), &TyKind::Uint(UintTy::U8));
+        assert!(krate.mono_items.contains(&MonoItem::Fn(generic_instance.clone())));
+
+        let mut encoder = MemEncoder::new();
+        main_body.encode(&mut encoder);
+        let data = encoder.finish();
+        assert_eq!(Body::decode(&mut MemDecoder::new(&data, 0)), main_body);
+    })
+    .unwrap();
+
+    std::fs::remove_file(path).unwrap();
+}
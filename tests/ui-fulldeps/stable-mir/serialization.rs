@@ -0,0 +1,119 @@
+// run-pass
+// Test that the bodies, signatures and generics of the stable MIR round-trip through
+// `rustc_serialize`, for items which use most kinds of statements, rvalues and terminators.
+
+// ignore-cross-compile
+// ignore-stage1
+// ignore-remote
+
+#![feature(rustc_private)]
+
+extern crate rustc_serialize;
+extern crate rustc_smir;
+
+use rustc_serialize::opaque::{MemDecoder, MemEncoder};
+use rustc_serialize::{Decodable, Encodable};
+use rustc_smir::stable_mir::mir::Body;
+use rustc_smir::stable_mir::mono::MonoItem;
+use rustc_smir::stable_mir::ty::{FnSig, Generics};
+use std::fmt::Debug;
+
+const CRATE_NAME: &str = "input";
+
+fn round_trip<T>(value: &T)
+where
+    T: Encodable<MemEncoder> + for<'a> Decodable<MemDecoder<'a>> + PartialEq + Debug,
+{
+    let mut encoder = MemEncoder::new();
+    value.encode(&mut encoder);
+    let data = encoder.finish();
+    let mut decoder = MemDecoder::new(&data, 0);
+    assert_eq!(&T::decode(&mut decoder), value);
+    assert_eq!(decoder.position(), data.len());
+}
+
+fn main() {
+    let file_name = format!("stable_mir_serialization_{}.rs", std::process::id());
+    let path = std::env::temp_dir().join(file_name);
+    std::fs::write(
+        &path,
+        r#"
+        use std::fmt::Debug;
+
+        pub struct Wrapper<T>(Vec<T>);
+
+        pub enum Shape {
+            Circle(f64),
+            Rect { w: u32, h: u32 },
+        }
+
+        pub fn area(shape: &Shape) -> f64 {
+            match *shape {
+                Shape::Circle(r) => 3.14 * r * r,
+                Shape::Rect { w, h } => (w * h) as f64,
+            }
+        }
+
+        pub fn index(values: [i32; 4], i: usize) -> i32 {
+            values[i] / values[0]
+        }
+
+        pub fn sum<'a, T: Copy + Into<u64>>(values: &'a [T], mut f: impl FnMut(u64)) -> u64 {
+            let mut total = 0;
+            for value in values {
+                total += (*value).into();
+                f(total);
+            }
+            total
+        }
+
+        pub fn describe<const N: usize>(items: Wrapper<&dyn Debug>) -> String {
+            let raw = &items as *const Wrapper<&dyn Debug>;
+            format!("{} {:?} {}", N, raw.is_null(), "text")
+        }
+
+        fn main() {
+            let _ = area(&Shape::Rect { w: 2, h: 3 });
+            let _ = index([1, 2, 3, 4], 2);
+            let _ = sum(&[1u8, 2], |_| ());
+            let _ = describe::<3>(Wrapper(vec![&1u8 as &dyn Debug]));
+        }
+        "#,
+    )
+    .unwrap();
+
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.display().to_string(),
+    ];
+    rustc_smir::run(&args, |mut krate| {
+        // Closures are local items too, but `fn_sig` can't be asked about them.
+        let fns: Vec<_> =
+            krate.local_items.iter().filter(|item| !item.path.contains("{closure")).collect();
+        assert_eq!(fns.len(), 5);
+        for item in &krate.local_items {
+            let body: Body = krate.cx.mir_body(item.def_id);
+            round_trip(&body);
+        }
+        for item in fns {
+            let sig: FnSig = krate.cx.fn_sig(item.def_id);
+            round_trip(&sig);
+            let generics: Generics = krate.cx.generics_of(item.def_id);
+            round_trip(&generics);
+        }
+
+        assert!(!krate.mono_items.is_empty());
+        for mono_item in &krate.mono_items {
+            round_trip(mono_item);
+            if let MonoItem::Fn(instance) = mono_item {
+                round_trip(instance);
+            }
+        }
+    })
+    .unwrap();
+
+    std::fs::remove_file(path).unwrap();
+}
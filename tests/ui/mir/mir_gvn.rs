@@ -0,0 +1,63 @@
+// run-pass
+// compile-flags: -Zmir-opt-level=2 -Zmir-enable-passes=+GVN -Zvalidate-mir
+// Checks that global value numbering does not change the behavior of redundant computations
+// spread across basic blocks.
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Shape {
+    Circle(u32),
+    Rect(u32, u32),
+}
+
+fn repeated_len(s: &[u32], cond: bool) -> usize {
+    let a = s.len();
+    let b = if cond { s.len() + 1 } else { s.len() };
+    a + b
+}
+
+fn repeated_discriminant(shape: Shape) -> u32 {
+    let first = match shape {
+        Shape::Circle(r) => r,
+        Shape::Rect(w, _) => w,
+    };
+    let second = match shape {
+        Shape::Circle(r) => r * 2,
+        Shape::Rect(w, h) => w * h,
+    };
+    first + second
+}
+
+fn repeated_fields(pair: (u32, (u32, u32)), cond: bool) -> u32 {
+    let x = (pair.1).0;
+    if cond { x + (pair.1).0 + (pair.1).1 } else { (pair.1).0 }
+}
+
+fn identical_aggregates(x: u32, cond: bool) -> (Shape, Shape) {
+    let a = Shape::Rect(x, x + 1);
+    let b = if cond { Shape::Rect(x, x + 1) } else { Shape::Circle(x) };
+    (a, b)
+}
+
+fn borrows_are_not_merged(cond: bool) -> u32 {
+    let mut x = 1;
+    let a = &mut x;
+    *a += 1;
+    let b = &mut x;
+    if cond {
+        *b += 1;
+    }
+    x
+}
+
+fn main() {
+    assert_eq!(repeated_len(&[1, 2, 3], true), 7);
+    assert_eq!(repeated_len(&[1, 2, 3], false), 6);
+    assert_eq!(repeated_discriminant(Shape::Circle(3)), 9);
+    assert_eq!(repeated_discriminant(Shape::Rect(2, 5)), 12);
+    assert_eq!(repeated_fields((0, (4, 5)), true), 13);
+    assert_eq!(repeated_fields((0, (4, 5)), false), 4);
+    assert_eq!(identical_aggregates(2, true), (Shape::Rect(2, 3), Shape::Rect(2, 3)));
+    assert_eq!(identical_aggregates(2, false), (Shape::Rect(2, 3), Shape::Circle(2)));
+    assert_eq!(borrows_are_not_merged(true), 3);
+    assert_eq!(borrows_are_not_merged(false), 2);
+}
@@ -0,0 +1,84 @@
+// run-pass
+// compile-flags: -Zmir-opt-level=2 -Zmir-enable-passes=+JumpThreading -Zvalidate-mir
+// Checks that jump threading preserves the behavior of switches whose discriminant is known
+// along some of the paths leading to them.
+
+#[derive(Debug, PartialEq)]
+enum Signed {
+    Low = -2,
+    Mid = 0,
+    High = 3,
+}
+
+fn parse(s: &str) -> Result<u32, String> {
+    s.parse::<u32>().map_err(|e| e.to_string())
+}
+
+fn sum(a: &str, b: &str) -> Result<u32, String> {
+    let a = parse(a)?;
+    let b = parse(b)?;
+    Ok(a + b)
+}
+
+fn fresh_option(x: u32) -> u32 {
+    let opt = if x > 10 { Some(x) } else { None };
+    match opt {
+        Some(v) => v * 2,
+        None => 0,
+    }
+}
+
+fn same_switch_twice(x: u8) -> u32 {
+    let first = match x {
+        0 => 10,
+        1 => 20,
+        _ => 30,
+    };
+    let second = match x {
+        0 => 1,
+        1 => 2,
+        _ => 3,
+    };
+    first + second
+}
+
+fn through_blocks(cond: bool, y: u32) -> u32 {
+    let mut flag = false;
+    if cond {
+        flag = true;
+    }
+    let z = y + 1;
+    if flag { z } else { z * 2 }
+}
+
+fn signed(x: i32) -> i32 {
+    let s = if x < 0 {
+        Signed::Low
+    } else if x == 0 {
+        Signed::Mid
+    } else {
+        Signed::High
+    };
+    match s {
+        Signed::Low => -1,
+        Signed::Mid => 0,
+        Signed::High => 1,
+    }
+}
+
+fn main() {
+    assert_eq!(sum("1", "2"), Ok(3));
+    assert!(sum("x", "2").is_err());
+    assert!(sum("1", "y").is_err());
+    assert_eq!(fresh_option(11), 22);
+    assert_eq!(fresh_option(3), 0);
+    assert_eq!(same_switch_twice(0), 11);
+    assert_eq!(same_switch_twice(1), 22);
+    assert_eq!(same_switch_twice(7), 33);
+    assert_eq!(through_blocks(true, 4), 5);
+    assert_eq!(through_blocks(false, 4), 10);
+    assert_eq!(signed(-5), -1);
+    assert_eq!(signed(0), 0);
+    assert_eq!(signed(9), 1);
+    assert_eq!(Signed::Low as i32, -2);
+}
@@ -0,0 +1,69 @@
+// run-pass
+// compile-flags: -Zmir-opt-level=2 -Zmir-enable-passes=+LoopInvariantCodeMotion -Zvalidate-mir
+// Checks that moving loop-invariant computations out of loops preserves the behavior of the
+// loops, including the ones which aren't entered, the nested ones and the ones with assertions.
+
+#[inline(never)]
+fn sum_scaled(xs: &[u32], a: u32, b: u32) -> u32 {
+    let mut sum = 0u32;
+    for &x in xs {
+        let scale = a.wrapping_mul(b) ^ 7;
+        sum = sum.wrapping_add(x.wrapping_mul(scale));
+    }
+    sum
+}
+
+#[inline(never)]
+fn nested(n: usize, m: usize, k: i64) -> i64 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < n {
+        let outer = k * 3 + 1;
+        let mut j = 0;
+        while j < m {
+            let inner = (outer as f64 * 0.5) as i64;
+            total += inner + (i * j) as i64;
+            j += 1;
+        }
+        i += 1;
+    }
+    total
+}
+
+#[inline(never)]
+fn guarded_division(xs: &[u32], d: u32) -> u32 {
+    let mut sum = 0;
+    for &x in xs {
+        if d != 0 {
+            sum += x / d;
+        }
+    }
+    sum
+}
+
+#[inline(never)]
+fn early_exit(xs: &[i32], limit: i32) -> Option<usize> {
+    let mut index = 0;
+    loop {
+        if index >= xs.len() {
+            return None;
+        }
+        let bound = limit.wrapping_neg();
+        if xs[index] < bound {
+            return Some(index);
+        }
+        index += 1;
+    }
+}
+
+fn main() {
+    assert_eq!(sum_scaled(&[], 3, 5), 0);
+    assert_eq!(sum_scaled(&[1, 2, 3], 3, 5), 6 * (15 ^ 7));
+    assert_eq!(nested(0, 4, 2), 0);
+    assert_eq!(nested(3, 0, 2), 0);
+    assert_eq!(nested(2, 3, 2), 6 * 3 + 3);
+    assert_eq!(guarded_division(&[10, 20], 0), 0);
+    assert_eq!(guarded_division(&[10, 20], 5), 6);
+    assert_eq!(early_exit(&[1, -5, 3], 2), Some(1));
+    assert_eq!(early_exit(&[1, 2, 3], 2), None);
+}
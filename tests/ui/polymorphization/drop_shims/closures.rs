@@ -0,0 +1,25 @@
+// run-pass
+// compile-flags:-Zpolymorphize=on -Copt-level=0
+
+// This test checks that the drop glue and clone shims of closures which don't use the generic
+// parameters of their parent, shared by the instances of the parent, drop and clone the captured
+// values.
+
+use std::rc::Rc;
+
+fn make<T>(counter: Rc<()>) -> impl Fn() -> usize + Clone {
+    move || Rc::strong_count(&counter)
+}
+
+fn main() {
+    let counter = Rc::new(());
+    {
+        let a = make::<u8>(counter.clone());
+        let b = make::<String>(counter.clone());
+        let c = a.clone();
+        assert_eq!(a(), 4);
+        assert_eq!(b(), 4);
+        assert_eq!(c(), 4);
+    }
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
@@ -0,0 +1,37 @@
+// run-pass
+// compile-flags:-Zpolymorphize=on -Copt-level=0
+
+// This test checks that the functions only using a type parameter for its size and alignment
+// return the right values when instantiated with types sharing the same layout, or without an
+// integer type of the same alignment.
+
+fn size<T>() -> usize {
+    std::mem::size_of::<T>()
+}
+
+fn align<T>() -> usize {
+    std::mem::align_of::<T>()
+}
+
+fn both<T>() -> (usize, usize) {
+    (size::<T>(), align::<T>())
+}
+
+#[repr(align(8))]
+struct Aligned8(u8);
+
+#[repr(align(64))]
+struct Aligned64(u8);
+
+fn main() {
+    assert_eq!(size::<u32>(), 4);
+    assert_eq!(size::<char>(), 4);
+    assert_eq!(size::<(u8, u8, u8)>(), 3);
+    assert_eq!(size::<[u16; 5]>(), 10);
+    assert_eq!(size::<()>(), 0);
+    assert_eq!(align::<u8>(), 1);
+    assert_eq!(align::<[u16; 5]>(), 2);
+    assert_eq!(both::<Aligned8>(), (8, 8));
+    assert_eq!(both::<Aligned64>(), (64, 64));
+    assert_eq!(both::<(u8, u32)>(), (8, 4));
+}